@@ -40,9 +40,30 @@ struct Args {
 
     #[arg(long)]
     filter_selector: Option<String>,
+
+    /// Print every step of the analysis pass matching the mode
+    #[arg(long)]
+    trace: bool,
+}
+
+struct PrintTrace(Option<evmole::AnalysisPhase>);
+
+impl evmole::TraceSink for PrintTrace {
+    fn enabled(&self, phase: evmole::AnalysisPhase) -> bool {
+        self.0 == Some(phase)
+    }
+
+    fn record(&self, step: evmole::TraceStep) {
+        println!("{step:#?}\n");
+    }
 }
 
-fn timeit(args: evmole::ContractInfoArgs) -> (evmole::Contract, u64) {
+fn timeit(args: evmole::ContractInfoArgs, trace: &PrintTrace) -> (evmole::Contract, u64) {
+    let args = if trace.0.is_some() {
+        args.with_trace(trace)
+    } else {
+        args
+    };
     let now = Instant::now();
     let result = evmole::contract_info(args);
     let duration_us = now.elapsed().as_micros() as u64;
@@ -52,6 +73,15 @@ fn timeit(args: evmole::ContractInfoArgs) -> (evmole::Contract, u64) {
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cfg = Args::parse();
 
+    let trace = PrintTrace(match cfg.mode {
+        _ if !cfg.trace => None,
+        Mode::Selectors => Some(evmole::AnalysisPhase::Selectors),
+        Mode::Arguments => Some(evmole::AnalysisPhase::Arguments),
+        Mode::Mutability => Some(evmole::AnalysisPhase::StateMutability),
+        Mode::Storage => Some(evmole::AnalysisPhase::Storage),
+        Mode::Blocks | Mode::Flow => None,
+    });
+
     type Meta = u64; // duration in ms
 
     let selectors: HashMap<String, (Meta, Vec<String>)> = match cfg.mode {
@@ -98,7 +128,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         match cfg.mode {
             Mode::Selectors => {
-                let (info, dur) = timeit(evmole::ContractInfoArgs::new(&code).with_selectors());
+                let (info, dur) = timeit(
+                    evmole::ContractInfoArgs::new(&code).with_selectors(),
+                    &trace,
+                );
                 ret_selectors.insert(
                     fname,
                     (
@@ -119,7 +152,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     &selectors[&fname].1
                 };
 
-                let (info, dur) = timeit(evmole::ContractInfoArgs::new(&code).with_arguments());
+                let (info, dur) = timeit(
+                    evmole::ContractInfoArgs::new(&code).with_arguments(),
+                    &trace,
+                );
                 let args: HashMap<String, String> = info
                     .functions
                     .unwrap()
@@ -159,8 +195,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     &selectors[&fname].1
                 };
 
-                let (info, dur) =
-                    timeit(evmole::ContractInfoArgs::new(&code).with_state_mutability());
+                let (info, dur) = timeit(
+                    evmole::ContractInfoArgs::new(&code).with_state_mutability(),
+                    &trace,
+                );
                 let smut: HashMap<String, String> = info
                     .functions
                     .unwrap()
@@ -190,7 +228,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             Mode::Storage => {
-                let (info, dur) = timeit(evmole::ContractInfoArgs::new(&code).with_storage());
+                let (info, dur) =
+                    timeit(evmole::ContractInfoArgs::new(&code).with_storage(), &trace);
                 ret_other.insert(
                     fname,
                     (
@@ -207,7 +246,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             Mode::Blocks => {
-                let (info, dur) = timeit(evmole::ContractInfoArgs::new(&code).with_basic_blocks());
+                let (info, dur) = timeit(
+                    evmole::ContractInfoArgs::new(&code).with_basic_blocks(),
+                    &trace,
+                );
                 ret_flow.insert(
                     fname,
                     (dur, info.basic_blocks.unwrap().into_iter().collect()),
@@ -215,8 +257,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             }
 
             Mode::Flow => {
-                let (info, dur) =
-                    timeit(evmole::ContractInfoArgs::new(&code).with_control_flow_graph());
+                let (info, dur) = timeit(
+                    evmole::ContractInfoArgs::new(&code).with_control_flow_graph(),
+                    &trace,
+                );
                 let cfg = info.control_flow_graph.unwrap();
 
                 // node id to block's bytecode start
@@ -8,18 +8,18 @@ BDIR=`pwd`/benchmark
 ln -s `pwd` ${BDIR}/providers/evmole-rs/rust 2>/dev/null || true
 
 case ${NOTRACE+x} in
-    x) FEAT='' ;;
-    *) FEAT="--features evmole/trace_${MODE}" ;;
+    x) TRACE='' ;;
+    *) TRACE='--trace' ;;
 esac
 
 cargo run \
     --manifest-path benchmark/providers/evmole-rs/Cargo.toml \
-    ${FEAT} \
     ${MODE} \
     ${BDIR}/datasets/${2} \
     out.json \
     ${BDIR}/results/reference.selectors_${2}.json \
     --filter-filename ${3} \
-    --filter-selector ${4}
+    --filter-selector ${4} \
+    ${TRACE}
 
 rm -rf ${BDIR}/providers/evmole-rs/rust
@@ -1,5 +1,45 @@
 from typing import List, Literal, Optional, Tuple, Union
 
+class Evidence:
+    """
+    Represents an instruction that supports an inferred result.
+
+    Attributes:
+        pc (int): Bytecode offset of the instruction.
+        opcode (str): Opcode name (e.g., 'CALLDATALOAD').
+        reason (str): Short description of what the instruction shows.
+    """
+
+    pc: int
+    opcode: str
+    reason: str
+
+class FunctionEvidence:
+    """
+    Instructions supporting the inferred arguments and state mutability of a function.
+
+    Attributes:
+        arguments (Optional[List[List[Evidence]]]): Evidence for each argument, in argument order.
+            None if arguments were not extracted
+        state_mutability (Optional[List[Evidence]]): Evidence for the state mutability.
+            None if state mutability was not extracted
+    """
+
+    arguments: Optional[List[List[Evidence]]]
+    state_mutability: Optional[List[Evidence]]
+
+class ArgumentConfidence:
+    """
+    Confidence of an inferred function argument type.
+
+    Attributes:
+        confidence (int): Confidence in the inferred type, from 0 to 100.
+        alternative (Optional[str]): Second most likely type (e.g., 'bool' for an inferred 'uint8'), if any.
+    """
+
+    confidence: int
+    alternative: Optional[str]
+
 class Function:
     """
     Represents a public smart contract function.
@@ -7,16 +47,82 @@ class Function:
     Attributes:
         selector (str): Function selector as a 4-byte hex string without '0x' prefix (e.g., 'aabbccdd').
         bytecode_offset (int): Starting byte offset within the EVM bytecode for the function body.
+        dispatch (str): 'abi' when the selector appears in the normal external-function dispatcher,
+            or 'fallback' when it appears in fallback dispatch logic.
         arguments (Optional[str]): Function argument types in canonical format (e.g., 'uint256,address[]').
             None if arguments were not extracted
+        argument_confidence (Optional[List[ArgumentConfidence]]): Confidence of each argument type.
+            None if arguments were not extracted
+        arguments_validation (Optional[str]): Result of replaying the function with ABI-encoded
+            arguments ('validated', 'contradicted', or 'inconclusive').
+            None if validation was not requested
+        outputs (Optional[str]): Function return types in canonical format (e.g., 'uint256,bool').
+            None if outputs were not extracted
         state_mutability (Optional[str]): Function's state mutability ('pure', 'view', 'payable', or 'nonpayable').
             None if state mutability was not extracted
+        events (Optional[List[str]]): Topics of the events emitted by the function, as hex strings.
+            None if events were not extracted
+        evidence (Optional[FunctionEvidence]): Instructions supporting the inferred results.
+            None if evidence was not requested
     """
 
     selector: str
     bytecode_offset: int
+    dispatch: str
     arguments: Optional[str]
+    argument_confidence: Optional[List[ArgumentConfidence]]
+    arguments_validation: Optional[str]
+    outputs: Optional[str]
     state_mutability: Optional[str]
+    events: Optional[List[str]]
+    evidence: Optional[FunctionEvidence]
+
+class SpecialFunctions:
+    """
+    Represents the fallback and receive functions of a contract.
+
+    Attributes:
+        fallback (Optional[str]): State mutability of the fallback function ('payable' or 'nonpayable').
+            None if calls matching no selector revert
+        receive (bool): True if the contract has a receive function.
+    """
+
+    fallback: Optional[str]
+    receive: bool
+
+class Event:
+    """
+    Represents an event emitted by the contract.
+
+    Attributes:
+        topic (str): Event signature hash (topic0) as a hex string without '0x' prefix.
+        indexed (int): Number of indexed parameters.
+        data (str): Non-indexed parameter types in canonical format (e.g., 'uint256,address').
+    """
+
+    topic: str
+    indexed: int
+    data: str
+
+class RevertError:
+    """
+    Represents an error the contract can revert with.
+
+    Attributes:
+        kind (str): Error kind ('custom', 'panic', or 'reason').
+        selector (str): Error selector as a 4-byte hex string without '0x' prefix.
+        arguments (str): Error argument types in canonical format (e.g., 'uint256').
+        panic_code (Optional[int]): Panic code for Panic(uint256) errors, None otherwise.
+        reason (Optional[str]): Constant reason string for Error(string) errors, None otherwise.
+        functions (List[str]): Selectors of the functions that can revert with this error.
+    """
+
+    kind: str
+    selector: str
+    arguments: str
+    panic_code: Optional[int]
+    reason: Optional[str]
+    functions: List[str]
 
 class StorageRecord:
     """
@@ -29,6 +135,8 @@ class StorageRecord:
         type (str): Variable type (e.g., 'uint256', 'mapping(address => uint256)', 'bytes32').
         reads (List[str]): List of function selectors that read from this storage location.
         writes (List[str]): List of function selectors that write to this storage location.
+        evidence (Optional[List[Evidence]]): Instructions supporting the inferred type.
+            None if evidence was not requested
     """
 
     slot: str
@@ -36,6 +144,7 @@ class StorageRecord:
     type: str
     reads: List[str]
     writes: List[str]
+    evidence: Optional[List[Evidence]]
 
 class DynamicJump:
     """
@@ -106,10 +215,16 @@ class Contract:
     Attributes:
         functions (Optional[List[Function]]): List of detected contract functions.
             None if no functions were extracted
+        special_functions (Optional[SpecialFunctions]): Fallback and receive functions.
+            None if special functions were not extracted
         storage (Optional[List[StorageRecord]]): List of persistent contract storage records.
             None if storage layout was not extracted
         transient_storage (Optional[List[StorageRecord]]): List of transient contract storage records.
             None if storage layout was not extracted
+        events (Optional[List[Event]]): List of events emitted by the contract.
+            None if events were not extracted
+        errors (Optional[List[RevertError]]): List of errors the contract can revert with.
+            None if errors were not extracted
         disassembled (Optional[List[Tuple[int, str]]]): List of bytecode instructions, where each element is [offset, instruction].
             None if disassembly was not requested
         basic_blocks (Optional[List[Tuple[int, int]]]): List of basic block ranges as (first_op, last_op) offsets.
@@ -118,15 +233,21 @@ class Contract:
             None if control flow analysis was not requested
         metadata (Optional[CborMetadata]): Terminal CBOR metadata.
             None if extraction was not requested or no valid trailer exists
+        trace (Optional[str]): JSON array of the recorded analysis steps, each with phase,
+            selector, pc, opcode, stack, memory and state. None if tracing was not requested
     """
 
     functions: Optional[List[Function]]
+    special_functions: Optional[SpecialFunctions]
     storage: Optional[List[StorageRecord]]
     transient_storage: Optional[List[StorageRecord]]
+    events: Optional[List[Event]]
+    errors: Optional[List[RevertError]]
     disassembled: Optional[List[Tuple[int, str]]]
     basic_blocks: Optional[List[Tuple[int, int]]]
     control_flow_graph: Optional[ControlFlowGraph]
     metadata: Optional[CborMetadata]
+    trace: Optional[str]
 
 class CborValue:
     """
@@ -170,13 +291,20 @@ def contract_info(
     code: Union[bytes, str],
     *,
     selectors: bool = False,
+    special_functions: bool = False,
     arguments: bool = False,
+    arguments_validation: bool = False,
+    outputs: bool = False,
     state_mutability: bool = False,
     storage: bool = False,
+    events: bool = False,
+    errors: bool = False,
     disassemble: bool = False,
     basic_blocks: bool = False,
     control_flow_graph: bool = False,
     metadata: bool = False,
+    evidence: bool = False,
+    trace: Optional[List[str]] = None,
 ) -> Contract:
     """
     Extracts information about a smart contract from its EVM bytecode.
@@ -185,11 +313,19 @@ def contract_info(
         code (Union[bytes, str]): Runtime bytecode as a hex string (with or without '0x' prefix)
             or raw bytes.
         selectors (bool, optional): When True, extracts function selectors. Defaults to False.
-        arguments (bool, optional): When True, extracts function arguments. Defaults to False.
+        special_functions (bool, optional): When True, detects fallback and receive functions.
+            Defaults to False.
+        arguments (bool, optional): When True, extracts function arguments and their confidence.
+            Defaults to False.
+        arguments_validation (bool, optional): When True, validates the extracted arguments by
+            replaying each function with ABI-encoded calldata. Defaults to False.
+        outputs (bool, optional): When True, extracts function return types. Defaults to False.
         state_mutability (bool, optional): When True, extracts function state mutability.
             Defaults to False.
         storage (bool, optional): When True, extracts persistent and transient storage layouts.
             Defaults to False.
+        events (bool, optional): When True, extracts emitted events. Defaults to False.
+        errors (bool, optional): When True, extracts revert errors. Defaults to False.
         disassemble (bool, optional): When True, includes disassembled bytecode.
             Defaults to False.
         basic_blocks (bool, optional): When True, extracts basic block ranges.
@@ -198,6 +334,10 @@ def contract_info(
             Defaults to False.
         metadata (bool, optional): When True, extracts terminal CBOR metadata.
             Defaults to False.
+        evidence (bool, optional): When True, attaches the instructions supporting inferred
+            arguments, state mutability and storage types. Defaults to False.
+        trace (Optional[List[str]], optional): Analysis phases, e.g. ['arguments'], whose
+            abstract VM steps are recorded into Contract.trace. Defaults to None.
 
     Returns:
         Contract: Object containing the requested smart contract information. Fields that
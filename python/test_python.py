@@ -46,3 +46,29 @@ assert info.metadata.entries[0].key == 'solc'
 assert isinstance(info.metadata.entries[0].value, CborValue)
 assert info.metadata.entries[0].value.type == 'bytes'
 assert info.metadata.entries[0].value.value == bytes.fromhex('00081a')
+
+print(f'Success #3, {info}')
+
+from evmole import ArgumentConfidence, FunctionEvidence, Evidence, SpecialFunctions, RevertError
+info = contract_info(code, special_functions=True, arguments=True, arguments_validation=True, outputs=True, state_mutability=True, events=True, errors=True, evidence=True)
+f = info.functions[0]
+assert isinstance(f.argument_confidence[0], ArgumentConfidence)
+assert f.argument_confidence[0].confidence == 100
+assert f.arguments_validation == 'validated'
+assert f.outputs == 'uint32'
+assert f.events == []
+assert isinstance(f.evidence, FunctionEvidence)
+assert isinstance(f.evidence.arguments[0][0], Evidence)
+assert f.evidence.arguments[0][0].opcode == 'EQ'
+assert f.evidence.state_mutability[0].reason == 'callvalue check'
+
+assert isinstance(info.special_functions, SpecialFunctions)
+assert info.special_functions.fallback is None
+assert info.special_functions.receive is False
+assert info.events == []
+assert isinstance(info.errors[0], RevertError)
+assert info.errors[0].kind == 'panic'
+assert info.errors[0].panic_code == 0x11
+assert info.errors[0].functions == ['fae7ab82']
+
+print(f'Success #4, {info}')
@@ -0,0 +1,331 @@
+//! Conversion of analysis results to the Solidity JSON ABI, and of ABIs to Solidity and
+//! Vyper interface source code.
+//!
+//! Bytecode holds no names, so functions, events and errors get placeholders derived from
+//! their selectors: `sig_<selector>`, `event_<first 4 bytes of topic>` and `error_<selector>`,
+//! unless a function signature was resolved, see [`crate::SignatureResolver`]. Parameters
+//! are unnamed.
+
+use crate::{
+    Contract, DynSolType, JsonAbi, RevertKind, StateMutability, signatures::signature_types,
+};
+use alloy_json_abi::{Error, Event, EventParam, Fallback, Function, Param, Receive};
+use alloy_primitives::hex;
+
+mod solidity;
+mod vyper;
+pub use solidity::solidity_interface;
+pub use vyper::vyper_interface;
+
+/// Returns the ABI type string of `tp` and the components of its tuple, if any
+fn type_and_components(tp: &DynSolType) -> (String, Vec<Param>) {
+    let mut suffix = String::new();
+    let mut inner = tp;
+    loop {
+        match inner {
+            DynSolType::Array(t) => {
+                suffix.insert_str(0, "[]");
+                inner = t;
+            }
+            DynSolType::FixedArray(t, n) => {
+                suffix.insert_str(0, &format!("[{n}]"));
+                inner = t;
+            }
+            _ => break,
+        }
+    }
+    match inner {
+        DynSolType::Tuple(types) => (format!("tuple{suffix}"), types.iter().map(param).collect()),
+        _ => (tp.sol_type_name().into_owned(), Vec::new()),
+    }
+}
+
+/// Splits an ABI type into its element type and array dimensions, innermost first;
+/// `None` stands for a dynamic array
+fn split_array(ty: &str) -> (&str, Vec<Option<usize>>) {
+    let (base, dims) = ty.split_at(ty.find('[').unwrap_or(ty.len()));
+    let dims = dims
+        .split_terminator(']')
+        .map(|d| d.trim_start_matches('[').parse().ok())
+        .collect();
+    (base, dims)
+}
+
+/// Tuple types of an ABI, declared as structs named `Tuple<N>` in order of first
+/// appearance, nested tuples first
+#[derive(Default)]
+struct Structs {
+    /// Component list of every struct, keyed by the tuple type it stands for
+    structs: Vec<(String, Vec<Param>)>,
+}
+
+impl Structs {
+    fn collect(abi: &JsonAbi) -> Self {
+        let mut structs = Self::default();
+        let params = abi.errors().flat_map(|e| &e.inputs).chain(
+            abi.functions()
+                .flat_map(|f| f.inputs.iter().chain(&f.outputs)),
+        );
+        for p in params {
+            structs.visit(&p.ty, &p.components);
+        }
+        for p in abi.events().flat_map(|e| &e.inputs) {
+            structs.visit(&p.ty, &p.components);
+        }
+        structs
+    }
+
+    fn visit(&mut self, ty: &str, components: &[Param]) {
+        if split_array(ty).0 != "tuple" {
+            return;
+        }
+        for c in components {
+            self.visit(&c.ty, &c.components);
+        }
+        let key = Self::key(components);
+        if !self.structs.iter().any(|(k, _)| *k == key) {
+            self.structs.push((key, components.to_vec()));
+        }
+    }
+
+    fn key(components: &[Param]) -> String {
+        let types: Vec<_> = components.iter().map(|c| c.selector_type()).collect();
+        format!("({})", types.join(","))
+    }
+
+    /// Returns the struct name of the tuple with the given components
+    fn name(&self, components: &[Param]) -> String {
+        let key = Self::key(components);
+        let idx = self
+            .structs
+            .iter()
+            .position(|(k, _)| *k == key)
+            .expect("collected");
+        format!("Tuple{idx}")
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (String, &[Param])> {
+        self.structs
+            .iter()
+            .enumerate()
+            .map(|(i, (_, components))| (format!("Tuple{i}"), components.as_slice()))
+    }
+}
+
+fn param(tp: &DynSolType) -> Param {
+    let (ty, components) = type_and_components(tp);
+    Param {
+        ty,
+        name: String::new(),
+        components,
+        internal_type: None,
+    }
+}
+
+fn event_param(tp: &DynSolType, indexed: bool) -> EventParam {
+    let (ty, components) = type_and_components(tp);
+    EventParam {
+        ty,
+        name: String::new(),
+        indexed,
+        components,
+        internal_type: None,
+    }
+}
+
+impl Contract {
+    /// Builds the Solidity JSON ABI of the analyzed contract
+    ///
+    /// Includes what was extracted: functions with their resolved signatures or inferred
+    /// arguments, return types and state mutability (`nonpayable` when not analyzed),
+    /// fallback and receive functions, events and custom errors. A resolved signature is
+    /// only used when its argument types equal the inferred ones. Indexed event arguments
+    /// are reported as `bytes32`.
+    ///
+    /// Event and error names can be replaced in the returned ABI once known, before
+    /// generating interface source code with [`solidity_interface`] or [`vyper_interface`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use evmole::{ContractInfoArgs, contract_info};
+    /// use alloy_primitives::hex;
+    ///
+    /// let code = hex::decode("6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256").unwrap();
+    /// let info = contract_info(ContractInfoArgs::new(&code).with_arguments());
+    ///
+    /// let abi = info.abi();
+    /// let f = &abi.functions["sig_2125b65b"][0];
+    /// assert_eq!(f.signature(), "sig_2125b65b(uint32,address,uint224)");
+    /// ```
+    pub fn abi(&self) -> JsonAbi {
+        let mut abi = JsonAbi::new();
+
+        for f in self.functions.iter().flatten() {
+            // a resolved signature contradicting the inferred arguments is a selector collision
+            let resolved = f
+                .signatures
+                .iter()
+                .flatten()
+                .filter(|s| {
+                    f.arguments
+                        .as_ref()
+                        .is_none_or(|args| signature_types(s).as_ref() == Some(args))
+                })
+                .find_map(|s| Function::parse(s).ok());
+            let (name, inputs) = match resolved {
+                Some(resolved) => (resolved.name, resolved.inputs),
+                None => (
+                    format!("sig_{}", hex::encode(f.selector)),
+                    f.arguments.iter().flatten().map(param).collect(),
+                ),
+            };
+            let function = Function {
+                name: name.clone(),
+                inputs,
+                outputs: f.outputs.iter().flatten().map(param).collect(),
+                state_mutability: f.state_mutability.unwrap_or(StateMutability::NonPayable),
+            };
+            abi.functions.entry(name).or_default().push(function);
+        }
+
+        if let Some(special) = &self.special_functions {
+            abi.fallback = special
+                .fallback
+                .map(|state_mutability| Fallback { state_mutability });
+            abi.receive = special.receive.then_some(Receive {
+                state_mutability: StateMutability::Payable,
+            });
+        }
+
+        for e in self.events.iter().flatten() {
+            let name = format!("event_{}", hex::encode(&e.topic[..4]));
+            let indexed = std::iter::repeat_n(
+                event_param(&DynSolType::FixedBytes(32), true),
+                e.indexed as usize,
+            );
+            let data = e.data.iter().map(|tp| event_param(tp, false));
+            let event = Event {
+                name: name.clone(),
+                inputs: indexed.chain(data).collect(),
+                anonymous: false,
+            };
+            abi.events.entry(name).or_default().push(event);
+        }
+
+        for e in self.errors.iter().flatten() {
+            if e.kind != RevertKind::Custom {
+                continue;
+            }
+            let name = format!("error_{}", hex::encode(e.selector));
+            let error = Error {
+                name: name.clone(),
+                inputs: e.arguments.iter().map(param).collect(),
+            };
+            abi.errors.entry(name).or_default().push(error);
+        }
+
+        abi
+    }
+}
+
+/// Serializes a [`Contract`] as a Solidity JSON ABI array, see [`Contract::abi`]
+///
+/// Usable with `#[serde(serialize_with = "evmole::abi::serialize")]`.
+#[cfg(feature = "serde")]
+pub fn serialize<S: serde::Serializer>(
+    contract: &Contract,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serde::Serialize::serialize(&contract.abi(), serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ContractInfoArgs, contract_info};
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_events_errors_and_special_functions() {
+        let transfer = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+        let mask = "ff".repeat(20);
+        // 0x11223344(address) emits `transfer` with msg.sender indexed and the argument
+        // as data, then reverts with Unauthorized(address) (0x8e4a23d6); the fallback is
+        // payable
+        let code = hex::decode(format!(
+            "60003560e01c63112233441460105700\
+             5b60043573{mask}16805f52337f{transfer}60205fa2\
+             638e4a23d660e01b5f5260045260245ffd"
+        ))
+        .unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_special_functions()
+                .with_events()
+                .with_errors(),
+        );
+        let abi = info.abi();
+
+        let f = &abi.functions["sig_11223344"][0];
+        assert_eq!(f.signature(), "sig_11223344(address)");
+        assert_eq!(f.state_mutability, crate::StateMutability::NonPayable);
+
+        assert_eq!(
+            abi.fallback.map(|f| f.state_mutability),
+            Some(crate::StateMutability::Payable)
+        );
+        assert!(abi.receive.is_none());
+
+        let e = &abi.events["event_ddf252ad"][0];
+        assert_eq!(e.signature(), "event_ddf252ad(bytes32,address)");
+        assert!(e.inputs[0].indexed);
+        assert!(!e.inputs[1].indexed);
+
+        let e = &abi.errors["error_8e4a23d6"][0];
+        assert_eq!(e.signature(), "error_8e4a23d6(address)");
+    }
+
+    #[test]
+    fn test_resolved_signature_must_match_arguments() {
+        let mask = "ff".repeat(20);
+        // 0x11223344(address)
+        let code = hex::decode(format!(
+            "60003560e01c63112233441460105700\
+             5b60043573{mask}165f5500"
+        ))
+        .unwrap();
+        let mut info = contract_info(ContractInfoArgs::new(&code).with_arguments());
+        let f = &mut info.functions.as_mut().unwrap()[0];
+
+        f.signatures = Some(vec!["collision(uint256)".to_string()]);
+        let abi = info.abi();
+        assert_eq!(
+            abi.functions["sig_11223344"][0].signature(),
+            "sig_11223344(address)"
+        );
+        assert!(!abi.functions.contains_key("collision"));
+
+        let f = &mut info.functions.as_mut().unwrap()[0];
+        f.signatures = Some(vec![
+            "collision(uint256)".to_string(),
+            "owner(address)".to_string(),
+        ]);
+        let abi = info.abi();
+        assert_eq!(abi.functions["owner"][0].signature(), "owner(address)");
+    }
+
+    #[test]
+    fn test_tuple_params() {
+        use crate::DynSolType;
+        let tp = DynSolType::Array(Box::new(DynSolType::FixedArray(
+            Box::new(DynSolType::Tuple(vec![
+                DynSolType::Address,
+                DynSolType::Uint(8),
+            ])),
+            2,
+        )));
+        let p = super::param(&tp);
+        assert_eq!(p.ty, "tuple[2][]");
+        assert_eq!(p.selector_type(), "(address,uint8)[2][]");
+    }
+}
@@ -0,0 +1,175 @@
+use super::{Structs, split_array};
+use crate::{JsonAbi, StateMutability};
+use alloy_json_abi::Param;
+use std::fmt::Write;
+
+/// Renders a type, with `location` appended to reference types
+fn sol_type(structs: &Structs, ty: &str, components: &[Param], location: Option<&str>) -> String {
+    let (base, _) = split_array(ty);
+    let mut out = match base {
+        "tuple" => format!("{}{}", structs.name(components), &ty[base.len()..]),
+        _ => ty.to_string(),
+    };
+    let is_reference = base != ty || matches!(base, "tuple" | "bytes" | "string");
+    if let (true, Some(location)) = (is_reference, location) {
+        out.push(' ');
+        out.push_str(location);
+    }
+    out
+}
+
+fn params(structs: &Structs, params: &[Param], location: Option<&str>) -> String {
+    let types: Vec<_> = params
+        .iter()
+        .map(|p| {
+            let tp = sol_type(structs, &p.ty, &p.components, location);
+            match p.name.as_str() {
+                "" => tp,
+                name => format!("{tp} {name}"),
+            }
+        })
+        .collect();
+    types.join(",")
+}
+
+fn mutability(state_mutability: StateMutability) -> &'static str {
+    match state_mutability {
+        StateMutability::NonPayable => "",
+        StateMutability::Payable => " payable",
+        StateMutability::View => " view",
+        StateMutability::Pure => " pure",
+    }
+}
+
+/// Generates a Solidity interface named `name` from an ABI, see [`crate::Contract::abi`]
+///
+/// Tuples are declared as structs inside the interface.
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{ContractInfoArgs, abi::solidity_interface, contract_info};
+/// use alloy_primitives::hex;
+///
+/// let code = hex::decode("6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256").unwrap();
+/// let info = contract_info(ContractInfoArgs::new(&code).with_arguments().with_state_mutability());
+///
+/// let source = solidity_interface(&info.abi(), "IContract");
+/// assert!(source.contains("function sig_2125b65b(uint32,address,uint224) external pure;"));
+/// ```
+pub fn solidity_interface(abi: &JsonAbi, name: &str) -> String {
+    let structs = Structs::collect(abi);
+    let mut body = Vec::new();
+
+    for (struct_name, components) in structs.iter() {
+        let mut s = format!("struct {struct_name} {{");
+        for (i, c) in components.iter().enumerate() {
+            write!(
+                s,
+                " {} field{i};",
+                sol_type(&structs, &c.ty, &c.components, None)
+            )
+            .unwrap();
+        }
+        s.push_str(" }");
+        body.push(s);
+    }
+
+    for e in abi.errors() {
+        body.push(format!(
+            "error {}({});",
+            e.name,
+            params(&structs, &e.inputs, None)
+        ));
+    }
+
+    for e in abi.events() {
+        let inputs: Vec<_> = e
+            .inputs
+            .iter()
+            .map(|p| {
+                let tp = sol_type(&structs, &p.ty, &p.components, None);
+                let indexed = if p.indexed { " indexed" } else { "" };
+                match p.name.as_str() {
+                    "" => format!("{tp}{indexed}"),
+                    name => format!("{tp}{indexed} {name}"),
+                }
+            })
+            .collect();
+        let anonymous = if e.anonymous { " anonymous" } else { "" };
+        body.push(format!(
+            "event {}({}){anonymous};",
+            e.name,
+            inputs.join(",")
+        ));
+    }
+
+    if let Some(f) = &abi.fallback {
+        body.push(format!(
+            "fallback() external{};",
+            mutability(f.state_mutability)
+        ));
+    }
+    if abi.receive.is_some() {
+        body.push("receive() external payable;".to_string());
+    }
+
+    for f in abi.functions() {
+        let mut s = format!(
+            "function {}({}) external{}",
+            f.name,
+            params(&structs, &f.inputs, Some("calldata")),
+            mutability(f.state_mutability)
+        );
+        if !f.outputs.is_empty() {
+            write!(
+                s,
+                " returns ({})",
+                params(&structs, &f.outputs, Some("memory"))
+            )
+            .unwrap();
+        }
+        s.push(';');
+        body.push(s);
+    }
+
+    let mut out =
+        String::from("// SPDX-License-Identifier: UNLICENSED\npragma solidity ^0.8.4;\n\n");
+    writeln!(out, "interface {name} {{").unwrap();
+    for line in body {
+        writeln!(out, "    {line}").unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structs_and_locations() {
+        let mut abi = JsonAbi::parse([
+            "function sig_11223344((address,uint8)[],bytes) external view returns (string)",
+            "event event_ddf252ad(bytes32 indexed,uint256)",
+            "error error_8e4a23d6(address)",
+        ])
+        .unwrap();
+        abi.fallback = Some(alloy_json_abi::Fallback {
+            state_mutability: StateMutability::Payable,
+        });
+        assert_eq!(
+            solidity_interface(&abi, "IContract"),
+            "// SPDX-License-Identifier: UNLICENSED\n\
+             pragma solidity ^0.8.4;\n\
+             \n\
+             interface IContract {\n    \
+                 struct Tuple0 { address field0; uint8 field1; }\n    \
+                 error error_8e4a23d6(address);\n    \
+                 event event_ddf252ad(bytes32 indexed,uint256);\n    \
+                 fallback() external payable;\n    \
+                 function sig_11223344(Tuple0[] calldata,bytes calldata) external view returns (string memory);\n\
+             }\n"
+        );
+    }
+}
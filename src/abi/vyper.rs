@@ -0,0 +1,146 @@
+use super::{Structs, split_array};
+use crate::{JsonAbi, StateMutability};
+use alloy_json_abi::Param;
+use std::fmt::Write;
+
+/// Bound of dynamic values, which Vyper requires for `Bytes`, `String` and `DynArray`
+const MAX_LEN: usize = 1024;
+
+fn vy_type(structs: &Structs, ty: &str, components: &[Param]) -> String {
+    let (base, dims) = split_array(ty);
+    let mut out = match base {
+        "tuple" => structs.name(components),
+        "bytes" => format!("Bytes[{MAX_LEN}]"),
+        "string" => format!("String[{MAX_LEN}]"),
+        _ => base.to_string(),
+    };
+    for dim in dims {
+        out = match dim {
+            Some(n) => format!("{out}[{n}]"),
+            None => format!("DynArray[{out}, {MAX_LEN}]"),
+        };
+    }
+    out
+}
+
+/// Returns the parameter name, or `arg<i>` for unnamed ones
+fn arg_name(name: &str, i: usize) -> String {
+    match name {
+        "" => format!("arg{i}"),
+        name => name.to_string(),
+    }
+}
+
+/// Generates a Vyper interface (`.vyi`) from an ABI, see [`crate::Contract::abi`]
+///
+/// Tuples are declared as structs, and unnamed parameters are named `arg<N>`. `Bytes`,
+/// `String` and `DynArray` are bounded to 1024 items. Fallback and receive functions and
+/// errors have no Vyper counterpart and are omitted.
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{ContractInfoArgs, abi::vyper_interface, contract_info};
+/// use alloy_primitives::hex;
+///
+/// let code = hex::decode("6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256").unwrap();
+/// let info = contract_info(ContractInfoArgs::new(&code).with_arguments().with_state_mutability());
+///
+/// let source = vyper_interface(&info.abi());
+/// assert!(source.contains("@pure\ndef sig_2125b65b(arg0: uint32, arg1: address, arg2: uint224):\n"));
+/// ```
+pub fn vyper_interface(abi: &JsonAbi) -> String {
+    let structs = Structs::collect(abi);
+    let mut blocks = Vec::new();
+
+    for (name, components) in structs.iter() {
+        let mut s = format!("struct {name}:\n");
+        for (i, c) in components.iter().enumerate() {
+            let tp = vy_type(&structs, &c.ty, &c.components);
+            writeln!(s, "    field{i}: {tp}").unwrap();
+        }
+        blocks.push(s);
+    }
+
+    for e in abi.events() {
+        let mut s = format!("event {}:\n", e.name);
+        if e.inputs.is_empty() {
+            s.push_str("    pass\n");
+        }
+        for (i, p) in e.inputs.iter().enumerate() {
+            let tp = vy_type(&structs, &p.ty, &p.components);
+            let tp = if p.indexed {
+                format!("indexed({tp})")
+            } else {
+                tp
+            };
+            writeln!(s, "    {}: {tp}", arg_name(&p.name, i)).unwrap();
+        }
+        blocks.push(s);
+    }
+
+    for f in abi.functions() {
+        let decorator = match f.state_mutability {
+            StateMutability::NonPayable => "nonpayable",
+            StateMutability::Payable => "payable",
+            StateMutability::View => "view",
+            StateMutability::Pure => "pure",
+        };
+        let inputs: Vec<_> = f
+            .inputs
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let tp = vy_type(&structs, &p.ty, &p.components);
+                format!("{}: {tp}", arg_name(&p.name, i))
+            })
+            .collect();
+        let outputs: Vec<_> = f
+            .outputs
+            .iter()
+            .map(|p| vy_type(&structs, &p.ty, &p.components))
+            .collect();
+        let returns = match outputs.len() {
+            0 => String::new(),
+            1 => format!(" -> {}", outputs[0]),
+            _ => format!(" -> ({})", outputs.join(", ")),
+        };
+        blocks.push(format!(
+            "@external\n@{decorator}\ndef {}({}){returns}:\n    ...\n",
+            f.name,
+            inputs.join(", ")
+        ));
+    }
+
+    blocks.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_structs_events_and_returns() {
+        let abi = JsonAbi::parse([
+            "function sig_11223344((address,uint8)[2][],bytes) external returns (string,uint256)",
+            "event event_ddf252ad(bytes32 indexed,uint256)",
+            "error error_8e4a23d6(address)",
+        ])
+        .unwrap();
+        assert_eq!(
+            vyper_interface(&abi),
+            "struct Tuple0:\n    \
+                 field0: address\n    \
+                 field1: uint8\n\
+             \n\
+             event event_ddf252ad:\n    \
+                 arg0: indexed(bytes32)\n    \
+                 arg1: uint256\n\
+             \n\
+             @external\n\
+             @nonpayable\n\
+             def sig_11223344(arg0: DynArray[Tuple0[2], 1024], arg1: Bytes[1024]) -> (String[1024], uint256):\n    \
+                 ...\n"
+        );
+    }
+}
@@ -0,0 +1,153 @@
+//! Constructor parameter inference.
+//!
+//! Constructors copy their ABI-encoded arguments from the end of the creation
+//! code into memory. Loads from such a copy are presented to [`analyze`] as the
+//! matching calldata loads, so the function argument rules apply unchanged.
+
+use super::{ArgsResult, Label, Val, analyze};
+use crate::{
+    AnalysisPhase, DynSolType,
+    creation::CallDataImpl,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{U256, element::Element, op, vm::Vm},
+    trace::Tracer,
+};
+
+/// Size of the zeroed arguments region that replaces the appended arguments
+const ARGS_REGION_LEN: usize = 8192;
+
+/// Memory range filled by CODECOPY from the arguments region
+struct ArgsCopy {
+    mem_start: u32,
+    mem_end: u32,
+    /// Memory address corresponding to argument offset 0
+    base: u32,
+}
+
+/// Converts a memory address within an arguments copy into the calldata offset
+/// that a function would load the same word from
+fn as_calldata_offset(addr: &Element<Label>, copies: &[ArgsCopy]) -> Option<Element<Label>> {
+    let mem_off = u32::try_from(addr).ok()?;
+    let copy = copies
+        .iter()
+        .rev()
+        .find(|c| (c.mem_start..c.mem_end).contains(&mem_off))?;
+    let label = match &addr.label {
+        Some(Label::Arg(val)) => {
+            // pointer derived from a loaded offset: rebase it from `base` to the selector end
+            let add_val = val.add_val.checked_sub(copy.base)? + 4;
+            Some(Label::Arg(Val {
+                add_val,
+                ..val.clone()
+            }))
+        }
+        _ => None,
+    };
+    Some(Element {
+        data: U256::from(mem_off - copy.base + 4).to_be_bytes(),
+        label,
+    })
+}
+
+/// Infers constructor parameter types from creation code
+///
+/// `args_offset` is where the appended constructor arguments start, see
+/// [`crate::CreationInfo::constructor_args_offset`].
+pub(crate) fn constructor_argument_types(
+    code: &[u8],
+    args_offset: usize,
+    gas_limit: u32,
+    tracer: Tracer,
+) -> (Vec<DynSolType>, Vec<Diagnostic>) {
+    let args_offset = args_offset.min(code.len());
+    let mut padded = code[..args_offset].to_vec();
+    padded.resize(args_offset + ARGS_REGION_LEN, 0);
+
+    let calldata = CallDataImpl;
+    let mut vm = Vm::new(&padded, &calldata);
+    let mut args = ArgsResult {
+        decoder_active: true,
+        ..ArgsResult::default()
+    };
+    let mut copies: Vec<ArgsCopy> = Vec::new();
+    let mut diagnostics = Vec::new();
+    let mut gas_used = 0;
+
+    while !vm.stopped {
+        tracer.step_with_state(&vm, || format!("{:?}", args.data));
+        let pc = vm.pc;
+        let mut ret = match vm.step() {
+            Ok(v) => v,
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(&mut diagnostics, AnalysisPhase::Creation, pc, kind);
+                break;
+            }
+        };
+        gas_used += ret.gas_used;
+        if gas_used > gas_limit {
+            record(
+                &mut diagnostics,
+                AnalysisPhase::Creation,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
+            break;
+        }
+
+        match ret.op {
+            op::CODECOPY => {
+                if let Ok(mem_start) = u32::try_from(&ret.args[0])
+                    && let Ok(size) = u32::try_from(&ret.args[1])
+                    && let Some(Ok(src_off)) = ret.exargs.first().map(usize::try_from)
+                    && let Some(rel) = src_off.checked_sub(args_offset)
+                    && let Some(base) = u32::try_from(rel)
+                        .ok()
+                        .and_then(|rel| mem_start.checked_sub(rel))
+                {
+                    copies.push(ArgsCopy {
+                        mem_start,
+                        mem_end: mem_start.saturating_add(size),
+                        base,
+                    });
+                }
+            }
+            op::MLOAD => {
+                if let Some(offset) = as_calldata_offset(&ret.args[0], &copies) {
+                    vm.stack.peek_mut().expect("MLOAD pushes its result").label =
+                        Some(Label::CallData);
+                    ret.op = op::CALLDATALOAD;
+                    ret.args[0] = offset;
+                    ret.memory_load = None;
+                }
+            }
+            op::MCOPY => {
+                if let Some(offset) = ret
+                    .exargs
+                    .first()
+                    .and_then(|src| as_calldata_offset(src, &copies))
+                {
+                    let dest = std::mem::replace(&mut ret.args[0], offset);
+                    ret.op = op::CALLDATACOPY;
+                    ret.args[1] = dest;
+                    ret.exargs.remove(0);
+                    ret.memory_load = None;
+                }
+            }
+            _ => {}
+        }
+
+        if let Err(e) = analyze(&mut vm, &mut args, pc, ret) {
+            let kind = DiagnosticKind::from_error(e.as_ref());
+            record(&mut diagnostics, AnalysisPhase::Creation, pc, kind);
+            break;
+        }
+    }
+
+    let types = if args.data.children.is_empty() {
+        vec![]
+    } else {
+        args.data.to_alloy_type(true)
+    };
+    (types, diagnostics)
+}
@@ -1,12 +1,15 @@
 use crate::{
-    DynSolType, Selector,
+    AnalysisPhase, DynSolType, Evidence, Selector,
     collections::HashSet,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
     evm::{
         U256, VAL_0_B, VAL_1, VAL_1_B, VAL_32_B,
+        calldata::CallData,
         element::Element,
         op,
         vm::{StepResult, Vm},
     },
+    trace::Tracer,
     utils::{and_mask_to_type, elabel, execute_until_function_start, match_first_two},
 };
 use alloy_primitives::uint;
@@ -15,6 +18,9 @@ use std::{cmp::max, collections::BTreeMap};
 mod calldata;
 use calldata::CallDataImpl;
 
+mod constructor;
+pub(crate) use constructor::constructor_argument_types;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 enum Label {
     CallData,
@@ -40,10 +46,44 @@ enum InfoVal {
     Array(u32),
 }
 
+/// How much an inferred argument type can be trusted, see [`crate::Function::argument_confidence`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct ArgumentConfidence {
+    /// Confidence in the inferred type, from 0 to 100
+    ///
+    /// 100 means a decoder check (mask comparison, sign extension, high bits check)
+    /// pins the type; lower values come from how the value is used. An argument that
+    /// is never narrowed is reported as `uint256` with confidence 0.
+    pub confidence: u8,
+
+    /// Second most likely type, e.g. `bool` for an inferred `uint8`
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::opt_type")
+    )]
+    pub alternative: Option<DynSolType>,
+}
+
+/// Highest confidence passed to [`ArgsResult::set_tname`]
+const MAX_CONFIDENCE: u8 = 20;
+
+/// A word that is never narrowed: `uint256`, with the same encoding as `bytes32`
+fn unconstrained_word() -> ArgumentConfidence {
+    ArgumentConfidence {
+        confidence: 0,
+        alternative: Some(DynSolType::FixedBytes(32)),
+    }
+}
+
 #[derive(Default, Debug)]
 struct Info {
     tinfo: Option<InfoVal>,
     tname: Option<(DynSolType, u8)>,
+    // opcode that set `tname`
+    evidence: Option<Evidence>,
+    // best candidate that lost to `tname`
+    alternative: Option<(DynSolType, u8)>,
     children: BTreeMap<u32, Info>,
 }
 
@@ -52,19 +92,23 @@ impl Info {
         self.tinfo.is_some() || self.children.values().any(Self::has_dynamic)
     }
 
-    fn to_alloy_type(&self, is_root: bool) -> Vec<DynSolType> {
-        if let Some((name, _)) = &self.tname {
-            if matches!(name, DynSolType::Bytes) {
-                if let Some(InfoVal::Array(0)) | Some(InfoVal::Dynamic(1)) | None = self.tinfo {
-                    return vec![name.clone()];
-                }
-            } else if self.children.is_empty()
-                && let Some(InfoVal::Dynamic(_)) | None = self.tinfo
-            {
-                return vec![name.clone()];
+    /// Returns `tname` if it names the whole type rather than being overridden by the structure
+    fn leaf_tname(&self) -> Option<&(DynSolType, u8)> {
+        let t = self.tname.as_ref()?;
+        if matches!(t.0, DynSolType::Bytes) {
+            if let Some(InfoVal::Array(0)) | Some(InfoVal::Dynamic(1)) | None = self.tinfo {
+                return Some(t);
             }
+        } else if self.children.is_empty()
+            && let Some(InfoVal::Dynamic(_)) | None = self.tinfo
+        {
+            return Some(t);
         }
+        None
+    }
 
+    /// First and last offsets of the words making up the type
+    fn key_range(&self) -> (u32, u32) {
         let start_key = if let Some(InfoVal::Array(_)) = self.tinfo {
             32
         } else {
@@ -78,7 +122,68 @@ impl Info {
         if let Some(InfoVal::Array(n_elements) | InfoVal::Dynamic(n_elements)) = self.tinfo {
             end_key = max(end_key, n_elements * 32);
         }
+        (start_key, end_key)
+    }
+
+    /// Confidence of the word at `key` in a parent without a type for it, see [`Info::confidence`]
+    fn child_confidence(&self, key: u32) -> ArgumentConfidence {
+        self.children
+            .get(&key)
+            .map_or_else(unconstrained_word, Self::confidence)
+    }
+
+    /// Mirrors [`Info::to_alloy_type`] for a non-root node
+    fn confidence(&self) -> ArgumentConfidence {
+        let scale =
+            |conf: u8| (conf.min(MAX_CONFIDENCE) as u32 * 100 / MAX_CONFIDENCE as u32) as u8;
+        if let Some((_, conf)) = self.leaf_tname() {
+            return ArgumentConfidence {
+                confidence: scale(*conf),
+                alternative: self.alternative.as_ref().map(|(t, _)| t.clone()),
+            };
+        }
+        if self.tinfo.is_none() && self.children.is_empty() {
+            return unconstrained_word();
+        }
+        if matches!(self.tinfo, Some(InfoVal::Dynamic(0))) && self.children.is_empty() {
+            // nothing tells the bytes from a string
+            return ArgumentConfidence {
+                confidence: 0,
+                alternative: Some(DynSolType::String),
+            };
+        }
+        let (start_key, end_key) = self.key_range();
+        let confidence = (start_key..=end_key)
+            .step_by(32)
+            .map(|k| self.child_confidence(k).confidence)
+            .min()
+            .unwrap_or(0);
+        ArgumentConfidence {
+            confidence,
+            alternative: None,
+        }
+    }
+
+    /// Appends the evidence of the type, see [`Info::to_alloy_type`]
+    fn collect_evidence(&self, out: &mut Vec<Evidence>) {
+        if self.leaf_tname().is_some() {
+            out.extend(self.evidence);
+            return;
+        }
+        let (start_key, end_key) = self.key_range();
+        for k in (start_key..=end_key).step_by(32) {
+            if let Some(child) = self.children.get(&k) {
+                child.collect_evidence(out);
+            }
+        }
+    }
+
+    fn to_alloy_type(&self, is_root: bool) -> Vec<DynSolType> {
+        if let Some((name, _)) = self.leaf_tname() {
+            return vec![name.clone()];
+        }
 
+        let (start_key, end_key) = self.key_range();
         let q: Vec<_> = (start_key..=end_key)
             .step_by(32)
             .flat_map(|k| {
@@ -152,16 +257,27 @@ impl ArgsResult {
     fn mark_not_bool(&mut self, path: &[u32], offset: u32) {
         let full_path = [path, &[offset]].concat();
 
-        if let Some(el) = self.get_mut(&full_path)
-            && let Some((DynSolType::Bool, _)) = &mut el.tname
-        {
-            el.tname = None;
+        if let Some(el) = self.get_mut(&full_path) {
+            if let Some((DynSolType::Bool, _)) = &el.tname {
+                el.tname = None;
+                el.evidence = None;
+            }
+            if let Some((DynSolType::Bool, _)) = &el.alternative {
+                el.alternative = None;
+            }
         }
 
         self.not_bool.insert(full_path);
     }
 
-    fn set_tname(&mut self, path: &[u32], offset: u32, tname: DynSolType, confidence: u8) {
+    fn set_tname(
+        &mut self,
+        path: &[u32],
+        offset: u32,
+        tname: DynSolType,
+        confidence: u8,
+        evidence: Evidence,
+    ) {
         let full_path = [path, &[offset]].concat();
 
         if matches!(tname, DynSolType::Bool) && self.not_bool.contains(&full_path) {
@@ -169,12 +285,32 @@ impl ArgsResult {
         }
 
         let el = self.get_or_create(&full_path);
-        if let Some((_, conf)) = el.tname
-            && confidence <= conf
+        let (winner, loser) = match el.tname.take() {
+            Some(current) if confidence <= current.1 => (current, (tname, confidence)),
+            Some(current) => {
+                el.evidence = Some(evidence);
+                ((tname, confidence), current)
+            }
+            None => {
+                if el.alternative.as_ref().is_some_and(|(t, _)| *t == tname) {
+                    el.alternative = None;
+                }
+                el.tname = Some((tname, confidence));
+                el.evidence = Some(evidence);
+                return;
+            }
+        };
+        if loser.0 != winner.0
+            && el
+                .alternative
+                .as_ref()
+                .is_none_or(|(t, conf)| *t == winner.0 || loser.1 > *conf)
         {
-            return;
+            el.alternative = Some(loser);
+        } else if el.alternative.as_ref().is_some_and(|(t, _)| *t == winner.0) {
+            el.alternative = None;
         }
-        el.tname = Some((tname, confidence));
+        el.tname = Some(winner);
     }
 
     fn array_in_path(&self, path: &[u32]) -> Vec<bool> {
@@ -282,11 +418,14 @@ fn dense_min_calldata_words(code: &[u8], pc: usize, packed: &Element<Label>) ->
     (min_size >= 4 && (min_size - 4).is_multiple_of(32)).then_some((min_size - 4) / 32)
 }
 
-fn analyze(
-    vm: &mut Vm<Label, CallDataImpl>,
+fn analyze<U: CallData<Label>>(
+    vm: &mut Vm<Label, U>,
     args: &mut ArgsResult,
+    pc: usize,
     ret: StepResult<Label>,
 ) -> Result<(), Box<dyn std::error::Error>> {
+    let opcode = ret.op;
+    let at = |reason| Evidence::new(pc, opcode, reason);
     if matches!(
         ret.op,
         op::SLOAD
@@ -372,7 +511,13 @@ fn analyze(
                     op::CALLDATALOAD => vm.stack.peek_mut()?.label = new_label,
                     op::CALLDATACOPY => {
                         if let Some(v) = vm.memory.get_mut(mem_offset) {
-                            args.set_tname(&path, offset, DynSolType::Bytes, 10);
+                            args.set_tname(
+                                &path,
+                                offset,
+                                DynSolType::Bytes,
+                                10,
+                                at("copied from calldata"),
+                            );
                             v.label = new_label;
                         }
                     }
@@ -418,7 +563,7 @@ fn analyze(
             ..
         } => {
             args.mark_not_bool(&path, offset);
-            args.set_tname(&path, offset, DynSolType::Address, 20);
+            args.set_tname(&path, offset, DynSolType::Address, 20, at("call target"));
         }
 
         StepResult {
@@ -507,7 +652,13 @@ fn analyze(
                 && (8..=256).contains(&shift)
                 && shift.is_multiple_of(8)
             {
-                args.set_tname(&path, offset, DynSolType::FixedBytes(shift / 8), 20);
+                args.set_tname(
+                    &path,
+                    offset,
+                    DynSolType::FixedBytes(shift / 8),
+                    20,
+                    at("high bits check"),
+                );
             }
         }
 
@@ -558,14 +709,26 @@ fn analyze(
                 match mult {
                     VAL_1 => {
                         if let Some((last, rest)) = path.split_last() {
-                            args.set_tname(rest, *last, DynSolType::Bytes, 10);
+                            args.set_tname(
+                                rest,
+                                *last,
+                                DynSolType::Bytes,
+                                10,
+                                at("byte-sized elements"),
+                            );
                         }
                     }
 
                     VAL_2 => {
                         // slen*2+1 for SSTORE
                         if let Some((last, rest)) = path.split_last() {
-                            args.set_tname(rest, *last, DynSolType::String, 20);
+                            args.set_tname(
+                                rest,
+                                *last,
+                                DynSolType::String,
+                                20,
+                                at("string length encoding"),
+                            );
                         }
                     }
 
@@ -657,7 +820,13 @@ fn analyze(
             ..
         } => {
             args.mark_not_bool(&path, offset);
-            args.set_tname(&path, offset, DynSolType::Uint(8), 12);
+            args.set_tname(
+                &path,
+                offset,
+                DynSolType::Uint(8),
+                12,
+                at("single byte store"),
+            );
         }
 
         // Vyper validates narrow ABI scalars by rejecting non-zero high bits.
@@ -682,7 +851,7 @@ fn analyze(
                     None
                 };
                 if let Some(inferred_type) = inferred_type {
-                    args.set_tname(&path, offset, inferred_type, 20);
+                    args.set_tname(&path, offset, inferred_type, 20, at("high bits check"));
                 }
             }
         }
@@ -693,7 +862,13 @@ fn analyze(
             ..
         } => {
             args.mark_not_bool(&path, offset);
-            args.set_tname(&path, offset, DynSolType::Int(256), 8);
+            args.set_tname(
+                &path,
+                offset,
+                DynSolType::Int(256),
+                8,
+                at("signed arithmetic"),
+            );
         }
 
         StepResult {
@@ -713,7 +888,7 @@ fn analyze(
             args.mark_not_bool(&path, offset);
             let mask: U256 = ot.into();
             if let Some(t) = and_mask_to_type(mask) {
-                args.set_tname(&path, offset, t, 5);
+                args.set_tname(&path, offset, t, 5, at("mask"));
                 vm.stack.peek_mut()?.label = Some(Label::Arg(Val {
                     offset,
                     path,
@@ -747,7 +922,7 @@ fn analyze(
                 && (s_add_val == add_val)
                 && let Some(t) = and_mask_to_type(mask)
             {
-                args.set_tname(&path, offset, t, 20);
+                args.set_tname(&path, offset, t, 20, at("mask comparison"));
             }
         }
 
@@ -784,7 +959,13 @@ fn analyze(
             }
 
             if is_bool {
-                args.set_tname(&val.path, val.offset, DynSolType::Bool, 5);
+                args.set_tname(
+                    &val.path,
+                    val.offset,
+                    DynSolType::Bool,
+                    5,
+                    at("double negation"),
+                );
             }
         }
 
@@ -795,7 +976,13 @@ fn analyze(
         } => {
             if s0.data < VAL_32_B {
                 let s0: u8 = s0.data[31];
-                args.set_tname(&path, offset, DynSolType::Int((s0 as usize + 1) * 8), 20);
+                args.set_tname(
+                    &path,
+                    offset,
+                    DynSolType::Int((s0 as usize + 1) * 8),
+                    20,
+                    at("sign extension"),
+                );
             }
         }
 
@@ -804,7 +991,13 @@ fn analyze(
             args: [_, elabel!(Label::Arg(Val { offset, path, .. })), ..],
             ..
         } => {
-            args.set_tname(&path, offset, DynSolType::FixedBytes(32), 4);
+            args.set_tname(
+                &path,
+                offset,
+                DynSolType::FixedBytes(32),
+                4,
+                at("byte access"),
+            );
         }
 
         StepResult {
@@ -829,19 +1022,47 @@ fn analyze(
 
 /// Extracts function arguments
 ///
+/// Returns the inferred types and the diagnostics explaining why execution
+/// stopped early, if it did.
+///
 /// # Arguments
 ///
 /// * `code` - A slice of deployed contract bytecode
 /// * `selector` - A function selector
 /// * `gas_limit` - Maximum allowed gas usage; set to `0` to use defaults
 /// ```
-pub fn function_arguments(code: &[u8], selector: &Selector, gas_limit: u32) -> Vec<DynSolType> {
-    if cfg!(feature = "trace_arguments") {
-        println!(
-            "Processing selector {:02x}{:02x}{:02x}{:02x}",
-            selector[0], selector[1], selector[2], selector[3]
-        );
-    }
+pub fn function_arguments(
+    code: &[u8],
+    selector: &Selector,
+    gas_limit: u32,
+) -> (Vec<DynSolType>, Vec<Diagnostic>) {
+    let (inferred, diagnostics) = infer_arguments(
+        code,
+        selector,
+        gas_limit,
+        Tracer::disabled(AnalysisPhase::Arguments),
+    );
+    (inferred.types, diagnostics)
+}
+
+/// Inferred arguments with details on how each was inferred, see [`infer_arguments`]
+#[derive(Debug, Default)]
+pub(crate) struct InferredArguments {
+    pub types: Vec<DynSolType>,
+    /// Confidence of each of `types`
+    pub confidence: Vec<ArgumentConfidence>,
+    /// Opcodes that narrowed each of `types`, in pc order
+    pub evidence: Vec<Vec<Evidence>>,
+}
+
+/// Extracts function arguments along with their confidence and evidence, see [`function_arguments`]
+pub(crate) fn infer_arguments(
+    code: &[u8],
+    selector: &Selector,
+    gas_limit: u32,
+    tracer: Tracer,
+) -> (InferredArguments, Vec<Diagnostic>) {
+    let tracer = tracer.with_selector(*selector);
     let calldata = CallDataImpl {
         selector: *selector,
     };
@@ -857,10 +1078,13 @@ pub fn function_arguments(code: &[u8], selector: &Selector, gas_limit: u32) -> V
         gas_limit
     };
 
-    if let Some(g) = execute_until_function_start(&mut vm, real_gas_limit) {
-        gas_used += g;
-    } else {
-        return vec![];
+    let mut diagnostics = Vec::new();
+    match execute_until_function_start(&mut vm, real_gas_limit) {
+        Ok(g) => gas_used += g,
+        Err(e) => {
+            e.record(&mut diagnostics, AnalysisPhase::Arguments);
+            return (InferredArguments::default(), diagnostics);
+        }
     }
 
     // Seed Vyper's static ABI head from its minimum-calldata-size check. This
@@ -873,25 +1097,30 @@ pub fn function_arguments(code: &[u8], selector: &Selector, gas_limit: u32) -> V
     }
 
     while !vm.stopped {
-        if cfg!(feature = "trace_arguments") {
-            println!("args: {args:?}");
-            println!("not_bool: {:?}", args.not_bool);
-            println!("{:#?}", args.data);
-            println!("{vm:?}\n");
-        }
+        tracer.step_with_state(&vm, || format!("{args:?}"));
+        let pc = vm.pc;
         let ret = match vm.step() {
             Ok(v) => v,
-            Err(_e) => {
-                // println!("{}", _e);
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(&mut diagnostics, AnalysisPhase::Arguments, pc, kind);
                 break;
             }
         };
         gas_used += ret.gas_used;
         if gas_used > real_gas_limit {
+            record(
+                &mut diagnostics,
+                AnalysisPhase::Arguments,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
             break;
         }
 
-        if analyze(&mut vm, &mut args, ret).is_err() {
+        if let Err(e) = analyze(&mut vm, &mut args, pc, ret) {
+            let kind = DiagnosticKind::from_error(e.as_ref());
+            record(&mut diagnostics, AnalysisPhase::Arguments, pc, kind);
             break;
         }
     }
@@ -907,19 +1136,87 @@ pub fn function_arguments(code: &[u8], selector: &Selector, gas_limit: u32) -> V
     }
 
     if args.data.children.is_empty() {
-        vec![]
-    } else {
-        args.data.to_alloy_type(true)
+        return (InferredArguments::default(), diagnostics);
     }
+    let (start_key, end_key) = args.data.key_range();
+    let keys = (start_key..=end_key).step_by(32);
+    let inferred = InferredArguments {
+        types: args.data.to_alloy_type(true),
+        confidence: keys
+            .clone()
+            .map(|k| args.data.child_confidence(k))
+            .collect(),
+        evidence: keys
+            .map(|k| {
+                let mut evidence = Vec::new();
+                if let Some(child) = args.data.children.get(&k) {
+                    child.collect_evidence(&mut evidence);
+                }
+                evidence.sort();
+                evidence.dedup();
+                evidence
+            })
+            .collect(),
+    };
+    (inferred, diagnostics)
 }
 
 #[cfg(test)]
 mod tests {
-    use super::function_arguments;
+    use super::{ArgumentConfidence, function_arguments, infer_arguments};
     use crate::selectors::function_selectors;
-    use crate::{ContractInfoArgs, contract_info};
+    use crate::trace::Tracer;
+    use crate::{AnalysisPhase, ContractInfoArgs, DynSolType, Evidence, contract_info};
     use alloy_primitives::hex;
 
+    #[test]
+    fn test_confidence_and_alternatives() {
+        // arg0 is masked to 8 bits, then divided as a signed number; arg1 is never narrowed
+        let code = hex::decode(
+            "60003560e01c631122334414601057005b\
+             60043560ff1650600435600205506024355000",
+        )
+        .unwrap();
+        let (inferred, _) = infer_arguments(
+            &code,
+            &[0x11, 0x22, 0x33, 0x44],
+            0,
+            Tracer::disabled(AnalysisPhase::Arguments),
+        );
+        assert_eq!(
+            inferred.types,
+            vec![DynSolType::Int(256), DynSolType::Uint(256)]
+        );
+        assert_eq!(
+            inferred.confidence,
+            vec![
+                ArgumentConfidence {
+                    confidence: 40,
+                    alternative: Some(DynSolType::Uint(8)),
+                },
+                ArgumentConfidence {
+                    confidence: 0,
+                    alternative: Some(DynSolType::FixedBytes(32)),
+                },
+            ]
+        );
+        assert_eq!(
+            inferred.evidence,
+            vec![
+                vec![Evidence::new(
+                    0x1d,
+                    crate::evm::op::SDIV,
+                    "signed arithmetic"
+                )],
+                vec![],
+            ]
+        );
+        assert_eq!(
+            inferred.evidence[0][0].to_string(),
+            "signed arithmetic: SDIV at pc 0x1d"
+        );
+    }
+
     #[test]
     fn test_double_iszero_at_end_of_code() {
         let code = hex::decode("60003560e01c631122334414600f575b6004351515").unwrap();
@@ -940,7 +1237,10 @@ mod tests {
         // Mainnet 0x27e70bfdf7de32bae2274c8d37d51934ff098910
         let code = hex::decode("6000608052600060a052600060c052600060e05260006101005260006101205260006101405260006101605260006101805260006101a05260006101c05260006101e05260006102005260006102205260006102405260006102605260006102805260006102a05260006102c05260006102e05260006103005260006103205260006103405260006103605260006103805260006103a05260006103c05260006103e0526000610400526000610420526000610440527f66702d66702d7075662d763100000000000000000000000000000000000000006000554360018060000101556101806103dc610200396102005160045561022051600855610240516006556102605160075561028051600a556102a051600b556102c0516001556102e0516010556103005160115561032051601455610340516080906103dc9060208101101561014c57600080fd5b602061034051016103dc01101561016257600080fd5b6103405160208101101561017557600080fd5b602061034051016103dc0161038039610380516012556103a0516013556103c0516015556103e051601655610360516020906103dc90810110156101b857600080fd5b610360516103dc016104003961040051600c556103dc610240810110156101de57600080fd5b6102406103dc016103605260006104605261040051610480525b610480511561033157602061036051600160006104605114610218575060005b6102405760206104605160206104605102041461023457600080fd5b60206104605102610243565b60005b6103605101101561025357600080fd5b600160006104605114610264575060005b61028c5760206104605160206104605102041461028057600080fd5b6020610460510261028f565b60005b6103605101610420396104205161044051810110156102ad57600080fd5b610440516104205101610440527f7061796d656e740000000000000000000000000000000000000000000000000060c0526104605160e05261042051604060c020556104605160016104605101101561030557600080fd5b6001610460510161046052610480516001111561032157600080fd5b60016104805103610480526101f8565b341561033c57600080fd5b60016003557f587ece4cd19692c5be1a4184503d607d45542d2aca0698c0068f52e09ccb541c6040610200a16066806103766000396000f3007c010000000000000000000000000000000000000000000000000000000060003504608081905263696eb8fb1415603e576000546104a0908152602090f35b366000803760008036600060016000015460155a03f4605c57600080fd5b3d6000803e3d6000f3").unwrap();
 
-        for sig in function_selectors(&code, 0, None).0.keys() {
+        for sig in function_selectors(&code, 0, None, Tracer::disabled(AnalysisPhase::Selectors))
+            .selectors
+            .keys()
+        {
             let _ = function_arguments(&code, sig, 0);
         }
     }
@@ -0,0 +1,171 @@
+//! Analysis of many contracts at once, deduplicated by code hash.
+
+use crate::{Contract, ContractInfoArgs, collections::HashMap, contract_info, utils::map_ordered};
+use alloy_primitives::{B256, keccak256};
+use std::time::{Duration, Instant};
+
+/// Throughput statistics of a [`contract_info_batch`] call
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct BatchStats {
+    /// Number of input codes
+    pub inputs: usize,
+
+    /// Number of distinct codes that were analyzed
+    pub unique: usize,
+
+    /// Total size of all input codes, in bytes
+    pub input_bytes: usize,
+
+    /// Total size of the distinct codes, in bytes
+    pub unique_bytes: usize,
+
+    /// Wall-clock time spent hashing and analyzing
+    pub elapsed: Duration,
+}
+
+impl BatchStats {
+    /// Input codes processed per second, `0.0` if no time has elapsed
+    pub fn inputs_per_sec(&self) -> f64 {
+        self.per_sec(self.inputs)
+    }
+
+    /// Distinct codes analyzed per second, `0.0` if no time has elapsed
+    pub fn unique_per_sec(&self) -> f64 {
+        self.per_sec(self.unique)
+    }
+
+    fn per_sec(&self, count: usize) -> f64 {
+        let secs = self.elapsed.as_secs_f64();
+        if secs == 0.0 {
+            0.0
+        } else {
+            count as f64 / secs
+        }
+    }
+}
+
+/// Results of [`contract_info_batch`]
+#[derive(Debug)]
+pub struct ContractBatch {
+    /// Analysis results for each distinct code, in order of first appearance
+    pub contracts: Vec<Contract>,
+
+    /// Keccak-256 hashes of the distinct codes, parallel to `contracts`
+    pub code_hashes: Vec<B256>,
+
+    /// Index into `contracts` for each input code
+    pub indices: Vec<usize>,
+
+    /// Throughput statistics
+    pub stats: BatchStats,
+}
+
+impl ContractBatch {
+    /// Returns the analysis result of the input code at position `input`
+    pub fn get(&self, input: usize) -> Option<&Contract> {
+        self.indices.get(input).map(|&i| &self.contracts[i])
+    }
+
+    /// Returns the code hash of the input code at position `input`
+    pub fn code_hash(&self, input: usize) -> Option<&B256> {
+        self.indices.get(input).map(|&i| &self.code_hashes[i])
+    }
+
+    /// Iterates over the analysis results in input order
+    pub fn iter(&self) -> impl Iterator<Item = &Contract> {
+        self.indices.iter().map(|&i| &self.contracts[i])
+    }
+}
+
+/// Analyzes many contracts, running the analysis once per distinct code.
+///
+/// Codes are deduplicated by their Keccak-256 hash. Each distinct code is
+/// analyzed with the options of `args`; the code passed to `args` itself is
+/// ignored. With the `parallel` feature, distinct codes are analyzed on the
+/// rayon thread pool.
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{ContractInfoArgs, contract_info_batch};
+/// use alloy_primitives::hex;
+///
+/// let a = hex::decode("6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256").unwrap();
+/// let b = hex::decode("00").unwrap();
+///
+/// let batch = contract_info_batch(
+///     [a.as_slice(), b.as_slice(), a.as_slice()],
+///     ContractInfoArgs::default().with_selectors(),
+/// );
+/// assert_eq!(batch.stats.inputs, 3);
+/// assert_eq!(batch.stats.unique, 2);
+/// assert_eq!(batch.indices, vec![0, 1, 0]);
+/// assert_eq!(batch.get(2).unwrap().functions.as_ref().unwrap().len(), 2);
+/// ```
+pub fn contract_info_batch<'a, I>(codes: I, args: ContractInfoArgs<'a>) -> ContractBatch
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let start = Instant::now();
+    let mut stats = BatchStats::default();
+
+    let mut seen: HashMap<B256, usize> = HashMap::default();
+    let mut unique: Vec<&[u8]> = Vec::new();
+    let mut code_hashes = Vec::new();
+    let mut indices = Vec::new();
+    for code in codes {
+        stats.inputs += 1;
+        stats.input_bytes += code.len();
+        let hash = keccak256(code);
+        let index = *seen.entry(hash).or_insert_with(|| {
+            stats.unique_bytes += code.len();
+            unique.push(code);
+            code_hashes.push(hash);
+            unique.len() - 1
+        });
+        indices.push(index);
+    }
+    stats.unique = unique.len();
+
+    let contracts = map_ordered(&unique, |code| contract_info(args.with_code(code)));
+
+    stats.elapsed = start.elapsed();
+    ContractBatch {
+        contracts,
+        code_hashes,
+        indices,
+        stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_batch() {
+        let batch = contract_info_batch([], ContractInfoArgs::default().with_selectors());
+        assert!(batch.contracts.is_empty());
+        assert!(batch.get(0).is_none());
+        assert_eq!(batch.stats.inputs, 0);
+        assert_eq!(BatchStats::default().inputs_per_sec(), 0.0);
+        assert_eq!(BatchStats::default().unique_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn test_deduplication() {
+        let codes: [&[u8]; 4] = [&[0x00], &[0xfe], &[0x00], &[0x00]];
+        let batch = contract_info_batch(codes, ContractInfoArgs::default().with_disassemble());
+        assert_eq!(batch.indices, vec![0, 1, 0, 0]);
+        assert_eq!(batch.contracts.len(), 2);
+        assert_eq!(batch.code_hash(3), Some(&keccak256([0x00])));
+        assert_eq!(batch.stats.input_bytes, 4);
+        assert_eq!(batch.stats.unique_bytes, 2);
+
+        let disassembled: Vec<_> = batch
+            .iter()
+            .map(|c| c.disassembled.as_ref().unwrap()[0].1.clone())
+            .collect();
+        assert_eq!(disassembled, vec!["STOP", "INVALID", "STOP", "STOP"]);
+    }
+}
@@ -1,12 +1,25 @@
-use crate::{CborMetadata, DynSolType, Selector, StateMutability, StorageRecord};
 use crate::{
-    arguments::function_arguments,
+    ArgumentConfidence, ArgumentsValidation, CborMetadata, Diagnostic, DynSolType, Event,
+    FunctionEvidence, Proxy, RevertError, Selector, SpecialFunctions, StateMutability,
+    StorageRecord, Topic, TraceSink,
+};
+use crate::{
+    arguments::infer_arguments,
     control_flow_graph::basic_blocks,
     control_flow_graph::{ControlFlowGraph, control_flow_graph},
+    diagnostics::truncated_phases,
+    errors::contract_errors,
+    events::contract_events,
     evm::code_iterator::disassemble,
+    outputs::function_outputs,
     selectors::function_selectors,
+    signatures::{SignatureResolver, rank_signatures},
+    special_functions::special_functions,
     state_mutability::function_state_mutability,
     storage::contract_storage,
+    trace::Tracer,
+    utils::map_ordered,
+    validation::validate_arguments,
 };
 
 /// Describes where a selector is dispatched in the runtime bytecode.
@@ -30,6 +43,69 @@ impl SelectorDispatch {
     }
 }
 
+/// Identifies one of the VM-driven analysis passes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AnalysisPhase {
+    /// Function selector extraction.
+    Selectors,
+    /// Function argument type inference.
+    Arguments,
+    /// State mutability inference.
+    StateMutability,
+    /// Persistent and transient storage layout extraction.
+    Storage,
+    /// Constructor execution of creation code, see [`crate::creation_info`].
+    Creation,
+    /// Recognition of storage-slot proxies, see [`crate::Proxy`].
+    Proxy,
+    /// Event extraction, see [`crate::Event`].
+    Events,
+    /// Revert error extraction, see [`crate::RevertError`].
+    Errors,
+    /// Function return type inference.
+    Outputs,
+    /// Fallback and receive detection, see [`crate::SpecialFunctions`].
+    SpecialFunctions,
+    /// Re-execution with the inferred arguments, see [`crate::ArgumentsValidation`].
+    ArgumentsValidation,
+}
+
+impl AnalysisPhase {
+    /// All phases, in declaration order
+    pub const ALL: [Self; 11] = [
+        Self::Selectors,
+        Self::Arguments,
+        Self::StateMutability,
+        Self::Storage,
+        Self::Creation,
+        Self::Proxy,
+        Self::Events,
+        Self::Errors,
+        Self::Outputs,
+        Self::SpecialFunctions,
+        Self::ArgumentsValidation,
+    ];
+
+    /// Returns the stable camelCase representation used by language bindings.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Selectors => "selectors",
+            Self::Arguments => "arguments",
+            Self::StateMutability => "stateMutability",
+            Self::Storage => "storage",
+            Self::Creation => "creation",
+            Self::Proxy => "proxy",
+            Self::Events => "events",
+            Self::Errors => "errors",
+            Self::Outputs => "outputs",
+            Self::SpecialFunctions => "specialFunctions",
+            Self::ArgumentsValidation => "argumentsValidation",
+        }
+    }
+}
+
 /// Represents a selector-bearing smart contract entry point.
 #[derive(Debug)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -55,6 +131,35 @@ pub struct Function {
     )]
     pub arguments: Option<Vec<DynSolType>>,
 
+    /// Confidence and alternative type of each of the `arguments`
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "argumentConfidence", skip_serializing_if = "Option::is_none")
+    )]
+    pub argument_confidence: Option<Vec<ArgumentConfidence>>,
+
+    /// Whether the function accepts calldata encoded with `arguments`
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            rename = "argumentsValidation",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub arguments_validation: Option<ArgumentsValidation>,
+
+    /// Text signatures with this selector from the [`SignatureResolver`], the ones most
+    /// consistent with `arguments` first
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub signatures: Option<Vec<String>>,
+
+    /// Function return types
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::arguments")
+    )]
+    pub outputs: Option<Vec<DynSolType>>,
+
     /// State mutability
     #[cfg_attr(
         feature = "serde",
@@ -64,6 +169,25 @@ pub struct Function {
         )
     )]
     pub state_mutability: Option<StateMutability>,
+
+    /// Topics of the events the function can emit, see [`Contract::events`]
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::topics"))]
+    pub events: Option<Vec<Topic>>,
+
+    /// Opcodes that the inferred arguments and state mutability are based on, when requested
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub evidence: Option<FunctionEvidence>,
+
+    /// Per-function analysis phases that ran out of gas before finishing
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub truncated: Vec<AnalysisPhase>,
+
+    /// Reasons why per-function analysis passes stopped early.
+    ///
+    /// An empty `arguments` list with no `Arguments` diagnostic means the
+    /// function takes no arguments rather than that the analysis failed.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 /// Contains analyzed information about a smart contract
@@ -73,6 +197,10 @@ pub struct Contract {
     /// List of contract functions with their metadata
     pub functions: Option<Vec<Function>>,
 
+    /// Fallback and receive functions
+    #[cfg_attr(feature = "serde", serde(rename = "specialFunctions"))]
+    pub special_functions: Option<SpecialFunctions>,
+
     /// Persistent contract storage layout
     pub storage: Option<Vec<StorageRecord>>,
 
@@ -80,6 +208,12 @@ pub struct Contract {
     #[cfg_attr(feature = "serde", serde(rename = "transientStorage"))]
     pub transient_storage: Option<Vec<StorageRecord>>,
 
+    /// Events emitted by the functions and the fallback, sorted by topic
+    pub events: Option<Vec<Event>>,
+
+    /// Errors the functions and the fallback can revert with
+    pub errors: Option<Vec<RevertError>>,
+
     /// Disassembled code
     pub disassembled: Option<Vec<(usize, String)>>,
 
@@ -94,23 +228,63 @@ pub struct Contract {
     /// Terminal CBOR metadata, when requested and valid.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub metadata: Option<CborMetadata>,
+
+    /// Proxy recognized from the code, when requested.
+    ///
+    /// Minimal proxies and clones dispatch every call to their implementation,
+    /// so their `functions` are empty. Storage-slot proxies list their own
+    /// admin functions, if any.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub proxy: Option<Proxy>,
+
+    /// Contract-wide analysis phases that ran out of gas before finishing.
+    ///
+    /// Per-function truncation is reported in [`Function::truncated`].
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub truncated: Vec<AnalysisPhase>,
+
+    /// Reasons why contract-wide analysis passes stopped early
+    ///
+    /// Covers selector extraction and the fallback storage pass; see
+    /// [`Function::diagnostics`] for per-function passes.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 /// Builder for configuring contract analysis parameters
 ///
 /// See [`contract_info`] for usage examples.
-#[derive(Default)]
+#[derive(Clone, Default)]
 pub struct ContractInfoArgs<'a> {
     code: &'a [u8],
 
     need_selectors: bool,
+    need_special_functions: bool,
     need_arguments: bool,
+    need_arguments_validation: bool,
+    need_outputs: bool,
     need_state_mutability: bool,
     need_storage: bool,
+    need_events: bool,
+    need_errors: bool,
     need_disassemble: bool,
     need_basic_blocks: bool,
     need_control_flow_graph: bool,
     need_metadata: bool,
+    need_proxy: bool,
+    need_evidence: bool,
+
+    signature_resolver: Option<&'a dyn SignatureResolver>,
+    trace_sink: Option<&'a dyn TraceSink>,
+
+    gas_limit: u32,
+    selectors_gas_limit: Option<u32>,
+    arguments_gas_limit: Option<u32>,
+    outputs_gas_limit: Option<u32>,
+    state_mutability_gas_limit: Option<u32>,
+    storage_gas_limit: Option<u32>,
+    events_gas_limit: Option<u32>,
+    errors_gas_limit: Option<u32>,
 }
 
 impl<'a> ContractInfoArgs<'a> {
@@ -132,6 +306,13 @@ impl<'a> ContractInfoArgs<'a> {
         self
     }
 
+    /// Enables the detection of fallback and receive functions
+    pub fn with_special_functions(mut self) -> Self {
+        self.need_selectors = true;
+        self.need_special_functions = true;
+        self
+    }
+
     /// Enables the extraction of function arguments
     pub fn with_arguments(mut self) -> Self {
         self.need_selectors = true;
@@ -139,6 +320,23 @@ impl<'a> ContractInfoArgs<'a> {
         self
     }
 
+    /// Enables the validation of extracted arguments by calling each function with
+    /// values of the inferred types
+    pub fn with_arguments_validation(mut self) -> Self {
+        self.need_selectors = true;
+        self.need_arguments = true;
+        self.need_arguments_validation = true;
+        self
+    }
+
+    /// Enables the inference of function return types
+    pub fn with_outputs(mut self) -> Self {
+        self.need_selectors = true;
+        self.need_arguments = true;
+        self.need_outputs = true;
+        self
+    }
+
     /// Enables the extraction of state mutability
     pub fn with_state_mutability(mut self) -> Self {
         self.need_selectors = true;
@@ -154,6 +352,22 @@ impl<'a> ContractInfoArgs<'a> {
         self
     }
 
+    /// Enables extraction of emitted events and their data layouts
+    pub fn with_events(mut self) -> Self {
+        self.need_selectors = true;
+        self.need_arguments = true;
+        self.need_events = true;
+        self
+    }
+
+    /// Enables extraction of custom errors, panic codes and revert reasons
+    pub fn with_errors(mut self) -> Self {
+        self.need_selectors = true;
+        self.need_arguments = true;
+        self.need_errors = true;
+        self
+    }
+
     /// Enables disassemble bytecodes into individual opcodes
     pub fn with_disassemble(mut self) -> Self {
         self.need_disassemble = true;
@@ -178,6 +392,117 @@ impl<'a> ContractInfoArgs<'a> {
         self.need_metadata = true;
         self
     }
+
+    /// Enables proxy recognition: minimal proxies and clones with hardcoded
+    /// implementations, and fallbacks delegating to an address kept in storage.
+    ///
+    /// Also enables the extraction of function selectors: the proxy's own functions
+    /// are checked for writes to the implementation slot, see [`crate::Proxy::writers`].
+    pub fn with_proxy(mut self) -> Self {
+        self.need_selectors = true;
+        self.need_proxy = true;
+        self
+    }
+
+    /// Records the opcodes that inferred argument types, state mutability and storage
+    /// records are based on, see [`Function::evidence`] and [`StorageRecord::evidence`]
+    pub fn with_evidence(mut self) -> Self {
+        self.need_evidence = true;
+        self
+    }
+
+    /// Resolves selectors to text signatures, ranked by agreement with the inferred
+    /// arguments when they are extracted too
+    pub fn with_signature_resolver(mut self, resolver: &'a dyn SignatureResolver) -> Self {
+        self.need_selectors = true;
+        self.signature_resolver = Some(resolver);
+        self
+    }
+
+    /// Records every step of the abstract VM runs into `sink`, see [`crate::TraceRecorder`]
+    pub fn with_trace(mut self, sink: &'a dyn TraceSink) -> Self {
+        self.trace_sink = Some(sink);
+        self
+    }
+
+    /// Sets the gas limit used by every analysis phase; `0` restores the defaults
+    ///
+    /// Phase-specific limits set with `with_*_gas_limit()` take precedence.
+    pub fn with_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+
+    /// Sets the gas limit for selector extraction (default: 500000)
+    pub fn with_selectors_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.selectors_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the per-function gas limit for argument extraction (default: 50000)
+    pub fn with_arguments_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.arguments_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the per-function gas limit for return type inference (default: 1000000)
+    pub fn with_outputs_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.outputs_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the per-function gas limit for state mutability extraction (default: 500000)
+    pub fn with_state_mutability_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.state_mutability_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the per-function gas limit for storage layout extraction (default: 1000000)
+    pub fn with_storage_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.storage_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the per-function gas limit for event extraction (default: 1000000)
+    pub fn with_events_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.events_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Sets the per-function gas limit for error extraction (default: 1000000)
+    pub fn with_errors_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.errors_gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Returns the same configuration applied to another code
+    pub(crate) fn with_code(&self, code: &'a [u8]) -> Self {
+        Self {
+            code,
+            ..self.clone()
+        }
+    }
+
+    fn tracer(&self, phase: AnalysisPhase) -> Tracer<'a> {
+        Tracer::new(self.trace_sink, phase)
+    }
+
+    fn phase_gas_limit(&self, phase: AnalysisPhase) -> u32 {
+        match phase {
+            AnalysisPhase::Selectors => self.selectors_gas_limit,
+            AnalysisPhase::Arguments => self.arguments_gas_limit,
+            AnalysisPhase::Outputs => self.outputs_gas_limit,
+            AnalysisPhase::StateMutability => self.state_mutability_gas_limit,
+            AnalysisPhase::Storage => self.storage_gas_limit,
+            AnalysisPhase::Events => self.events_gas_limit,
+            AnalysisPhase::Errors => self.errors_gas_limit,
+            AnalysisPhase::Creation
+            | AnalysisPhase::Proxy
+            | AnalysisPhase::SpecialFunctions
+            | AnalysisPhase::ArgumentsValidation => None,
+        }
+        .unwrap_or(self.gas_limit)
+    }
 }
 
 /// Extracts information about a smart contract from its EVM bytecode.
@@ -192,6 +517,9 @@ impl<'a> ContractInfoArgs<'a> {
 ///
 /// Returns a [`Contract`] object containing the requested smart contract information. The
 /// `Contract` struct wraps optional fields depending on the configuration provided in `args`.
+///
+/// With the `parallel` cargo feature, per-function analyses (arguments, outputs, state mutability,
+/// storage, events, errors) run on the rayon thread pool. The result is the same as without it.
 /// # Examples
 ///
 /// ```
@@ -213,8 +541,6 @@ impl<'a> ContractInfoArgs<'a> {
 /// assert_eq!(fns[0].state_mutability, Some(StateMutability::Pure));
 /// ```
 pub fn contract_info(args: ContractInfoArgs) -> Contract {
-    const GAS_LIMIT: u32 = 0;
-
     let metadata = (args.need_metadata || args.need_selectors)
         .then(|| crate::metadata::extract(args.code))
         .flatten();
@@ -232,27 +558,129 @@ pub fn contract_info(args: ContractInfoArgs) -> Contract {
         (None, None)
     };
 
-    let functions = args.need_selectors.then(|| {
-        let (selectors, _selectors_gas_used) =
-            function_selectors(args.code, GAS_LIMIT, metadata.as_ref());
-        selectors
-            .into_iter()
-            .map(|(selector, (bytecode_offset, dispatch))| Function {
+    let mut diagnostics = Vec::new();
+
+    let mut functions = args.need_selectors.then(|| {
+        let selectors = function_selectors(
+            args.code,
+            args.phase_gas_limit(AnalysisPhase::Selectors),
+            metadata.as_ref(),
+            args.tracer(AnalysisPhase::Selectors),
+        );
+        diagnostics.extend(selectors.diagnostics);
+        let selectors: Vec<_> = selectors.selectors.into_iter().collect();
+        map_ordered(&selectors, |&(selector, (bytecode_offset, dispatch))| {
+            let mut fn_diagnostics = Vec::new();
+            let inferred = args.need_arguments.then(|| {
+                let (inferred, diagnostics) = infer_arguments(
+                    args.code,
+                    &selector,
+                    args.phase_gas_limit(AnalysisPhase::Arguments),
+                    args.tracer(AnalysisPhase::Arguments),
+                );
+                fn_diagnostics.extend(diagnostics);
+                inferred
+            });
+            let (arguments, argument_confidence, argument_evidence) = match inferred {
+                Some(i) => (Some(i.types), Some(i.confidence), Some(i.evidence)),
+                None => (None, None, None),
+            };
+            let arguments_validation = args.need_arguments_validation.then(|| {
+                let (validation, diagnostics) = validate_arguments(
+                    args.code,
+                    selector,
+                    arguments
+                        .as_ref()
+                        .expect("enabled on with_arguments_validation()"),
+                    args.phase_gas_limit(AnalysisPhase::ArgumentsValidation),
+                );
+                fn_diagnostics.extend(diagnostics);
+                validation
+            });
+            let signatures = args.signature_resolver.map(|resolver| {
+                rank_signatures(
+                    selector,
+                    resolver.signatures(selector),
+                    arguments.as_deref(),
+                )
+            });
+            let outputs = args.need_outputs.then(|| {
+                let (outputs, diagnostics) = function_outputs(
+                    args.code,
+                    selector,
+                    arguments.as_ref().expect("enabled on with_outputs()"),
+                    args.phase_gas_limit(AnalysisPhase::Outputs),
+                    args.tracer(AnalysisPhase::Outputs),
+                );
+                fn_diagnostics.extend(diagnostics);
+                outputs
+            });
+            let (state_mutability, state_mutability_evidence) = args
+                .need_state_mutability
+                .then(|| {
+                    let (state_mutability, evidence, diagnostics) = function_state_mutability(
+                        args.code,
+                        &selector,
+                        args.phase_gas_limit(AnalysisPhase::StateMutability),
+                        args.tracer(AnalysisPhase::StateMutability),
+                    );
+                    fn_diagnostics.extend(diagnostics);
+                    (state_mutability, evidence)
+                })
+                .unzip();
+            let evidence = args.need_evidence.then_some(FunctionEvidence {
+                arguments: argument_evidence,
+                state_mutability: state_mutability_evidence,
+            });
+            Function {
                 selector,
                 dispatch,
-                arguments: if args.need_arguments {
-                    Some(function_arguments(args.code, &selector, GAS_LIMIT))
-                } else {
-                    None
-                },
-                state_mutability: if args.need_state_mutability {
-                    Some(function_state_mutability(args.code, &selector, GAS_LIMIT))
-                } else {
-                    None
-                },
+                arguments,
+                argument_confidence,
+                arguments_validation,
+                signatures,
+                outputs,
+                state_mutability,
+                events: None,
                 bytecode_offset,
-            })
-            .collect::<Vec<_>>()
+                evidence,
+                truncated: Vec::new(),
+                diagnostics: fn_diagnostics,
+            }
+        })
+    });
+
+    let special_functions = args.need_special_functions.then(|| {
+        let selectors = functions
+            .as_ref()
+            .expect("enabled on with_special_functions()")
+            .iter()
+            .map(|f| f.selector)
+            .collect();
+        let (special_functions, special_diagnostics) = special_functions(
+            args.code,
+            &selectors,
+            args.phase_gas_limit(AnalysisPhase::SpecialFunctions),
+        );
+        diagnostics.extend(special_diagnostics);
+        special_functions
+    });
+
+    let proxy = args.need_proxy.then(|| {
+        let selectors: Vec<_> = functions
+            .as_ref()
+            .expect("enabled on with_proxy()")
+            .iter()
+            .map(|f| f.selector)
+            .collect();
+        let (proxy, proxy_diagnostics) = crate::proxy::detect(
+            args.code,
+            &selectors,
+            args.phase_gas_limit(AnalysisPhase::Proxy),
+            args.tracer(AnalysisPhase::Proxy),
+        );
+        diagnostics.extend(proxy_diagnostics);
+        proxy
     });
 
     //TODO: filter fns by state_mutability if available
@@ -262,21 +690,460 @@ pub fn contract_info(args: ContractInfoArgs) -> Contract {
             .expect("enabled on with_storage()")
             .iter()
             .map(|f| (f.selector, f.bytecode_offset, f.arguments.as_ref().unwrap()));
-        contract_storage(args.code, fns, GAS_LIMIT)
+        contract_storage(
+            args.code,
+            fns,
+            args.phase_gas_limit(AnalysisPhase::Storage),
+            args.need_evidence,
+            args.tracer(AnalysisPhase::Storage),
+        )
     });
-    let (storage, transient_storage) = storage_analysis.map_or((None, None), |layouts| {
+    let (storage, transient_storage) = storage_analysis.map_or((None, None), |mut layouts| {
+        diagnostics.append(&mut layouts.fallback_diagnostics);
+        if let Some(fns) = functions.as_mut() {
+            for f in fns.iter_mut() {
+                if let Some(d) = layouts.diagnostics.remove(&f.selector) {
+                    f.diagnostics.extend(d);
+                }
+            }
+        }
         (Some(layouts.storage), Some(layouts.transient_storage))
     });
 
+    let events = args.need_events.then(|| {
+        let fns = functions
+            .as_ref()
+            .expect("enabled on with_events()")
+            .iter()
+            .map(|f| (f.selector, f.arguments.as_ref().unwrap()));
+        let mut found = contract_events(
+            args.code,
+            fns,
+            args.phase_gas_limit(AnalysisPhase::Events),
+            args.tracer(AnalysisPhase::Events),
+        );
+        diagnostics.append(&mut found.fallback_diagnostics);
+        if let Some(fns) = functions.as_mut() {
+            for f in fns.iter_mut() {
+                f.events = found.emitted.remove(&f.selector);
+                if let Some(d) = found.diagnostics.remove(&f.selector) {
+                    f.diagnostics.extend(d);
+                }
+            }
+        }
+        found.events
+    });
+
+    let errors = args.need_errors.then(|| {
+        let fns = functions
+            .as_ref()
+            .expect("enabled on with_errors()")
+            .iter()
+            .map(|f| (f.selector, f.arguments.as_ref().unwrap()));
+        let mut found = contract_errors(
+            args.code,
+            fns,
+            args.phase_gas_limit(AnalysisPhase::Errors),
+            args.tracer(AnalysisPhase::Errors),
+        );
+        diagnostics.append(&mut found.fallback_diagnostics);
+        if let Some(fns) = functions.as_mut() {
+            for f in fns.iter_mut() {
+                if let Some(d) = found.diagnostics.remove(&f.selector) {
+                    f.diagnostics.extend(d);
+                }
+            }
+        }
+        found.errors
+    });
+
+    if let Some(fns) = functions.as_mut() {
+        for f in fns.iter_mut() {
+            f.truncated = truncated_phases(&f.diagnostics);
+        }
+    }
+
     let disassembled = args.need_disassemble.then(|| disassemble(args.code));
 
     Contract {
         functions,
+        special_functions,
         storage,
         transient_storage,
+        events,
+        errors,
         disassembled,
         basic_blocks,
         control_flow_graph,
         metadata,
+        proxy: proxy.flatten(),
+        truncated: truncated_phases(&diagnostics),
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DiagnosticKind, Evidence};
+    use alloy_primitives::hex;
+
+    const CODE: &str = "6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256";
+
+    #[test]
+    fn test_default_gas_limits_are_not_truncated() {
+        let code = hex::decode(CODE).unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_arguments_validation()
+                .with_outputs()
+                .with_state_mutability()
+                .with_storage(),
+        );
+        assert!(info.truncated.is_empty());
+        for f in info.functions.unwrap() {
+            assert!(f.truncated.is_empty());
+            assert_eq!(f.outputs, Some(vec![]));
+            assert_eq!(f.arguments_validation, Some(ArgumentsValidation::Validated));
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let mask = "ff".repeat(20);
+        let codes = [
+            hex::decode(CODE).unwrap(),
+            hex::decode(format!(
+                "60003560e01c8063a9059cbb14601a57631122334414603d57005b\
+                 60043573{mask}165060243560010150005b00"
+            ))
+            .unwrap(),
+        ];
+        let analyze = |code: &[u8]| {
+            format!(
+                "{:?}",
+                contract_info(
+                    ContractInfoArgs::new(code)
+                        .with_arguments()
+                        .with_arguments_validation()
+                        .with_outputs()
+                        .with_state_mutability()
+                        .with_storage()
+                        .with_events()
+                        .with_errors()
+                        .with_evidence(),
+                )
+            )
+        };
+        // a single-threaded pool runs the per-function closures one after another
+        let sequential = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap();
+        let parallel = rayon::ThreadPoolBuilder::new()
+            .num_threads(4)
+            .build()
+            .unwrap();
+        for code in &codes {
+            let expected = sequential.install(|| analyze(code));
+            assert!(expected.contains("selector"));
+            assert_eq!(parallel.install(|| analyze(code)), expected);
+        }
+    }
+
+    #[test]
+    fn test_phase_gas_limit_truncation() {
+        let code = hex::decode(CODE).unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_arguments_gas_limit(100),
+        );
+        assert!(info.truncated.is_empty());
+        let fns = info.functions.unwrap();
+        assert_eq!(fns.len(), 2);
+        for f in fns {
+            assert_eq!(f.truncated, vec![AnalysisPhase::Arguments]);
+        }
+    }
+
+    #[test]
+    fn test_overall_gas_limit_truncation() {
+        let code = hex::decode(CODE).unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_selectors()
+                .with_gas_limit(10)
+                .with_selectors_gas_limit(0),
+        );
+        assert!(info.truncated.is_empty());
+        assert_eq!(info.functions.unwrap().len(), 2);
+
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_gas_limit(10)
+                .with_selectors(),
+        );
+        assert_eq!(info.truncated, vec![AnalysisPhase::Selectors]);
+        assert!(info.functions.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_opcode_diagnostics() {
+        // dispatches 0x11223344 to a body that starts with an undefined opcode at pc 0x11
+        let code = hex::decode("60003560e01c631122334414601057005b0c").unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_state_mutability(),
+        );
+        assert!(info.diagnostics.is_empty());
+        let fns = info.functions.unwrap();
+        assert_eq!(fns.len(), 1);
+        assert_eq!(fns[0].arguments, Some(vec![]));
+        assert!(fns[0].truncated.is_empty());
+        assert!(fns[0].diagnostics.contains(&Diagnostic {
+            phase: AnalysisPhase::Arguments,
+            pc: 0x11,
+            kind: DiagnosticKind::UnsupportedOpcode(0x0c),
+        }));
+        assert!(
+            fns[0]
+                .diagnostics
+                .iter()
+                .any(|d| d.phase == AnalysisPhase::StateMutability)
+        );
+    }
+
+    #[test]
+    fn test_signature_resolver() {
+        let mask = "ff".repeat(20);
+        // 0xa9059cbb(address,uint256) and 0x11223344()
+        let code = hex::decode(format!(
+            "60003560e01c8063a9059cbb14601a57631122334414603d57005b\
+             60043573{mask}165060243560010150005b00"
+        ))
+        .unwrap();
+        let db = crate::SignatureDb::from_reader(
+            "a9059cbb many_msg_babbage(bytes1)\ntransfer(address,uint256)".as_bytes(),
+        )
+        .unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_signature_resolver(&db),
+        );
+        let fns = info.functions.as_ref().unwrap();
+        assert_eq!(fns[1].selector, [0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(
+            fns[1].signatures,
+            Some(vec![
+                "transfer(address,uint256)".to_string(),
+                "many_msg_babbage(bytes1)".to_string()
+            ])
+        );
+        assert_eq!(fns[0].signatures, Some(vec![]));
+
+        let abi = info.abi();
+        assert_eq!(abi.functions["transfer"][0].selector(), fns[1].selector);
+        assert!(abi.functions.contains_key("sig_11223344"));
+    }
+
+    #[test]
+    fn test_minimal_proxy() {
+        let code = hex::decode(
+            "363d3d373d3d3d363d73bebebebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3",
+        )
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_selectors().with_proxy());
+        assert!(info.functions.unwrap().is_empty());
+        let proxy = info.proxy.unwrap();
+        assert_eq!(proxy.kind, crate::ProxyKind::Eip1167);
+        assert_eq!(
+            proxy.implementation,
+            Some(alloy_primitives::address!(
+                "bebebebebebebebebebebebebebebebebebebebe"
+            ))
+        );
+
+        let info = contract_info(ContractInfoArgs::new(&code).with_selectors());
+        assert!(info.proxy.is_none());
+    }
+
+    #[test]
+    fn test_storage_slot_proxy_writers() {
+        let slot = "360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc";
+        // upgradeTo(address) stores its argument into the EIP-1967 slot, anything else
+        // is delegated to the address loaded from it
+        let code = hex::decode(format!(
+            "5f3560e01c633659cfe61461003c57365f5f375f5f365f7f{slot}545af4005b6004357f{slot}5500"
+        ))
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_proxy());
+        let proxy = info.proxy.unwrap();
+        assert_eq!(proxy.kind, crate::ProxyKind::Eip1967);
+        assert_eq!(proxy.implementation, None);
+        assert_eq!(proxy.slot.map(hex::encode), Some(slot.to_string()));
+        assert_eq!(proxy.writers, vec![[0x36, 0x59, 0xcf, 0xe6]]);
+        assert!(info.diagnostics.is_empty());
+    }
+
+    #[test]
+    fn test_diamond_proxy() {
+        let pos = "c8fcad8db84d3cc18b4c41d551ea0ee66dd599cde068d998e57d5e09332c131c";
+        let mask = "ff".repeat(20);
+        // 0x1f931c1c stores calldata[36] to `facets[bytes4(calldata[4])]`, anything else
+        // is delegated to `facets[msg.sig]`
+        let code = hex::decode(format!(
+            "5f3560e01c631f931c1c1461006257\
+             5f5f365f5f3563ffffffff60e01b165f527f{pos}60205260405f205473{mask}165af400\
+             5b602435600435\
+             63ffffffff60e01b165f527f{pos}60205260405f205500"
+        ))
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_proxy());
+        let proxy = info.proxy.unwrap();
+        assert_eq!(proxy.kind, crate::ProxyKind::Diamond);
+        assert_eq!(proxy.slot.map(hex::encode), Some(pos.to_string()));
+        assert_eq!(proxy.writers, vec![[0x1f, 0x93, 0x1c, 0x1c]]);
+    }
+
+    #[test]
+    fn test_events() {
+        let transfer = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+        let received = "88a5966d370b9919b20f3e2c13ff65706f196a4e32cc2c12bf57088f88525874";
+        let mask = "ff".repeat(20);
+        // 0x11223344(address) emits `transfer` with msg.sender indexed and the argument
+        // as data, the fallback emits `received` without data
+        let code = hex::decode(format!(
+            "60003560e01c63112233441460345\
+             77f{received}5f5fa100\
+             5b60043573{mask}165f52337f{transfer}60205fa200"
+        ))
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_events());
+        let events = info.events.unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(hex::encode(events[0].topic), received);
+        assert_eq!(events[0].indexed, 0);
+        assert_eq!(events[0].data, vec![]);
+        assert_eq!(hex::encode(events[1].topic), transfer);
+        assert_eq!(events[1].indexed, 1);
+        assert_eq!(events[1].data, vec![DynSolType::Address]);
+
+        let fns = info.functions.unwrap();
+        assert_eq!(fns[0].arguments, Some(vec![DynSolType::Address]));
+        assert_eq!(fns[0].events, Some(vec![events[1].topic]));
+    }
+
+    #[test]
+    fn test_payload_length_overflow() {
+        let topic = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+        // mstore(0, 0x20); mstore(0x20, 0xffffffffffffffe1); log1(0, 0x40, topic); stop
+        let code = hex::decode(format!(
+            "60205f5267ffffffffffffffe160205260405f7f{topic}a100"
+        ))
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_events());
+        assert_eq!(info.events.unwrap().len(), 1);
+
+        // the same payload after a custom error selector
+        let code = hex::decode(
+            "638e4a23d660e01b5f526020600452\
+             67ffffffffffffffe160245260445ffd",
+        )
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_errors());
+        assert_eq!(info.errors.unwrap().len(), 1);
+
+        // mstore(0, 0xffffffffffffffe0); log1(0, 0x20, topic); stop
+        let code = hex::decode(format!("67ffffffffffffffe05f527f{topic}60205fa100")).unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_events());
+        assert_eq!(info.events.unwrap().len(), 1);
+
+        // the same word after a custom error selector
+        let code = hex::decode("638e4a23d660e01b5f5267ffffffffffffffe060045260245ffd").unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_errors());
+        assert_eq!(info.errors.unwrap().len(), 1);
+
+        // mstore(0xfffffffc, 0x11223344 << 224); revert(0xfffffffc, 0x24)
+        let code = hex::decode("631122334460e01b63fffffffc52602463fffffffcfd").unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_errors());
+        assert!(info.errors.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_errors() {
+        let mask = "ff".repeat(20);
+        // 0x11223344(address) reverts with Unauthorized(address) (0x8e4a23d6), the
+        // fallback with Panic(0x01)
+        let code = hex::decode(format!(
+            "60003560e01c631122334414602257\
+             634e487b7160e01b5f52600160045260245ffd\
+             5b60043573{mask}16638e4a23d660e01b5f5260045260245ffd"
+        ))
+        .unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_errors());
+        let errors = info.errors.unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].kind, crate::RevertKind::Custom);
+        assert_eq!(errors[0].selector, [0x8e, 0x4a, 0x23, 0xd6]);
+        assert_eq!(errors[0].arguments, vec![DynSolType::Address]);
+        assert_eq!(errors[0].functions, vec![[0x11, 0x22, 0x33, 0x44]]);
+        assert_eq!(errors[1].kind, crate::RevertKind::Panic);
+        assert_eq!(errors[1].panic_code, Some(1));
+        assert!(errors[1].functions.is_empty());
+    }
+
+    #[test]
+    fn test_evidence() {
+        let code = hex::decode(CODE).unwrap();
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_state_mutability()
+                .with_evidence(),
+        );
+        let fns = info.functions.unwrap();
+        let evidence = fns[0].evidence.as_ref().unwrap();
+        // uint32, address and uint224 are checked by comparing with their masked values
+        let arguments = evidence.arguments.as_ref().unwrap();
+        assert_eq!(arguments.len(), 3);
+        for e in arguments {
+            assert_eq!(e.last().unwrap().reason, "mask comparison");
+        }
+        // pure: only the callvalue check
+        assert_eq!(
+            evidence
+                .state_mutability
+                .as_ref()
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            vec![
+                "callvalue check: CALLVALUE at pc 0x5",
+                "callvalue check: JUMPI at pc 0xa",
+                "reverts on callvalue: REVERT at pc 0xd",
+            ]
+        );
+
+        // 0x11223344 increments slot 0
+        let code = hex::decode("60003560e01c631122334414601057005b5f546001015f5500").unwrap();
+        let info = contract_info(ContractInfoArgs::new(&code).with_storage().with_evidence());
+        let storage = info.storage.unwrap();
+        assert_eq!(storage.len(), 1);
+        assert_eq!(
+            storage[0].evidence,
+            Some(vec![
+                Evidence::new(0x12, crate::evm::op::SLOAD, "read"),
+                Evidence::new(0x17, crate::evm::op::SSTORE, "write")
+            ])
+        );
+
+        let info = contract_info(ContractInfoArgs::new(&code).with_storage());
+        assert_eq!(info.storage.unwrap()[0].evidence, None);
     }
 }
@@ -0,0 +1,34 @@
+use crate::evm::{U256, calldata::CallData, element::Element};
+use std::error;
+
+/// Empty calldata: constructors read their arguments from code instead
+pub(crate) struct CallDataImpl;
+
+impl<T> CallData<T> for CallDataImpl {
+    fn load32(&self, _offset: U256) -> Element<T> {
+        Element {
+            data: [0; 32],
+            label: None,
+        }
+    }
+
+    fn load(
+        &self,
+        _offset: U256,
+        size: U256,
+    ) -> Result<(Vec<u8>, Option<T>), Box<dyn error::Error>> {
+        let sz = u16::try_from(size)?;
+        if sz > 512 {
+            return Err("unsupported size".into());
+        }
+        Ok((vec![0; sz as usize], None))
+    }
+
+    fn selector(&self) -> [u8; 4] {
+        [0; 4]
+    }
+
+    fn len(&self) -> U256 {
+        U256::ZERO
+    }
+}
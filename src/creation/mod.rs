@@ -0,0 +1,496 @@
+//! Analysis of creation (init) code: runtime code location and constructor arguments.
+
+use crate::{
+    AnalysisPhase, ContractInfoArgs, DynSolType, DynSolValue, TraceSink,
+    arguments::constructor_argument_types,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{element::Element, op, vm::Vm},
+    trace::Tracer,
+};
+use std::fmt;
+
+mod calldata;
+pub(crate) use calldata::CallDataImpl;
+
+/// Runtime code and constructor arguments located in creation code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CreationInfo<'a> {
+    /// Runtime bytecode returned by the constructor, as stored in the creation code
+    ///
+    /// Immutable values are written by the constructor at deploy time, so their
+    /// positions hold placeholders here.
+    pub runtime: &'a [u8],
+
+    /// Offset of `runtime` within the creation code
+    pub runtime_offset: usize,
+
+    /// Trailing bytes read by the constructor as its arguments, or the bytes
+    /// following the runtime code if the constructor read none
+    pub constructor_args: &'a [u8],
+
+    /// Offset of `constructor_args` within the creation code
+    pub constructor_args_offset: usize,
+}
+
+impl<'a> CreationInfo<'a> {
+    /// Returns [`ContractInfoArgs`] for analyzing the runtime code with [`crate::contract_info`]
+    ///
+    /// The runtime analyses are configured on the returned builder; [`CreationArgs`] only
+    /// applies to the constructor runs.
+    pub fn contract_info_args(&self) -> ContractInfoArgs<'a> {
+        ContractInfoArgs::new(self.runtime)
+    }
+}
+
+/// Builder for configuring creation code analysis parameters
+///
+/// See [`creation_info`] for usage examples.
+#[derive(Clone, Default)]
+pub struct CreationArgs<'a> {
+    code: &'a [u8],
+    trace_sink: Option<&'a dyn TraceSink>,
+    gas_limit: u32,
+}
+
+impl<'a> CreationArgs<'a> {
+    /// Creates a new instance of creation code analysis configuration
+    ///
+    /// # Arguments
+    ///
+    /// * `code` - Creation code, optionally followed by ABI-encoded constructor arguments
+    pub fn new(code: &'a [u8]) -> Self {
+        CreationArgs {
+            code,
+            ..Default::default()
+        }
+    }
+
+    /// Records every step of the constructor runs into `sink` as
+    /// [`AnalysisPhase::Creation`], see [`crate::TraceRecorder`]
+    pub fn with_trace(mut self, sink: &'a dyn TraceSink) -> Self {
+        self.trace_sink = Some(sink);
+        self
+    }
+
+    /// Sets the gas limit of each constructor run (default: 1000000)
+    pub fn with_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+}
+
+/// Error returned when no constructor path returns code copied from the creation code
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CreationError {
+    /// Reasons why execution paths were aborted; empty if every path stopped or reverted
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl fmt::Display for CreationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "runtime code not found")?;
+        for diagnostic in &self.diagnostics {
+            write!(f, "; {diagnostic}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CreationError {}
+
+/// A CODECOPY into memory observed on the current execution path
+#[derive(Clone)]
+struct CodeCopy {
+    mem_off: usize,
+    src_off: usize,
+    size: usize,
+}
+
+struct Path<'a> {
+    vm: Vm<'a, (), CallDataImpl>,
+    copies: Vec<CodeCopy>,
+    depth: u32,
+}
+
+fn code_copy(args: &[Element<()>; 2], exargs: &[Element<()>]) -> Option<CodeCopy> {
+    Some(CodeCopy {
+        mem_off: usize::try_from(&args[0]).ok()?,
+        src_off: usize::try_from(exargs.first()?).ok()?,
+        size: usize::try_from(&args[1]).ok()?,
+    })
+}
+
+fn locate_runtime<'a>(
+    code: &'a [u8],
+    copies: &[CodeCopy],
+    args: &[Element<()>; 2],
+) -> Option<CreationInfo<'a>> {
+    let offset = usize::try_from(&args[0]).ok()?;
+    let size = usize::try_from(&args[1]).ok()?;
+    if size == 0 {
+        return None;
+    }
+
+    let copy = copies.iter().rev().find(|c| {
+        c.mem_off <= offset
+            && offset
+                .checked_add(size)
+                .zip(c.mem_off.checked_add(c.size))
+                .is_some_and(|(e, copy_end)| e <= copy_end)
+    })?;
+    let runtime_offset = copy.src_off.checked_add(offset - copy.mem_off)?;
+    if runtime_offset >= code.len() {
+        return None;
+    }
+    let runtime_end = code.len().min(runtime_offset.saturating_add(size));
+
+    // Solidity and Vyper copy the arguments with `CODECOPY(mem, initSize, CODESIZE - initSize)`
+    let constructor_args_offset = copies
+        .iter()
+        .filter(|c| c.src_off >= runtime_end && c.src_off.saturating_add(c.size) >= code.len())
+        .map(|c| c.src_off)
+        .min()
+        .unwrap_or(runtime_end)
+        .min(code.len());
+
+    Some(CreationInfo {
+        runtime: &code[runtime_offset..runtime_end],
+        runtime_offset,
+        constructor_args: &code[constructor_args_offset..],
+        constructor_args_offset,
+    })
+}
+
+/// Locates the runtime code and constructor arguments in creation code
+///
+/// Runs the constructor until it returns memory filled by CODECOPY. Paths that
+/// stop or revert are abandoned in favour of the other branch of earlier JUMPIs,
+/// so creation code without appended arguments is handled as well.
+///
+/// # Arguments
+///
+/// * `args` - A [`CreationArgs`] instance holding the creation code
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{CreationArgs, contract_info, creation_info};
+/// use alloy_primitives::hex;
+///
+/// // CODECOPY(0, 9, 1); RETURN(0, 1)
+/// let code = hex::decode("60018060095f395ff300").unwrap();
+/// let info = creation_info(CreationArgs::new(&code)).unwrap();
+/// assert_eq!(info.runtime, [0x00]);
+/// assert_eq!(info.runtime_offset, 9);
+///
+/// let runtime = contract_info(info.contract_info_args().with_selectors());
+/// assert!(runtime.functions.unwrap().is_empty());
+/// ```
+pub fn creation_info(args: CreationArgs<'_>) -> Result<CreationInfo<'_>, CreationError> {
+    locate(
+        args.code,
+        args.gas_limit,
+        Tracer::new(args.trace_sink, AnalysisPhase::Creation),
+    )
+}
+
+fn locate<'a>(
+    code: &'a [u8],
+    gas_limit: u32,
+    tracer: Tracer,
+) -> Result<CreationInfo<'a>, CreationError> {
+    const MAX_DEPTH: u32 = 8;
+
+    let real_gas_limit = if gas_limit == 0 {
+        1e6 as u32
+    } else {
+        gas_limit
+    };
+
+    let calldata = CallDataImpl;
+    let mut diagnostics = Vec::new();
+    let mut gas_used = 0;
+    let mut pending = vec![Path {
+        vm: Vm::new(code, &calldata),
+        copies: Vec::new(),
+        depth: 0,
+    }];
+
+    while let Some(mut path) = pending.pop() {
+        while !path.vm.stopped {
+            tracer.step(&path.vm);
+            let pc = path.vm.pc;
+            let ret = match path.vm.step() {
+                Ok(v) => v,
+                Err(e) => {
+                    let kind = DiagnosticKind::from_error(e.as_ref());
+                    record(&mut diagnostics, AnalysisPhase::Creation, pc, kind);
+                    break;
+                }
+            };
+            gas_used += ret.gas_used;
+            if gas_used > real_gas_limit {
+                record(
+                    &mut diagnostics,
+                    AnalysisPhase::Creation,
+                    pc,
+                    DiagnosticKind::GasLimit,
+                );
+                return Err(CreationError { diagnostics });
+            }
+
+            match ret.op {
+                op::CODECOPY => {
+                    if let Some(copy) = code_copy(&ret.args, &ret.exargs) {
+                        path.copies.push(copy);
+                    }
+                }
+                op::JUMPI => {
+                    let other_pc = usize::try_from(&ret.args[0]).expect("set to usize in vm.rs");
+                    if path.depth < MAX_DEPTH && other_pc != 0 {
+                        let mut vm = path.vm.fork();
+                        vm.pc = other_pc;
+                        vm.stopped = false;
+                        pending.push(Path {
+                            vm,
+                            copies: path.copies.clone(),
+                            depth: path.depth + 1,
+                        });
+                    }
+                }
+                op::RETURN => {
+                    if let Some(info) = locate_runtime(code, &path.copies, &ret.args) {
+                        return Ok(info);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Err(CreationError { diagnostics })
+}
+
+/// Constructor parameters inferred from creation code
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConstructorArguments {
+    /// Inferred parameter types
+    pub types: Vec<DynSolType>,
+
+    /// Appended constructor arguments decoded with `types`; `None` if they don't decode
+    pub values: Option<Vec<DynSolValue>>,
+
+    /// Reasons why the constructor run stopped before its arguments were decoded
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Infers constructor parameter types and decodes the appended arguments
+///
+/// The constructor is run with a zeroed arguments region in place of the
+/// appended arguments, and its reads from that region are analyzed like
+/// function calldata reads.
+///
+/// # Arguments
+///
+/// * `args` - A [`CreationArgs`] instance holding the creation code; its gas limit
+///   applies to each of the two constructor runs
+pub fn constructor_arguments(
+    args: CreationArgs<'_>,
+) -> Result<ConstructorArguments, CreationError> {
+    let code = args.code;
+    let real_gas_limit = if args.gas_limit == 0 {
+        1e6 as u32
+    } else {
+        args.gas_limit
+    };
+    let info = locate(
+        code,
+        real_gas_limit,
+        Tracer::new(args.trace_sink, AnalysisPhase::Creation),
+    )?;
+    let (types, diagnostics) = constructor_argument_types(
+        code,
+        info.constructor_args_offset,
+        real_gas_limit,
+        Tracer::new(args.trace_sink, AnalysisPhase::Creation),
+    );
+    let values = match DynSolType::Tuple(types.clone()).abi_decode_params(info.constructor_args) {
+        Ok(DynSolValue::Tuple(values)) => Some(values),
+        _ => None,
+    };
+    Ok(ConstructorArguments {
+        types,
+        values,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    const RUNTIME: &str = "6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256";
+
+    #[test]
+    fn test_runtime_with_constructor_args() {
+        let runtime = hex::decode(RUNTIME).unwrap();
+        let len = runtime.len();
+        let x = 0x18 + len;
+        // CODECOPY(0x80, X, CODESIZE - X); POP; CODECOPY(0, 0x18, len); RETURN(0, len)
+        let init = format!("61{x:04x}38038061{x:04x}6080395061{len:04x}806100185f395ff3");
+        let args = [0x11; 64];
+        let code = [hex::decode(init).unwrap(), runtime.clone(), args.to_vec()].concat();
+
+        let info = creation_info(CreationArgs::new(&code)).unwrap();
+        assert_eq!(info.runtime, runtime.as_slice());
+        assert_eq!(info.runtime_offset, 0x18);
+        assert_eq!(info.constructor_args, args.as_slice());
+        assert_eq!(info.constructor_args_offset, x);
+
+        let fns = crate::contract_info(info.contract_info_args().with_arguments())
+            .functions
+            .unwrap();
+        assert_eq!(fns.len(), 2);
+    }
+
+    #[test]
+    fn test_reverting_branch_is_skipped() {
+        let runtime = hex::decode(RUNTIME).unwrap();
+        let len = runtime.len();
+        // JUMPI(0x10, 1) jumps to REVERT, the fall-through path deploys
+        let init = format!("600160105761{len:04x}806100145f395ff35b5f5ffd");
+        let code = [hex::decode(init).unwrap(), runtime.clone()].concat();
+
+        let info = creation_info(CreationArgs::new(&code)).unwrap();
+        assert_eq!(info.runtime, runtime.as_slice());
+        assert_eq!(info.runtime_offset, 0x14);
+        assert!(info.constructor_args.is_empty());
+    }
+
+    #[test]
+    fn test_not_found() {
+        let err = creation_info(CreationArgs::new(&hex::decode("5f5ffd").unwrap())).unwrap_err();
+        assert!(err.diagnostics.is_empty());
+
+        let err = creation_info(CreationArgs::new(&hex::decode("600c56").unwrap())).unwrap_err();
+        assert_eq!(
+            err.diagnostics,
+            vec![Diagnostic {
+                phase: AnalysisPhase::Creation,
+                pc: 2,
+                kind: DiagnosticKind::InvalidJump,
+            }]
+        );
+
+        // CODECOPY(0, 0xffffffffffffffff, 0x20); RETURN(0x10, 1)
+        let code = hex::decode("602067ffffffffffffffff5f3960016010f3").unwrap();
+        assert!(creation_info(CreationArgs::new(&code)).is_err());
+
+        // CODECOPY(0, 9, 1); RETURN(0, 1) stopped by the gas limit at DUP1
+        let code = hex::decode("60018060095f395ff300").unwrap();
+        let err = creation_info(CreationArgs::new(&code).with_gas_limit(5)).unwrap_err();
+        assert_eq!(
+            err.diagnostics,
+            vec![Diagnostic {
+                phase: AnalysisPhase::Creation,
+                pc: 2,
+                kind: DiagnosticKind::GasLimit,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_constructor_arguments() {
+        let runtime = hex::decode(RUNTIME).unwrap();
+        let len = runtime.len();
+        let x = 0x39 + len;
+        // CODECOPY(0x80, X, CODESIZE - X); POP
+        // MLOAD(0x80) AND 0xff..ff (address); MLOAD(0xa0) AND 0xff (uint8)
+        // CODECOPY(0, 0x39, len); RETURN(0, len)
+        let mask = "ff".repeat(20);
+        let init = format!(
+            "61{x:04x}38038061{x:04x}60803950608051\
+             73{mask}165060a05160ff165061{len:04x}806100395f395ff3"
+        );
+        let mut args = [0u8; 64];
+        args[12..32].fill(0x11);
+        args[63] = 7;
+        let code = [hex::decode(init).unwrap(), runtime, args.to_vec()].concat();
+
+        let ctor = constructor_arguments(CreationArgs::new(&code)).unwrap();
+        assert_eq!(ctor.types, vec![DynSolType::Address, DynSolType::Uint(8)]);
+        assert!(ctor.diagnostics.is_empty());
+        assert_eq!(
+            ctor.values,
+            Some(vec![
+                DynSolValue::Address([0x11; 20].into()),
+                DynSolValue::Uint(alloy_primitives::U256::from(7), 8),
+            ])
+        );
+
+        // without appended arguments the types are still inferred
+        let ctor = constructor_arguments(CreationArgs::new(&code[..x])).unwrap();
+        assert_eq!(ctor.types, vec![DynSolType::Address, DynSolType::Uint(8)]);
+        assert_eq!(ctor.values, None);
+    }
+
+    #[test]
+    fn test_constructor_diagnostics() {
+        let runtime = hex::decode(RUNTIME).unwrap();
+        let len = runtime.len();
+        // JUMPI(0x10, 1) jumps to an undefined opcode, the fall-through path deploys
+        let init = format!("600160105761{len:04x}806100145f395ff35b0c0000");
+        let code = [hex::decode(init).unwrap(), runtime].concat();
+
+        let ctor = constructor_arguments(CreationArgs::new(&code)).unwrap();
+        assert_eq!(
+            ctor.diagnostics,
+            vec![Diagnostic {
+                phase: AnalysisPhase::Creation,
+                pc: 0x11,
+                kind: DiagnosticKind::UnsupportedOpcode(0x0c),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_constructor_bytes_argument() {
+        let runtime = hex::decode(RUNTIME).unwrap();
+        let len = runtime.len();
+        let x = 0x28 + len;
+        // CODECOPY(0x80, X, CODESIZE - X); POP
+        // ptr = 0x80 + MLOAD(0x80); MCOPY(0x400, ptr + 0x20, MLOAD(ptr))
+        // CODECOPY(0, 0x28, len); RETURN(0, len)
+        let init = format!(
+            "61{x:04x}38038061{x:04x}60803950608051608001805190602001610400\
+             5e61{len:04x}806100285f395ff3"
+        );
+        let args = hex::decode(
+            "0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             abcd000000000000000000000000000000000000000000000000000000000000",
+        )
+        .unwrap();
+        let code = [hex::decode(init).unwrap(), runtime, args].concat();
+
+        let ctor = constructor_arguments(CreationArgs::new(&code)).unwrap();
+        assert_eq!(ctor.types, vec![DynSolType::Bytes]);
+        assert_eq!(
+            ctor.values,
+            Some(vec![DynSolValue::Bytes(vec![0xab, 0xcd])])
+        );
+    }
+
+    #[test]
+    fn test_trace() {
+        // CODECOPY(0, 9, 1); RETURN(0, 1)
+        let code = hex::decode("60018060095f395ff300").unwrap();
+        let recorder = crate::TraceRecorder::with_phases([AnalysisPhase::Creation]);
+        creation_info(CreationArgs::new(&code).with_trace(&recorder)).unwrap();
+        let steps = recorder.into_steps();
+        assert_eq!(steps.len(), 7);
+        assert!(steps.iter().all(|s| s.phase == AnalysisPhase::Creation));
+        assert_eq!(steps.last().unwrap().opcode, op::RETURN);
+    }
+}
@@ -0,0 +1,189 @@
+//! Decoding of transaction input against the function arguments inferred from code.
+
+use crate::{
+    AnalysisPhase, Diagnostic, DynSolType, DynSolValue, Selector, arguments::function_arguments,
+    selectors::function_selectors, trace::Tracer,
+};
+use std::fmt;
+
+/// Function call decoded from transaction input, see [`decode_calldata`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedCalldata<'a> {
+    /// Called function selector, the first 4 bytes of the input
+    pub selector: Selector,
+
+    /// Inferred argument types of the function
+    pub arguments: Vec<DynSolType>,
+
+    /// Input decoded with `arguments`; `None` if it doesn't decode
+    pub values: Option<Vec<DynSolValue>>,
+
+    /// Input bytes past the encoding of `values`, or all bytes following the selector if
+    /// the input doesn't decode
+    ///
+    /// Empty if the arguments are not encoded canonically, e.g. with a gap before dynamic
+    /// data, as the end of the encoding is then unknown.
+    pub trailing: &'a [u8],
+
+    /// Reasons why argument inference stopped early
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// Error returned when the input doesn't call a function of the code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The input is shorter than a selector
+    NoSelector,
+    /// The selector is not dispatched by the code
+    UnknownSelector(Selector),
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSelector => write!(f, "input is shorter than a selector"),
+            Self::UnknownSelector(selector) => write!(
+                f,
+                "selector {} is not dispatched by the code",
+                alloy_primitives::hex::encode(selector)
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// Decodes a call to `code` with transaction input `input`
+///
+/// The argument types of the called function are inferred from the code, then the input
+/// is ABI-decoded with them.
+///
+/// # Arguments
+///
+/// * `code` - Runtime bytecode of the called contract
+/// * `input` - Transaction input: selector followed by ABI-encoded arguments
+/// * `gas_limit` - Maximum allowed gas usage of selector and argument extraction; set to
+///   `0` to use defaults
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{DynSolValue, decode_calldata};
+/// use alloy_primitives::{U256, hex};
+///
+/// let code = hex::decode("6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034578063b69ef8a8146044575b5f80fd5b6044603f3660046046565b505050565b005b5f805f606084860312156057575f80fd5b833563ffffffff811681146069575f80fd5b925060208401356001600160a01b03811681146083575f80fd5b915060408401356001600160e01b0381168114609d575f80fd5b80915050925092509256").unwrap();
+/// let input = hex::decode(format!("2125b65b{:064x}{:064x}{:064x}", 1, 2, 3)).unwrap();
+///
+/// let call = decode_calldata(&code, &input, 0).unwrap();
+/// let values = call.values.unwrap();
+/// assert_eq!(values[0], DynSolValue::Uint(U256::from(1), 32));
+/// assert!(call.trailing.is_empty());
+/// ```
+pub fn decode_calldata<'a>(
+    code: &[u8],
+    input: &'a [u8],
+    gas_limit: u32,
+) -> Result<DecodedCalldata<'a>, DecodeError> {
+    let (selector, data) = input
+        .split_first_chunk::<4>()
+        .ok_or(DecodeError::NoSelector)?;
+    let selectors = function_selectors(
+        code,
+        gas_limit,
+        None,
+        Tracer::disabled(AnalysisPhase::Selectors),
+    );
+    if !selectors.selectors.contains_key(selector) {
+        return Err(DecodeError::UnknownSelector(*selector));
+    }
+
+    let (arguments, diagnostics) = function_arguments(code, selector, gas_limit);
+    let (values, trailing) = match DynSolType::Tuple(arguments.clone()).abi_decode_params(data) {
+        Ok(value) => {
+            let encoded = value.abi_encode_params();
+            let trailing = data.strip_prefix(encoded.as_slice()).unwrap_or_default();
+            let values = value.as_fixed_seq().expect("decoded a tuple").to_vec();
+            (Some(values), trailing)
+        }
+        Err(_) => (None, data),
+    };
+    Ok(DecodedCalldata {
+        selector: *selector,
+        arguments,
+        values,
+        trailing,
+        diagnostics,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256, hex};
+
+    #[test]
+    fn test_decode_with_trailing_bytes() {
+        let mask = "ff".repeat(20);
+        // 0xa9059cbb(address,uint256)
+        let code = hex::decode(format!(
+            "60003560e01c63a9059cbb14601057005b\
+             60043573{mask}1650602435600101500000"
+        ))
+        .unwrap();
+        let to = "11".repeat(20);
+        let input = hex::decode(format!("a9059cbb{to:0>64}{:064x}cafe", 5)).unwrap();
+
+        let call = decode_calldata(&code, &input, 0).unwrap();
+        assert_eq!(
+            call.arguments,
+            vec![DynSolType::Address, DynSolType::Uint(256)]
+        );
+        assert_eq!(
+            call.values,
+            Some(vec![
+                DynSolValue::Address(Address::repeat_byte(0x11)),
+                DynSolValue::Uint(U256::from(5), 256),
+            ])
+        );
+        assert_eq!(call.trailing, [0xca, 0xfe]);
+
+        // truncated arguments
+        let call = decode_calldata(&code, &input[..40], 0).unwrap();
+        assert_eq!(call.values, None);
+        assert_eq!(call.trailing.len(), 36);
+
+        assert_eq!(
+            decode_calldata(&code, &input[..3], 0),
+            Err(DecodeError::NoSelector)
+        );
+        assert_eq!(
+            decode_calldata(&code, &hex::decode("11223344").unwrap(), 0),
+            Err(DecodeError::UnknownSelector([0x11, 0x22, 0x33, 0x44]))
+        );
+    }
+
+    #[test]
+    fn test_decode_non_canonical() {
+        // 0xaabbccdd(bytes)
+        let code = hex::decode(
+            "60003560e01c63aabbccdd1460105700\
+             5b6004356004018035906020015f3700",
+        )
+        .unwrap();
+        let data = format!("{:064x}{:0<64}", 4, "deadbeef");
+        let expected = Some(vec![DynSolValue::Bytes(vec![0xde, 0xad, 0xbe, 0xef])]);
+
+        let input = hex::decode(format!("aabbccdd{:064x}{data}cafe", 0x20)).unwrap();
+        let call = decode_calldata(&code, &input, 0).unwrap();
+        assert_eq!(call.arguments, vec![DynSolType::Bytes]);
+        assert_eq!(call.values, expected);
+        assert_eq!(call.trailing, [0xca, 0xfe]);
+
+        // a word between the offset and the bytes: the encoding doesn't end where the
+        // canonical one would
+        let input = hex::decode(format!("aabbccdd{:064x}{}{data}", 0x40, "11".repeat(32))).unwrap();
+        let call = decode_calldata(&code, &input, 0).unwrap();
+        assert_eq!(call.values, expected);
+        assert!(call.trailing.is_empty());
+    }
+}
@@ -0,0 +1,173 @@
+//! Reasons why a VM-driven analysis pass stopped before finishing.
+
+use crate::{
+    AnalysisPhase,
+    evm::{op, stack::IndexError, vm::UnsupportedOpError},
+};
+use std::fmt;
+
+/// Describes what stopped the VM during an analysis pass.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(tag = "type", content = "value", rename_all = "camelCase")
+)]
+pub enum DiagnosticKind {
+    /// The opcode is not supported by the VM.
+    UnsupportedOpcode(u8),
+    /// A JUMP or JUMPI targeted an offset that is not a JUMPDEST.
+    InvalidJump,
+    /// An opcode needed more stack elements than were available.
+    StackUnderflow,
+    /// The gas limit of the pass was reached.
+    GasLimit,
+    /// Any other VM error, e.g. an out-of-range memory offset.
+    Error(String),
+}
+
+impl DiagnosticKind {
+    pub(crate) fn from_error(err: &(dyn std::error::Error + 'static)) -> Self {
+        if let Some(e) = err.downcast_ref::<UnsupportedOpError>() {
+            if matches!(e.op, op::JUMP | op::JUMPI) {
+                Self::InvalidJump
+            } else {
+                Self::UnsupportedOpcode(e.op)
+            }
+        } else if err.is::<IndexError>() {
+            Self::StackUnderflow
+        } else {
+            Self::Error(err.to_string())
+        }
+    }
+}
+
+impl fmt::Display for DiagnosticKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedOpcode(opcode) => write!(
+                f,
+                "unsupported opcode 0x{opcode:02x} ({})",
+                op::info(*opcode).name
+            ),
+            Self::InvalidJump => write!(f, "invalid jump destination"),
+            Self::StackUnderflow => write!(f, "stack underflow"),
+            Self::GasLimit => write!(f, "gas limit reached"),
+            Self::Error(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+/// Records an early stop of an analysis pass.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Diagnostic {
+    /// Analysis pass that was stopped
+    pub phase: AnalysisPhase,
+
+    /// Bytecode offset of the opcode being executed when the pass stopped
+    pub pc: usize,
+
+    /// What stopped the pass
+    pub kind: DiagnosticKind,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} at pc 0x{:x}",
+            self.phase.as_str(),
+            self.kind,
+            self.pc
+        )
+    }
+}
+
+/// Appends a diagnostic unless an identical one was already recorded.
+///
+/// Forked executions tend to hit the same failing opcode many times.
+pub(crate) fn record(
+    diagnostics: &mut Vec<Diagnostic>,
+    phase: AnalysisPhase,
+    pc: usize,
+    kind: DiagnosticKind,
+) {
+    let diagnostic = Diagnostic { phase, pc, kind };
+    if !diagnostics.contains(&diagnostic) {
+        diagnostics.push(diagnostic);
+    }
+}
+
+/// Returns the distinct phases that reached their gas limit.
+pub(crate) fn truncated_phases(diagnostics: &[Diagnostic]) -> Vec<AnalysisPhase> {
+    let mut phases: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.kind == DiagnosticKind::GasLimit)
+        .map(|d| d.phase)
+        .collect();
+    phases.sort();
+    phases.dedup();
+    phases
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_from_error() {
+        let err: Box<dyn std::error::Error> = UnsupportedOpError { op: 0xfe }.into();
+        assert_eq!(
+            DiagnosticKind::from_error(err.as_ref()),
+            DiagnosticKind::UnsupportedOpcode(0xfe)
+        );
+
+        let err: Box<dyn std::error::Error> = UnsupportedOpError { op: op::JUMP }.into();
+        assert_eq!(
+            DiagnosticKind::from_error(err.as_ref()),
+            DiagnosticKind::InvalidJump
+        );
+
+        let err: Box<dyn std::error::Error> = IndexError.into();
+        assert_eq!(
+            DiagnosticKind::from_error(err.as_ref()),
+            DiagnosticKind::StackUnderflow
+        );
+
+        let err: Box<dyn std::error::Error> = "too large".into();
+        assert_eq!(
+            DiagnosticKind::from_error(err.as_ref()),
+            DiagnosticKind::Error("too large".to_string())
+        );
+    }
+
+    #[test]
+    fn test_record_deduplicates() {
+        let mut diagnostics = Vec::new();
+        record(
+            &mut diagnostics,
+            AnalysisPhase::Storage,
+            5,
+            DiagnosticKind::GasLimit,
+        );
+        record(
+            &mut diagnostics,
+            AnalysisPhase::Storage,
+            5,
+            DiagnosticKind::GasLimit,
+        );
+        record(
+            &mut diagnostics,
+            AnalysisPhase::Storage,
+            7,
+            DiagnosticKind::GasLimit,
+        );
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(truncated_phases(&diagnostics), vec![AnalysisPhase::Storage]);
+        assert_eq!(
+            diagnostics[0].to_string(),
+            "storage: gas limit reached at pc 0x5"
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use super::{Call, Execution, ExitReason, Host, frame::CodeOffset, run};
+use crate::evm::{memory::MemoryChunk, op};
+
+/// Runtime code bytes written by the constructor rather than copied from the creation code
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Immutable {
+    /// Offset in the runtime code
+    pub offset: usize,
+
+    /// Written bytes
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::bytes"))]
+    pub value: Vec<u8>,
+
+    /// Offset in the creation code of the bytes copied unchanged into this position;
+    /// `None` for computed values
+    ///
+    /// Values passed through an AND mask or SIGNEXTEND keep their source when these
+    /// leave them unchanged, as for a canonically encoded `address` or `int8`.
+    ///
+    /// For values taken from the constructor arguments, subtracting
+    /// [`crate::CreationInfo::constructor_args_offset`] gives their offset in the arguments.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub source: Option<usize>,
+}
+
+/// Result of [`simulate_deployment`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Deployment {
+    /// Execution of the creation code
+    ///
+    /// `return_data` holds the runtime code if the constructor returned, and
+    /// `storage_diff` the storage it initialized.
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub execution: Execution,
+
+    /// Positions of the runtime code patched by the constructor, sorted by offset
+    ///
+    /// Contiguous bytes with a common source are reported as one entry. Empty if the
+    /// runtime code does not start with bytes copied from the creation code.
+    pub immutables: Vec<Immutable>,
+}
+
+impl Deployment {
+    /// Returns the deployed runtime code, empty if the constructor did not return
+    pub fn runtime(&self) -> &[u8] {
+        if self.execution.exit == ExitReason::Return {
+            &self.execution.return_data
+        } else {
+            &[]
+        }
+    }
+}
+
+/// Finds the runtime bytes not at their position in the code copied by the constructor
+fn immutables(runtime: &[u8], origins: &[MemoryChunk<CodeOffset>]) -> Vec<Immutable> {
+    let mut code_offsets = vec![None; runtime.len()];
+    for chunk in origins {
+        let start = chunk.src_label + chunk.src_range.start;
+        for (i, pos) in chunk.dst_range.clone().enumerate() {
+            code_offsets[pos] = Some(start + i);
+        }
+    }
+    let Some(&Some(base)) = code_offsets.first() else {
+        return Vec::new();
+    };
+
+    let mut found: Vec<Immutable> = Vec::new();
+    for (pos, source) in code_offsets.into_iter().enumerate() {
+        if source == Some(base + pos) {
+            continue;
+        }
+        match found.last_mut() {
+            Some(last)
+                if last.offset + last.value.len() == pos
+                    && last.source.map(|s| s + last.value.len()) == source =>
+            {
+                last.value.push(runtime[pos]);
+            }
+            _ => found.push(Immutable {
+                offset: pos,
+                value: vec![runtime[pos]],
+                source,
+            }),
+        }
+    }
+    found
+}
+
+/// Executes creation code against the state of `host`, as a contract creation of
+/// `call.to` by `call.caller`
+///
+/// `call.input` is the creation code, followed by the ABI-encoded constructor arguments
+/// as in a deployment transaction. The creation fails with [`ExitReason::Error`] if
+/// `call.to` already has code. The execution follows [`crate::simulate_call`], and
+/// additionally tracks which runtime bytes were copied from the creation code to locate
+/// the immutable values the constructor wrote into the runtime code.
+///
+/// ```
+/// use evmole::{Call, StateHost, simulate_deployment};
+/// use alloy_primitives::{Address, U256, hex};
+///
+/// // constructor(uint256 x): sstore(0, x), returns a runtime code of `PUSH32 x; ...`
+/// let creation_code = hex::decode(
+///     "6020602038035f395f51805f556027601c60203960215260276020f3\
+///      7f00000000000000000000000000000000000000000000000000000000000000005f5260205ff3",
+/// )
+/// .unwrap();
+/// let args = U256::from(42).to_be_bytes_vec();
+/// let call = Call::new(Address::repeat_byte(0xcc), [creation_code, args.clone()].concat());
+///
+/// let r = simulate_deployment(&StateHost::new(), &call);
+/// assert_eq!(r.runtime()[1..33], args[..]);
+/// assert_eq!(r.execution.storage_diff[0].current, U256::from(42));
+/// assert_eq!((r.immutables[0].offset, r.immutables[0].source), (1, Some(67)));
+/// ```
+pub fn simulate_deployment<H: Host + ?Sized>(host: &H, call: &Call) -> Deployment {
+    let (execution, origins) = run(host, op::CREATE, call);
+    let immutables = if execution.exit == ExitReason::Return {
+        immutables(&execution.return_data, &origins)
+    } else {
+        Vec::new()
+    };
+    Deployment {
+        execution,
+        immutables,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Account, BlockEnv, StateHost, evm::U256, simulate_call};
+    use alloy_primitives::{Address, hex};
+
+    const CONTRACT: Address = Address::repeat_byte(0xcc);
+
+    #[test]
+    fn test_argument_and_computed_immutables() {
+        // constructor(uint256 x): sstore(0, x), then returns `return(x + timestamp)` with
+        // x and the deployment timestamp patched into its two PUSH32
+        let code = hex::decode(format!(
+            "6020602038035f395f51805f55604960206020396021524260425260496020f3\
+             7f{0}7f{0}015f5260205ff3",
+            "00".repeat(32)
+        ))
+        .unwrap();
+        let args = U256::from(42).to_be_bytes_vec();
+        let host = StateHost::new().with_block(BlockEnv {
+            timestamp: 1000,
+            ..Default::default()
+        });
+
+        let r = simulate_deployment(
+            &host,
+            &Call::new(CONTRACT, [code.clone(), args.clone()].concat()),
+        );
+        assert_eq!(r.execution.exit, ExitReason::Return);
+        assert_eq!(r.runtime().len(), 73);
+        assert_eq!(
+            r.immutables,
+            vec![
+                Immutable {
+                    offset: 1,
+                    value: args,
+                    source: Some(code.len()),
+                },
+                Immutable {
+                    offset: 34,
+                    value: U256::from(1000).to_be_bytes_vec(),
+                    source: None,
+                },
+            ]
+        );
+        assert_eq!(r.execution.storage_diff.len(), 1);
+        assert_eq!(r.execution.storage_diff[0].address, CONTRACT);
+        assert_eq!(r.execution.storage_diff[0].current, U256::from(42));
+
+        let host = host.with_account(
+            CONTRACT,
+            Account {
+                code: r.runtime().to_vec(),
+                ..Default::default()
+            },
+        );
+        let r = simulate_call(&host, &Call::new(CONTRACT, []));
+        assert_eq!(r.return_data, U256::from(1042).to_be_bytes_vec());
+
+        // CONTRACT already has code
+        let r = simulate_deployment(&host, &Call::new(CONTRACT, code));
+        assert_eq!(r.execution.exit, ExitReason::Error);
+        assert!(r.execution.storage_diff.is_empty());
+
+        // reverting constructor
+        let r = simulate_deployment(
+            &StateHost::new(),
+            &Call::new(CONTRACT, hex::decode("5f5ffd").unwrap()),
+        );
+        assert_eq!(r.execution.exit, ExitReason::Revert);
+        assert!(r.runtime().is_empty() && r.immutables.is_empty());
+    }
+
+    #[test]
+    fn test_address_immutable() {
+        // constructor(address a): returns `return(a)` with `a & (2**160 - 1)` patched into
+        // its PUSH32
+        let code = hex::decode(format!(
+            "6020602038035f395f5173{}166027602f6020396021526027\
+             6020f37f{}5f5260205ff3",
+            "ff".repeat(20),
+            "00".repeat(32)
+        ))
+        .unwrap();
+        let mut arg = [0; 32];
+        arg[12..].fill(0x11);
+
+        let r = simulate_deployment(
+            &StateHost::new(),
+            &Call::new(CONTRACT, [code.as_slice(), &arg].concat()),
+        );
+        assert_eq!(r.execution.exit, ExitReason::Return);
+        assert_eq!(
+            r.immutables,
+            vec![Immutable {
+                offset: 1,
+                value: arg.to_vec(),
+                source: Some(code.len()),
+            }]
+        );
+
+        // dirty high bits are cleared by the mask, the value is no longer a copy
+        arg[0] = 0xff;
+        let r = simulate_deployment(
+            &StateHost::new(),
+            &Call::new(CONTRACT, [code.as_slice(), &arg].concat()),
+        );
+        assert_eq!(r.immutables.len(), 1);
+        assert_eq!(r.immutables[0].source, None);
+    }
+}
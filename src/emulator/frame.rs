@@ -0,0 +1,703 @@
+//! Execution of call frames on the [`Vm`], with the values it stubs read from the [`Host`].
+
+use super::{BlockEnv, Call, ExitReason, Host, Log, StorageChange};
+use crate::evm::{
+    U256,
+    calldata::CallData,
+    element::Element,
+    inspector::Inspector,
+    memory::MemoryChunk,
+    op,
+    stack::IndexError,
+    vm::{StepResult, Vm},
+};
+use alloy_primitives::{Address, keccak256};
+use std::{collections::BTreeMap, error};
+
+/// Maximum call depth
+///
+/// Lower than the 1024 of the EVM: each nested frame is executed recursively, and calls
+/// past this depth fail the way calls past 1024 do, so that the deepest call chain fits in
+/// a 2 MiB thread stack.
+const MAX_DEPTH: usize = 128;
+
+/// Maximum size of the memory ranges and calldata read or written at once
+const MAX_RANGE: u32 = 1 << 20;
+
+/// Gas added to calls transferring value
+const CALL_STIPEND: u32 = 2300;
+
+/// Label of values copied unchanged from the executed code: the offset of their first byte
+pub(super) type CodeOffset = usize;
+
+/// Parts of a memory range copied unchanged from the executed code
+pub(super) type Origins = Vec<MemoryChunk<CodeOffset>>;
+
+/// Returns `size` bytes of `data` from `offset`, zero-padded
+fn padded(data: &[u8], offset: U256, size: usize) -> Vec<u8> {
+    let mut out = vec![0; size];
+    if let Ok(off) = usize::try_from(offset)
+        && off < data.len()
+    {
+        let n = (data.len() - off).min(size);
+        out[..n].copy_from_slice(&data[off..off + n]);
+    }
+    out
+}
+
+fn address(el: &Element<CodeOffset>) -> Address {
+    Address::from_slice(&el.data[12..])
+}
+
+fn address_word(address: Address) -> U256 {
+    U256::from_be_slice(address.as_slice())
+}
+
+/// Calldata of a frame
+pub(super) struct Input<'a>(&'a [u8]);
+
+impl CallData<CodeOffset> for Input<'_> {
+    fn load32(&self, offset: U256) -> Element<CodeOffset> {
+        Element {
+            data: padded(self.0, offset, 32).try_into().expect("32 bytes"),
+            label: None,
+        }
+    }
+
+    fn load(
+        &self,
+        offset: U256,
+        size: U256,
+    ) -> Result<(Vec<u8>, Option<CodeOffset>), Box<dyn error::Error>> {
+        let size = u32::try_from(size)?;
+        if size > MAX_RANGE {
+            return Err("calldata copy size too large".into());
+        }
+        Ok((padded(self.0, offset, size as usize), None))
+    }
+
+    fn len(&self) -> U256 {
+        U256::from(self.0.len())
+    }
+
+    fn selector(&self) -> [u8; 4] {
+        padded(self.0, U256::ZERO, 4).try_into().expect("4 bytes")
+    }
+}
+
+type FrameVm<'v, 'i> = Vm<'v, CodeOffset, Input<'i>>;
+
+/// Value overwritten by a state change, restored when the call making it fails
+enum Change {
+    Storage((Address, U256), Option<U256>),
+    Transient((Address, U256), Option<U256>),
+    Balance(Address, Option<U256>),
+}
+
+/// Position in the [`Journal`] a failed call reverts to
+#[derive(Clone, Copy)]
+struct Checkpoint {
+    changes: usize,
+    logs: usize,
+}
+
+/// State changes made so far, with the values they overwrote
+#[derive(Default)]
+pub(super) struct Journal {
+    storage: BTreeMap<(Address, U256), U256>,
+    transient: BTreeMap<(Address, U256), U256>,
+    balances: BTreeMap<Address, U256>,
+    changes: Vec<Change>,
+    pub logs: Vec<Log>,
+}
+
+impl Journal {
+    fn set_storage(&mut self, key: (Address, U256), val: U256) {
+        let prev = self.storage.insert(key, val);
+        self.changes.push(Change::Storage(key, prev));
+    }
+
+    fn set_transient(&mut self, key: (Address, U256), val: U256) {
+        let prev = self.transient.insert(key, val);
+        self.changes.push(Change::Transient(key, prev));
+    }
+
+    fn set_balance(&mut self, address: Address, val: U256) {
+        let prev = self.balances.insert(address, val);
+        self.changes.push(Change::Balance(address, prev));
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            changes: self.changes.len(),
+            logs: self.logs.len(),
+        }
+    }
+
+    /// Undoes the changes made since `checkpoint`, newest first
+    fn revert(&mut self, checkpoint: Checkpoint) {
+        fn restore<K: Ord, V>(map: &mut BTreeMap<K, V>, key: K, prev: Option<V>) {
+            match prev {
+                Some(val) => map.insert(key, val),
+                None => map.remove(&key),
+            };
+        }
+        for change in self.changes.drain(checkpoint.changes..).rev() {
+            match change {
+                Change::Storage(key, prev) => restore(&mut self.storage, key, prev),
+                Change::Transient(key, prev) => restore(&mut self.transient, key, prev),
+                Change::Balance(address, prev) => restore(&mut self.balances, address, prev),
+            }
+        }
+        self.logs.truncate(checkpoint.logs);
+    }
+}
+
+pub(super) struct State<'h, H: ?Sized> {
+    host: &'h H,
+    block: BlockEnv,
+    origin: Address,
+    pub journal: Journal,
+}
+
+impl<'h, H: Host + ?Sized> State<'h, H> {
+    pub fn new(host: &'h H, origin: Address) -> Self {
+        Self {
+            host,
+            block: host.block(),
+            origin,
+            journal: Journal::default(),
+        }
+    }
+
+    fn sload(&self, address: Address, slot: U256) -> U256 {
+        match self.journal.storage.get(&(address, slot)) {
+            Some(val) => *val,
+            None => self.host.storage(address, slot),
+        }
+    }
+
+    fn balance(&self, address: Address) -> U256 {
+        match self.journal.balances.get(&address) {
+            Some(val) => *val,
+            None => self.host.balance(address),
+        }
+    }
+
+    /// Moves `value` wei, returns false if `from` does not hold them
+    fn transfer(&mut self, from: Address, to: Address, value: U256) -> bool {
+        let balance = self.balance(from);
+        if balance < value {
+            return false;
+        }
+        if from != to && !value.is_zero() {
+            self.journal.set_balance(from, balance - value);
+            let to_balance = self.balance(to).saturating_add(value);
+            self.journal.set_balance(to, to_balance);
+        }
+        true
+    }
+
+    /// Returns the written slots holding a value different from the host's
+    pub fn storage_diff(&self) -> Vec<StorageChange> {
+        self.journal
+            .storage
+            .iter()
+            .filter_map(|(&(address, slot), &current)| {
+                let original = self.host.storage(address, slot);
+                (original != current).then_some(StorageChange {
+                    address,
+                    slot,
+                    original,
+                    current,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Execution context of a frame
+pub(super) struct Context {
+    /// Account whose storage and balance are used, returned by ADDRESS
+    pub address: Address,
+    pub caller: Address,
+    pub value: U256,
+    pub is_static: bool,
+    pub depth: usize,
+}
+
+pub(super) struct Outcome {
+    pub exit: ExitReason,
+    pub error: Option<String>,
+    pub output: Vec<u8>,
+    /// Parts of `output` copied unchanged from the code
+    pub output_origins: Origins,
+    pub gas_used: u32,
+}
+
+impl Outcome {
+    fn failed(error: &str) -> Self {
+        Self {
+            exit: ExitReason::Error,
+            error: Some(error.into()),
+            output: Vec::new(),
+            output_origins: Vec::new(),
+            gas_used: 0,
+        }
+    }
+}
+
+/// Executes the code of `code_address` in `ctx` as a call of type `kind`, reverting the
+/// state changes if it fails
+///
+/// For CREATE, `input` is executed as the creation code, without calldata.
+pub(super) fn message_call<H: Host + ?Sized>(
+    state: &mut State<H>,
+    kind: op::OpCode,
+    ctx: Context,
+    code_address: Address,
+    input: &[u8],
+    gas_limit: u32,
+) -> Outcome {
+    if ctx.depth > MAX_DEPTH {
+        return Outcome::failed("call depth limit reached");
+    }
+    if kind == op::CREATE && !state.host.code(ctx.address).is_empty() {
+        return Outcome::failed("contract address collision");
+    }
+    let checkpoint = state.journal.checkpoint();
+    let transfers = matches!(kind, op::CALL | op::CALLCODE | op::CREATE);
+    if transfers && !state.transfer(ctx.caller, ctx.address, ctx.value) {
+        return Outcome::failed("insufficient balance for transfer");
+    }
+
+    let outcome = if kind == op::CREATE {
+        execute(state, ctx, input, &[], gas_limit)
+    } else if ctx.depth > 0
+        && (kind == op::CALL || kind == op::STATICCALL)
+        && let Some(result) = state.host.call(&Call {
+            caller: ctx.caller,
+            to: code_address,
+            value: ctx.value,
+            input: input.to_vec(),
+            gas_limit,
+        })
+    {
+        Outcome {
+            exit: if result.success {
+                ExitReason::Return
+            } else {
+                ExitReason::Revert
+            },
+            error: None,
+            output: result.output,
+            output_origins: Vec::new(),
+            gas_used: 0,
+        }
+    } else {
+        let code = state.host.code(code_address);
+        execute(state, ctx, &code, input, gas_limit)
+    };
+
+    if !outcome.exit.is_success() {
+        state.journal.revert(checkpoint);
+    }
+    outcome
+}
+
+fn execute<H: Host + ?Sized>(
+    state: &mut State<H>,
+    ctx: Context,
+    code: &[u8],
+    input: &[u8],
+    gas_limit: u32,
+) -> Outcome {
+    let calldata = Input(input);
+    let mut vm = Vm::new(code, &calldata);
+    let mut frame = Frame {
+        state,
+        ctx,
+        gas_limit,
+        gas_used: 0,
+        return_data: Vec::new(),
+        exit: None,
+        output: Vec::new(),
+        output_origins: Vec::new(),
+    };
+    let (exit, error) = match vm.run(&mut frame, u32::MAX) {
+        Err(err) => (ExitReason::Error, Some(err.to_string())),
+        Ok(_) => frame.exit.take().unwrap_or((ExitReason::Stop, None)),
+    };
+    Outcome {
+        exit,
+        error,
+        output: frame.output,
+        output_origins: frame.output_origins,
+        gas_used: match exit {
+            ExitReason::OutOfGas | ExitReason::Error => gas_limit,
+            _ => frame.gas_used,
+        },
+    }
+}
+
+/// [`Inspector`] replacing the values stubbed by the [`Vm`] with the ones of the host
+struct Frame<'s, 'h, H: ?Sized> {
+    state: &'s mut State<'h, H>,
+    ctx: Context,
+    gas_limit: u32,
+    gas_used: u32,
+    /// Output of the last call
+    return_data: Vec<u8>,
+    exit: Option<(ExitReason, Option<String>)>,
+    /// Data passed to RETURN or REVERT
+    output: Vec<u8>,
+    output_origins: Origins,
+}
+
+impl<H: Host + ?Sized> Frame<'_, '_, H> {
+    fn stop(&mut self, vm: &mut FrameVm<'_, '_>, exit: ExitReason, error: Option<String>) {
+        self.exit = Some((exit, error));
+        vm.stopped = true;
+    }
+
+    fn check_static(&self, op: op::OpCode) -> Result<(), String> {
+        if self.ctx.is_static {
+            Err(format!("{} in a static call", op::info(op).name))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn read(
+        vm: &FrameVm<'_, '_>,
+        offset: &Element<CodeOffset>,
+        size: &Element<CodeOffset>,
+    ) -> Result<Vec<u8>, Box<dyn error::Error>> {
+        Ok(Self::read_with_origins(vm, offset, size)?.0)
+    }
+
+    fn read_with_origins(
+        vm: &FrameVm<'_, '_>,
+        offset: &Element<CodeOffset>,
+        size: &Element<CodeOffset>,
+    ) -> Result<(Vec<u8>, Origins), Box<dyn error::Error>> {
+        let size: u32 = size.try_into()?;
+        if size == 0 {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        if size > MAX_RANGE {
+            return Err("memory range too large".into());
+        }
+        let (data, chunks) = vm.memory.load(offset.try_into()?, size);
+        Ok((data, chunks.chunks))
+    }
+
+    fn write(
+        vm: &mut FrameVm<'_, '_>,
+        offset: &Element<CodeOffset>,
+        data: Vec<u8>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        if !data.is_empty() {
+            if data.len() > MAX_RANGE as usize {
+                return Err("memory range too large".into());
+            }
+            vm.memory.store(offset.try_into()?, data, None);
+        }
+        Ok(())
+    }
+
+    fn call(
+        &mut self,
+        vm: &mut FrameVm<'_, '_>,
+        ret: &StepResult<CodeOffset>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let [gas, args_offset, args_size, ret_offset, ret_size] = ret.exargs.as_slice() else {
+            unreachable!("calls have 5 extra arguments")
+        };
+        let to = address(&ret.args[0]);
+        let value: U256 = (&ret.args[1]).into();
+        if ret.op == op::CALL && !value.is_zero() {
+            self.check_static(ret.op)?;
+        }
+        let input = Self::read(vm, args_offset, args_size)?;
+
+        let remaining = self.gas_limit - self.gas_used;
+        let mut gas_limit =
+            (remaining - remaining / 64).min(U256::from_be_bytes(gas.data).saturating_to());
+        let stipend = if value.is_zero() { 0 } else { CALL_STIPEND };
+        gas_limit = gas_limit.saturating_add(stipend);
+
+        let parent = &self.ctx;
+        let depth = parent.depth + 1;
+        let ctx = match ret.op {
+            op::CALL => Context {
+                address: to,
+                caller: parent.address,
+                value,
+                is_static: parent.is_static,
+                depth,
+            },
+            op::CALLCODE => Context {
+                address: parent.address,
+                caller: parent.address,
+                value,
+                is_static: parent.is_static,
+                depth,
+            },
+            op::DELEGATECALL => Context {
+                address: parent.address,
+                caller: parent.caller,
+                value: parent.value,
+                is_static: parent.is_static,
+                depth,
+            },
+            _ => Context {
+                address: to,
+                caller: parent.address,
+                value: U256::ZERO,
+                is_static: true,
+                depth,
+            },
+        };
+
+        let outcome = message_call(self.state, ret.op, ctx, to, &input, gas_limit);
+        self.gas_used = self
+            .gas_used
+            .saturating_add(outcome.gas_used.saturating_sub(stipend));
+        vm.stack.peek_mut()?.data = U256::from(outcome.exit.is_success()).to_be_bytes();
+
+        let ret_size: usize = ret_size.try_into()?;
+        let n = ret_size.min(outcome.output.len());
+        Self::write(vm, ret_offset, outcome.output[..n].to_vec())?;
+        self.return_data = outcome.output;
+        Ok(())
+    }
+
+    /// Applies the effects of the opcode the [`Vm`] only stubs
+    fn apply(
+        &mut self,
+        vm: &mut FrameVm<'_, '_>,
+        ret: &StepResult<CodeOffset>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let [arg0, arg1] = &ret.args;
+        let block = &self.state.block;
+        let result = match ret.op {
+            op::ADDRESS => address_word(self.ctx.address),
+            op::CALLER => address_word(self.ctx.caller),
+            op::ORIGIN => address_word(self.state.origin),
+            op::CALLVALUE => self.ctx.value,
+            op::COINBASE => address_word(block.coinbase),
+            op::TIMESTAMP => U256::from(block.timestamp),
+            op::NUMBER => U256::from(block.number),
+            op::PREVRANDAO => block.prevrandao,
+            op::GASLIMIT => U256::from(block.gas_limit),
+            op::CHAINID => U256::from(block.chain_id),
+            op::BASEFEE => block.base_fee,
+            op::BLOBBASEFEE => block.blob_base_fee,
+            op::GASPRICE => block.gas_price,
+            op::BLOCKHASH | op::BLOBHASH => U256::ZERO,
+            op::GAS => U256::from(self.gas_limit - self.gas_used),
+            op::MSIZE => U256::from(vm.memory.size().div_ceil(32) * 32),
+            op::BALANCE => self.state.balance(address(arg0)),
+            op::SELFBALANCE => self.state.balance(self.ctx.address),
+            op::SLOAD => self.state.sload(self.ctx.address, arg0.into()),
+            op::TLOAD => {
+                let key = (self.ctx.address, arg0.into());
+                self.state
+                    .journal
+                    .transient
+                    .get(&key)
+                    .copied()
+                    .unwrap_or_default()
+            }
+            op::KECCAK256 => U256::from_be_bytes(keccak256(Self::read(vm, arg0, arg1)?).0),
+            op::EXTCODESIZE => U256::from(self.state.host.code(address(arg0)).len()),
+            op::EXTCODEHASH => {
+                let address = address(arg0);
+                let code = self.state.host.code(address);
+                if code.is_empty() && self.state.balance(address).is_zero() {
+                    U256::ZERO
+                } else {
+                    U256::from_be_bytes(keccak256(code).0)
+                }
+            }
+            op::RETURNDATASIZE => U256::from(self.return_data.len()),
+            op::CALL | op::CALLCODE | op::DELEGATECALL | op::STATICCALL => {
+                return self.call(vm, ret);
+            }
+
+            op::SSTORE | op::TSTORE => {
+                self.check_static(ret.op)?;
+                let key = (self.ctx.address, arg0.into());
+                if ret.op == op::SSTORE {
+                    self.state.journal.set_storage(key, arg1.into());
+                } else {
+                    self.state.journal.set_transient(key, arg1.into());
+                }
+                return Ok(());
+            }
+            op::LOG0..=op::LOG4 => {
+                self.check_static(ret.op)?;
+                let data = Self::read(vm, arg0, arg1)?;
+                self.state.journal.logs.push(Log {
+                    address: self.ctx.address,
+                    topics: ret.exargs.iter().map(|topic| topic.data).collect(),
+                    data,
+                });
+                return Ok(());
+            }
+            op::EXTCODECOPY => {
+                let code = self.state.host.code(address(arg0));
+                let size: u32 = (&ret.exargs[1]).try_into()?;
+                let data = padded(&code, (&ret.exargs[0]).into(), size as usize);
+                return Self::write(vm, arg1, data);
+            }
+            op::RETURNDATACOPY => {
+                let size: usize = (&ret.exargs[0]).try_into()?;
+                let range = usize::try_from(arg1)
+                    .ok()
+                    .and_then(|start| Some(start..start.checked_add(size)?))
+                    .filter(|range| range.end <= self.return_data.len())
+                    .ok_or("return data out of bounds")?;
+                return Self::write(vm, arg0, self.return_data[range].to_vec());
+            }
+            op::CREATE | op::CREATE2 => {
+                self.return_data.clear();
+                return Ok(());
+            }
+            op::CODECOPY => {
+                if let (Ok(mem_offset), Ok(code_offset)) =
+                    (arg0.try_into(), (&ret.exargs[0]).try_into())
+                    && let Some(written) = vm.memory.get_mut(mem_offset)
+                {
+                    written.label = Some(code_offset);
+                }
+                return Ok(());
+            }
+            op::AND | op::SIGNEXTEND => {
+                // keep the origin of values a mask or sign extension leaves unchanged
+                let src = if ret.op == op::AND {
+                    ret.args.iter().find(|arg| arg.label.is_some())
+                } else {
+                    Some(arg1)
+                };
+                let top = vm.stack.peek_mut()?;
+                if let Some(src) = src
+                    && src.data == top.data
+                {
+                    top.label = src.label;
+                }
+                return Ok(());
+            }
+            op::MLOAD => {
+                // keep the origin of words copied from the code, stored with MSTORE or CODECOPY
+                if let Some(loaded) = &ret.memory_load
+                    && let [chunk] = loaded.chunks.as_slice()
+                    && chunk.dst_range == (0..32)
+                {
+                    vm.stack.peek_mut()?.label = Some(chunk.src_label + chunk.src_range.start);
+                }
+                return Ok(());
+            }
+            op::MCOPY => {
+                let size: usize = (&ret.exargs[1]).try_into()?;
+                let label = match ret.memory_load.as_ref().map(|m| m.chunks.as_slice()) {
+                    Some([chunk]) if chunk.dst_range == (0..size) => {
+                        Some(chunk.src_label + chunk.src_range.start)
+                    }
+                    _ => None,
+                };
+                if let Some(written) = vm.memory.get_mut(arg0.try_into()?) {
+                    written.label = label;
+                }
+                return Ok(());
+            }
+            op::MSTORE8 => {
+                // the stored byte is the last one of the word
+                if let Some(written) = vm.memory.get_mut(arg0.try_into()?) {
+                    written.label = arg1.label.map(|offset| offset + 31);
+                }
+                return Ok(());
+            }
+            op::RETURN | op::REVERT => {
+                (self.output, self.output_origins) = Self::read_with_origins(vm, arg0, arg1)?;
+                let exit = if ret.op == op::RETURN {
+                    ExitReason::Return
+                } else {
+                    ExitReason::Revert
+                };
+                self.stop(vm, exit, None);
+                return Ok(());
+            }
+            op::SELFDESTRUCT => {
+                self.check_static(ret.op)?;
+                let balance = self.state.balance(self.ctx.address);
+                self.state
+                    .transfer(self.ctx.address, address(arg0), balance);
+                self.stop(vm, ExitReason::SelfDestruct, None);
+                return Ok(());
+            }
+            op::STOP => {
+                self.stop(vm, ExitReason::Stop, None);
+                return Ok(());
+            }
+            op::INVALID => return Err("INVALID opcode".into()),
+            _ => return Ok(()),
+        };
+        set_top(vm, result)?;
+        Ok(())
+    }
+}
+
+fn set_top(vm: &mut FrameVm<'_, '_>, val: U256) -> Result<(), IndexError> {
+    vm.stack.peek_mut()?.data = val.to_be_bytes();
+    Ok(())
+}
+
+impl<'i, H: Host + ?Sized> Inspector<CodeOffset, Input<'i>> for Frame<'_, '_, H> {
+    fn step_end(&mut self, vm: &mut FrameVm<'_, 'i>, _pc: usize, ret: &StepResult<CodeOffset>) {
+        match self.gas_used.checked_add(ret.gas_used) {
+            Some(gas_used) if gas_used <= self.gas_limit => self.gas_used = gas_used,
+            _ => {
+                self.stop(vm, ExitReason::OutOfGas, None);
+                return;
+            }
+        }
+        if let Err(err) = self.apply(vm, ret) {
+            self.stop(vm, ExitReason::Error, Some(err.to_string()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StateHost;
+
+    #[test]
+    fn test_gas_overflow() {
+        // loop: jumpdest; push0; jump
+        let host = StateHost::new();
+        let mut state = State::new(&host, Address::ZERO);
+        let calldata = Input(&[]);
+        let mut vm = Vm::new(&[0x5b, 0x5f, 0x56], &calldata);
+        let mut frame = Frame {
+            state: &mut state,
+            ctx: Context {
+                address: Address::ZERO,
+                caller: Address::ZERO,
+                value: U256::ZERO,
+                is_static: false,
+                depth: 0,
+            },
+            gas_limit: u32::MAX,
+            gas_used: u32::MAX - 20,
+            return_data: Vec::new(),
+            exit: None,
+            output: Vec::new(),
+            output_origins: Vec::new(),
+        };
+        vm.run(&mut frame, u32::MAX).unwrap();
+        assert_eq!(frame.exit, Some((ExitReason::OutOfGas, None)));
+    }
+}
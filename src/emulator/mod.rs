@@ -0,0 +1,461 @@
+//! Concrete execution of calls against the state provided by a [`Host`], for simulating
+//! calls to a contract offline.
+
+use crate::{
+    Topic,
+    evm::{U256, op},
+};
+use alloy_primitives::Address;
+
+mod deployment;
+mod frame;
+mod state;
+pub use deployment::{Deployment, Immutable, simulate_deployment};
+pub use state::{Account, StateHost};
+
+/// World state read by [`simulate_call`]
+///
+/// The host is only read: storage writes and value transfers are kept by the simulation
+/// and reported in [`Execution::storage_diff`].
+pub trait Host {
+    /// Returns the value of storage `slot` of `address`
+    fn storage(&self, address: Address, slot: U256) -> U256;
+
+    /// Returns the balance of `address` in wei
+    fn balance(&self, address: Address) -> U256;
+
+    /// Returns the runtime code of `address`, empty if it has none
+    fn code(&self, address: Address) -> Vec<u8>;
+
+    /// Returns the block the call is executed in
+    fn block(&self) -> BlockEnv {
+        BlockEnv::default()
+    }
+
+    /// Returns the result of a CALL or STATICCALL made by the executed code, or `None` to
+    /// execute the code of `call.to`
+    ///
+    /// DELEGATECALL and CALLCODE always execute the code of the target.
+    fn call(&self, call: &Call) -> Option<CallResult> {
+        let _ = call;
+        None
+    }
+}
+
+/// Block values returned by the environment opcodes
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlockEnv {
+    /// NUMBER
+    pub number: u64,
+    /// TIMESTAMP
+    pub timestamp: u64,
+    /// CHAINID
+    pub chain_id: u64,
+    /// COINBASE
+    pub coinbase: Address,
+    /// GASLIMIT
+    pub gas_limit: u64,
+    /// BASEFEE
+    pub base_fee: U256,
+    /// BLOBBASEFEE
+    pub blob_base_fee: U256,
+    /// PREVRANDAO
+    pub prevrandao: U256,
+    /// GASPRICE, the gas price of the simulated transaction
+    pub gas_price: U256,
+}
+
+/// Message call, see [`simulate_call`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Call {
+    /// Sender; for the simulated call also returned by ORIGIN
+    pub caller: Address,
+
+    /// Called account
+    pub to: Address,
+
+    /// Transferred wei
+    pub value: U256,
+
+    /// Calldata
+    pub input: Vec<u8>,
+
+    /// Gas available to the call
+    pub gas_limit: u32,
+}
+
+impl Call {
+    /// Creates a call of `to` from the zero address, without value and with 10M gas
+    pub fn new(to: Address, input: impl Into<Vec<u8>>) -> Self {
+        Self {
+            caller: Address::ZERO,
+            to,
+            value: U256::ZERO,
+            input: input.into(),
+            gas_limit: 10_000_000,
+        }
+    }
+
+    /// Sets the sender
+    pub fn with_caller(mut self, caller: Address) -> Self {
+        self.caller = caller;
+        self
+    }
+
+    /// Sets the transferred wei; the caller must hold them
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Sets the available gas
+    pub fn with_gas_limit(mut self, gas_limit: u32) -> Self {
+        self.gas_limit = gas_limit;
+        self
+    }
+}
+
+/// Result of a call answered by [`Host::call`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CallResult {
+    /// Whether the call succeeded; a failed call reverts with `output`
+    pub success: bool,
+
+    /// Returned data
+    pub output: Vec<u8>,
+}
+
+/// How an execution ended
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ExitReason {
+    /// STOP, or the execution ran past the end of the code
+    Stop,
+    /// RETURN
+    Return,
+    /// SELFDESTRUCT
+    SelfDestruct,
+    /// REVERT
+    Revert,
+    /// The gas limit was exceeded
+    OutOfGas,
+    /// An opcode failed, see [`Execution::error`]
+    Error,
+}
+
+impl ExitReason {
+    /// Returns whether the state changes of the execution are kept
+    pub fn is_success(&self) -> bool {
+        matches!(self, Self::Stop | Self::Return | Self::SelfDestruct)
+    }
+}
+
+/// Log emitted by a LOG opcode
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Log {
+    /// Emitting account
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::address"))]
+    pub address: Address,
+
+    /// Topics, 0 to 4
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::vec_topic")
+    )]
+    pub topics: Vec<Topic>,
+
+    /// Unindexed data
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::bytes"))]
+    pub data: Vec<u8>,
+}
+
+/// Storage slot whose value was changed by the execution
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct StorageChange {
+    /// Account owning the slot
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::address"))]
+    pub address: Address,
+
+    /// Slot key
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::uint"))]
+    pub slot: U256,
+
+    /// Value provided by the host
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::uint"))]
+    pub original: U256,
+
+    /// Value after the execution
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::uint"))]
+    pub current: U256,
+}
+
+/// Result of [`simulate_call`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Execution {
+    /// How the call ended
+    pub exit: ExitReason,
+
+    /// Why the call failed, if [`ExitReason::Error`]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub error: Option<String>,
+
+    /// Data passed to RETURN or REVERT
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "returnData", serialize_with = "crate::serialize::bytes")
+    )]
+    pub return_data: Vec<u8>,
+
+    /// Logs emitted by the call and the calls it made, in emission order; empty if it failed
+    pub logs: Vec<Log>,
+
+    /// Changed storage slots of all accounts, sorted by address and slot; empty if it failed
+    #[cfg_attr(feature = "serde", serde(rename = "storageDiff"))]
+    pub storage_diff: Vec<StorageChange>,
+
+    /// Gas used, approximated with the static opcode costs
+    #[cfg_attr(feature = "serde", serde(rename = "gasUsed"))]
+    pub gas_used: u32,
+}
+
+/// Executes `call` against the state of `host`
+///
+/// Storage, balances, code, calls and block values are taken from the host instead of
+/// the constants used by the analysis passes, and nested calls execute the code of their
+/// targets. CREATE and CREATE2 are not supported and fail; see [`simulate_deployment`]
+/// for running creation code. Gas costs do not account for memory expansion and cold
+/// accesses, so [`Execution::gas_used`] and the value returned by GAS are approximate.
+///
+/// ```
+/// use evmole::{Account, Call, ExitReason, StateHost, simulate_call};
+/// use alloy_primitives::{Address, U256, hex};
+///
+/// // return(sload(0) + calldataload(0))
+/// let code = hex::decode("5f545f35015f5260205ff3").unwrap();
+/// let contract = Address::repeat_byte(0xcc);
+/// let host = StateHost::new().with_account(
+///     contract,
+///     Account {
+///         code,
+///         storage: [(U256::ZERO, U256::from(5))].into(),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let result = simulate_call(&host, &Call::new(contract, U256::from(2).to_be_bytes_vec()));
+/// assert_eq!(result.exit, ExitReason::Return);
+/// assert_eq!(U256::from_be_slice(&result.return_data), U256::from(7));
+/// ```
+pub fn simulate_call<H: Host + ?Sized>(host: &H, call: &Call) -> Execution {
+    run(host, op::CALL, call).0
+}
+
+/// Executes `call` as a top-level call of type `kind`, also returning the parts of the
+/// output copied from the code
+fn run<H: Host + ?Sized>(host: &H, kind: op::OpCode, call: &Call) -> (Execution, frame::Origins) {
+    let mut state = frame::State::new(host, call.caller);
+    let outcome = frame::message_call(
+        &mut state,
+        kind,
+        frame::Context {
+            address: call.to,
+            caller: call.caller,
+            value: call.value,
+            is_static: false,
+            depth: 0,
+        },
+        call.to,
+        &call.input,
+        call.gas_limit,
+    );
+    let execution = Execution {
+        exit: outcome.exit,
+        error: outcome.error,
+        return_data: outcome.output,
+        storage_diff: state.storage_diff(),
+        logs: state.journal.logs,
+        gas_used: outcome.gas_used,
+    };
+    (execution, outcome.output_origins)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    const CALLER: Address = Address::repeat_byte(0xaa);
+    const CONTRACT: Address = Address::repeat_byte(0xcc);
+    const CALLEE: Address = Address::repeat_byte(0xdd);
+
+    fn word(val: u64) -> Vec<u8> {
+        U256::from(val).to_be_bytes_vec()
+    }
+
+    fn address_word(address: Address) -> Vec<u8> {
+        U256::from_be_slice(address.as_slice()).to_be_bytes_vec()
+    }
+
+    fn host(code: &str) -> StateHost {
+        StateHost::new().with_account(
+            CONTRACT,
+            Account {
+                code: hex::decode(code).unwrap(),
+                storage: [(U256::ZERO, U256::from(5))].into(),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_storage_and_logs() {
+        // sum = calldataload(4) + sload(0); sstore(0, sum); mstore(0, sum)
+        // log1(0, 32, caller); return(0, 32)
+        let host = host("6004355f5401805f55805f523360205fa160205ff3");
+        let input = [[0x11, 0x22, 0x33, 0x44].as_slice(), &word(7)].concat();
+        let r = simulate_call(&host, &Call::new(CONTRACT, input).with_caller(CALLER));
+
+        assert_eq!(r.exit, ExitReason::Return);
+        assert_eq!(r.error, None);
+        assert_eq!(r.return_data, word(12));
+        assert_eq!(
+            r.logs,
+            vec![Log {
+                address: CONTRACT,
+                topics: vec![address_word(CALLER).try_into().unwrap()],
+                data: word(12),
+            }]
+        );
+        assert_eq!(
+            r.storage_diff,
+            vec![StorageChange {
+                address: CONTRACT,
+                slot: U256::ZERO,
+                original: U256::from(5),
+                current: U256::from(12),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_revert_and_failures() {
+        // sstore(0, 1); log0(0, 0); revert(0, 0)
+        let host = host("60015f555f5fa05f5ffd");
+        let r = simulate_call(&host, &Call::new(CONTRACT, []));
+        assert_eq!(r.exit, ExitReason::Revert);
+        assert!(r.logs.is_empty());
+        assert!(r.storage_diff.is_empty());
+
+        let r = simulate_call(&host, &Call::new(CONTRACT, []).with_value(U256::from(1)));
+        assert_eq!(r.exit, ExitReason::Error);
+        assert!(r.error.is_some());
+
+        // loop: jumpdest; push0; jump
+        let host = StateHost::new().with_account(
+            CONTRACT,
+            Account {
+                code: hex::decode("5b5f56").unwrap(),
+                ..Default::default()
+            },
+        );
+        let r = simulate_call(&host, &Call::new(CONTRACT, []).with_gas_limit(1000));
+        assert_eq!(r.exit, ExitReason::OutOfGas);
+        assert_eq!(r.gas_used, 1000);
+    }
+
+    #[test]
+    fn test_call_depth() {
+        // call(gas, address, 0, 0, 0, 0, 0); stop
+        let host = StateHost::new().with_account(
+            CONTRACT,
+            Account {
+                code: hex::decode("5f5f5f5f5f305af100").unwrap(),
+                ..Default::default()
+            },
+        );
+        let r = std::thread::Builder::new()
+            .stack_size(2 << 20)
+            .spawn(move || simulate_call(&host, &Call::new(CONTRACT, []).with_gas_limit(u32::MAX)))
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(r.exit, ExitReason::Stop);
+    }
+
+    /// Code staticcalling CALLEE and returning its output word, the success flag and
+    /// returndatasize
+    fn caller_code() -> Vec<u8> {
+        hex::decode(format!(
+            "60205f5f5f73{}5afa6020523d60405260605ff3",
+            hex::encode(CALLEE)
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_nested_call() {
+        // callee: return(caller)
+        let host = StateHost::new()
+            .with_account(
+                CONTRACT,
+                Account {
+                    code: caller_code(),
+                    ..Default::default()
+                },
+            )
+            .with_account(
+                CALLEE,
+                Account {
+                    code: hex::decode("335f5260205ff3").unwrap(),
+                    ..Default::default()
+                },
+            );
+        let r = simulate_call(&host, &Call::new(CONTRACT, []));
+        assert_eq!(r.exit, ExitReason::Return);
+        assert_eq!(
+            r.return_data,
+            [address_word(CONTRACT), word(1), word(32)].concat()
+        );
+    }
+
+    struct Stubbed;
+
+    impl Host for Stubbed {
+        fn storage(&self, _: Address, _: U256) -> U256 {
+            U256::ZERO
+        }
+
+        fn balance(&self, _: Address) -> U256 {
+            U256::ZERO
+        }
+
+        fn code(&self, address: Address) -> Vec<u8> {
+            if address == CONTRACT {
+                caller_code()
+            } else {
+                Vec::new()
+            }
+        }
+
+        fn call(&self, call: &Call) -> Option<CallResult> {
+            assert_eq!((call.caller, call.to), (CONTRACT, CALLEE));
+            Some(CallResult {
+                success: false,
+                output: vec![0xab],
+            })
+        }
+    }
+
+    #[test]
+    fn test_host_call() {
+        let r = simulate_call(&Stubbed, &Call::new(CONTRACT, []));
+        assert_eq!(r.exit, ExitReason::Return);
+        let mut expected = word(0);
+        expected[0] = 0xab;
+        assert_eq!(r.return_data, [expected, word(0), word(1)].concat());
+    }
+}
@@ -0,0 +1,250 @@
+use super::{BlockEnv, Host};
+use crate::evm::U256;
+use alloy_primitives::Address;
+use std::collections::BTreeMap;
+
+/// Account of a [`StateHost`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Account {
+    /// Balance in wei
+    pub balance: U256,
+
+    /// Runtime code
+    pub code: Vec<u8>,
+
+    /// Non-zero storage slots
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// [`Host`] serving a fixed set of accounts, e.g. loaded from a JSON state file
+///
+/// Accounts not in the state have no code, balance or storage.
+#[derive(Clone, Debug, Default)]
+pub struct StateHost {
+    accounts: BTreeMap<Address, Account>,
+    block: BlockEnv,
+}
+
+impl StateHost {
+    /// Creates a host without accounts
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds or replaces the account at `address`
+    pub fn with_account(mut self, address: Address, account: Account) -> Self {
+        self.accounts.insert(address, account);
+        self
+    }
+
+    /// Sets the block returned by [`Host::block`]
+    pub fn with_block(mut self, block: BlockEnv) -> Self {
+        self.block = block;
+        self
+    }
+
+    /// Returns the account at `address`
+    pub fn account(&self, address: &Address) -> Option<&Account> {
+        self.accounts.get(address)
+    }
+}
+
+impl Host for StateHost {
+    fn storage(&self, address: Address, slot: U256) -> U256 {
+        self.accounts
+            .get(&address)
+            .and_then(|acc| acc.storage.get(&slot).copied())
+            .unwrap_or_default()
+    }
+
+    fn balance(&self, address: Address) -> U256 {
+        self.accounts
+            .get(&address)
+            .map(|acc| acc.balance)
+            .unwrap_or_default()
+    }
+
+    fn code(&self, address: Address) -> Vec<u8> {
+        self.accounts
+            .get(&address)
+            .map(|acc| acc.code.clone())
+            .unwrap_or_default()
+    }
+
+    fn block(&self) -> BlockEnv {
+        self.block.clone()
+    }
+}
+
+#[cfg(feature = "json")]
+mod json {
+    use super::*;
+    use alloy_primitives::hex;
+    use serde_json::{Map, Value};
+    use std::{
+        io::{self, Read},
+        path::Path,
+    };
+
+    fn invalid(msg: String) -> io::Error {
+        io::Error::new(io::ErrorKind::InvalidData, msg)
+    }
+
+    /// Parses a JSON number or a decimal or `0x`-prefixed hex string
+    fn uint(val: &Value, what: &str) -> io::Result<U256> {
+        match val {
+            Value::Number(n) => n.as_u64().map(U256::from),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| invalid(format!("invalid {what}: {val}")))
+    }
+
+    fn u64(val: &Value, what: &str) -> io::Result<u64> {
+        uint(val, what)?
+            .try_into()
+            .map_err(|_| invalid(format!("{what} out of range: {val}")))
+    }
+
+    fn address(s: &str) -> io::Result<Address> {
+        s.parse()
+            .map_err(|e| invalid(format!("invalid address '{s}': {e}")))
+    }
+
+    fn object<'a>(val: &'a Value, what: &str) -> io::Result<&'a Map<String, Value>> {
+        val.as_object()
+            .ok_or_else(|| invalid(format!("{what} is not an object")))
+    }
+
+    fn account(val: &Value, addr: &str) -> io::Result<Account> {
+        let val = object(val, &format!("account {addr}"))?;
+        let mut account = Account::default();
+        if let Some(balance) = val.get("balance") {
+            account.balance = uint(balance, &format!("balance of {addr}"))?;
+        }
+        if let Some(code) = val.get("code") {
+            account.code = code
+                .as_str()
+                .and_then(|s| hex::decode(s).ok())
+                .ok_or_else(|| invalid(format!("invalid code of {addr}")))?;
+        }
+        if let Some(storage) = val.get("storage") {
+            for (slot, value) in object(storage, &format!("storage of {addr}"))? {
+                let slot = uint(&Value::String(slot.clone()), "storage slot")?;
+                account.storage.insert(slot, uint(value, "storage value")?);
+            }
+        }
+        Ok(account)
+    }
+
+    fn block(val: &Value) -> io::Result<BlockEnv> {
+        let val = object(val, "block")?;
+        let mut block = BlockEnv::default();
+        for (key, v) in val {
+            match key.as_str() {
+                "number" => block.number = u64(v, key)?,
+                "timestamp" => block.timestamp = u64(v, key)?,
+                "chainId" => block.chain_id = u64(v, key)?,
+                "gasLimit" => block.gas_limit = u64(v, key)?,
+                "coinbase" => {
+                    block.coinbase = address(
+                        v.as_str()
+                            .ok_or_else(|| invalid(format!("invalid {key}")))?,
+                    )?
+                }
+                "baseFee" => block.base_fee = uint(v, key)?,
+                "blobBaseFee" => block.blob_base_fee = uint(v, key)?,
+                "prevrandao" => block.prevrandao = uint(v, key)?,
+                "gasPrice" => block.gas_price = uint(v, key)?,
+                _ => {}
+            }
+        }
+        Ok(block)
+    }
+
+    impl StateHost {
+        /// Reads a state from JSON
+        ///
+        /// The state maps addresses to accounts with optional `balance`, `code` and
+        /// `storage` fields, as printed by geth's `prestateTracer`. It can also be wrapped
+        /// in an object holding the map under `accounts` and the values of [`BlockEnv`]
+        /// under `block`:
+        ///
+        /// ```json
+        /// {
+        ///   "block": { "number": 19000000, "timestamp": "0x65a8c4f7", "chainId": 1 },
+        ///   "accounts": {
+        ///     "0x00000000000000000000000000000000000000cc": {
+        ///       "balance": "0xde0b6b3a7640000",
+        ///       "code": "0x5f545f35015f5260205ff3",
+        ///       "storage": { "0x0": "0x5" }
+        ///     }
+        ///   }
+        /// }
+        /// ```
+        ///
+        /// Numbers are JSON numbers, or decimal or `0x`-prefixed hex strings; other fields
+        /// are ignored.
+        pub fn from_reader<R: Read>(reader: R) -> io::Result<Self> {
+            let root: Value = serde_json::from_reader(reader).map_err(io::Error::from)?;
+            let root_obj = object(&root, "state")?;
+            let (accounts, block_val) = match root_obj.get("accounts") {
+                Some(accounts) => (object(accounts, "accounts")?, root_obj.get("block")),
+                None => (root_obj, None),
+            };
+
+            let mut host = Self::new();
+            if let Some(val) = block_val {
+                host.block = block(val)?;
+            }
+            for (addr, val) in accounts {
+                host.accounts.insert(address(addr)?, account(val, addr)?);
+            }
+            Ok(host)
+        }
+
+        /// Loads a state from a JSON file, see [`StateHost::from_reader`]
+        pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+            let file = std::fs::File::open(path)?;
+            Self::from_reader(io::BufReader::new(file))
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use crate::{Call, ExitReason, simulate_call};
+
+        #[test]
+        fn test_from_reader() {
+            let json = r#"{
+                "block": { "number": 19000000, "chainId": "0x1" },
+                "accounts": {
+                    "0x00000000000000000000000000000000000000cc": {
+                        "balance": "1000",
+                        "nonce": 1,
+                        "code": "0x5f545f35015f5260205ff3",
+                        "storage": { "0x0": "0x5" }
+                    }
+                }
+            }"#;
+            let host = StateHost::from_reader(json.as_bytes()).unwrap();
+            assert_eq!(host.block.number, 19_000_000);
+            assert_eq!(host.block.chain_id, 1);
+
+            let contract = Address::with_last_byte(0xcc);
+            assert_eq!(host.balance(contract), U256::from(1000));
+            let r = simulate_call(&host, &Call::new(contract, U256::from(2).to_be_bytes_vec()));
+            assert_eq!(r.exit, ExitReason::Return);
+            assert_eq!(r.return_data, U256::from(7).to_be_bytes_vec());
+
+            // plain prestateTracer output
+            let json = r#"{ "0x00000000000000000000000000000000000000cc": { "balance": "0x10" } }"#;
+            let host = StateHost::from_reader(json.as_bytes()).unwrap();
+            assert_eq!(host.balance(contract), U256::from(16));
+
+            let err = StateHost::from_reader(r#"{ "0xzz": {} }"#.as_bytes()).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
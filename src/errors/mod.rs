@@ -0,0 +1,325 @@
+//! Extraction of the errors a contract can revert with: custom errors, `Panic(uint256)`
+//! codes and literal `Error(string)` reasons.
+
+use crate::{
+    DynSolType, Selector,
+    diagnostics::Diagnostic,
+    evm::{U256, op, vm::StepResult},
+    payload::{self, Label, MAX_PAYLOAD_SIZE, PayloadVm},
+    trace::Tracer,
+    utils::map_ordered,
+};
+use alloy_primitives::hex;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// `bytes4(keccak256("Panic(uint256)"))`
+const PANIC_SELECTOR: Selector = hex!("4e487b71");
+
+/// `bytes4(keccak256("Error(string)"))`
+const ERROR_SELECTOR: Selector = hex!("08c379a0");
+
+/// Kind of revert payload
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum RevertKind {
+    /// Custom error, `error Name(...)` in Solidity
+    Custom,
+    /// `Panic(uint256)` raised by the compiler on failed checks
+    Panic,
+    /// `Error(string)` with a literal reason, raised by `require` and `revert`
+    Reason,
+}
+
+impl RevertKind {
+    /// Returns the kind as a camelCase string, matching its serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Custom => "custom",
+            Self::Panic => "panic",
+            Self::Reason => "reason",
+        }
+    }
+}
+
+/// Error the contract can revert with
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct RevertError {
+    /// Payload flavor
+    pub kind: RevertKind,
+
+    /// Error selector, the first 4 bytes of the revert data
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::selector")
+    )]
+    pub selector: Selector,
+
+    /// Types of the error arguments
+    ///
+    /// For custom errors, words whose type could not be narrowed are reported as
+    /// `uint256`, and dynamic values of unknown type as `bytes`.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::types"))]
+    pub arguments: Vec<DynSolType>,
+
+    /// Panic code, e.g. `0x11` for arithmetic overflow
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "panicCode", skip_serializing_if = "Option::is_none")
+    )]
+    pub panic_code: Option<u64>,
+
+    /// Literal revert reason
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub reason: Option<String>,
+
+    /// Function selectors that can revert with this error
+    ///
+    /// Empty when it is only raised by the fallback.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::vec_selector")
+    )]
+    pub functions: Vec<Selector>,
+}
+
+fn decode_reason(data: &[u8], chunks: &crate::evm::memory::MemoryChunks<Label>) -> Option<String> {
+    let word = |o: usize| U256::from_be_slice(&data[o..o + 32]);
+    if data.len() < 68 || word(4) != U256::from(32) {
+        return None;
+    }
+    let len = usize::try_from(word(36)).ok()?;
+    let range = 68..68usize.checked_add(len)?;
+    if range.end > data.len() || !payload::is_constant(chunks, range.clone()) {
+        return None;
+    }
+    String::from_utf8(data[range].to_vec()).ok()
+}
+
+/// Decodes the payload of a REVERT, if it holds an error
+fn reverted(vm: &PayloadVm, ret: &StepResult<Label>) -> Option<RevertError> {
+    let StepResult {
+        op: op::REVERT,
+        args: [offset, size],
+        ..
+    } = ret
+    else {
+        return None;
+    };
+    let (Ok(off), Ok(size)) = (u32::try_from(offset), u32::try_from(size)) else {
+        return None;
+    };
+    if !(4..=MAX_PAYLOAD_SIZE).contains(&size) {
+        return None;
+    }
+    let (data, chunks) = vm.memory.load(off, size);
+    let selector: Selector = data[..4].try_into().expect("at least 4 bytes");
+
+    let mut error = RevertError {
+        kind: RevertKind::Custom,
+        selector,
+        arguments: Vec::new(),
+        panic_code: None,
+        reason: None,
+        functions: Vec::new(),
+    };
+    match selector {
+        PANIC_SELECTOR => {
+            if size != 36 || !payload::is_constant(&chunks, 4..36) {
+                return None;
+            }
+            error.kind = RevertKind::Panic;
+            error.arguments = vec![DynSolType::Uint(256)];
+            error.panic_code = Some(u64::try_from(U256::from_be_slice(&data[4..])).ok()?);
+        }
+        ERROR_SELECTOR => {
+            error.kind = RevertKind::Reason;
+            error.arguments = vec![DynSolType::String];
+            error.reason = Some(decode_reason(&data, &chunks)?);
+        }
+        // zeroed memory, e.g. return data bubbled up from a call
+        [0, 0, 0, 0] => return None,
+        _ => {
+            if size % 32 != 4 {
+                return None;
+            }
+            let (data, chunks) = vm.memory.load(off.checked_add(4)?, size - 4);
+            error.arguments = payload::infer_types(&data, &chunks);
+        }
+    }
+    Some(error)
+}
+
+type ErrorKey = (RevertKind, Selector, Option<u64>, Option<String>);
+
+/// Keeps the most specific arguments seen for every error
+fn collect_errors(
+    found: &mut BTreeMap<ErrorKey, (RevertError, BTreeSet<Selector>)>,
+    selector: Option<Selector>,
+    errors: Vec<RevertError>,
+) {
+    for error in errors {
+        let key = (
+            error.kind,
+            error.selector,
+            error.panic_code,
+            error.reason.clone(),
+        );
+        let (known, functions) = found
+            .entry(key)
+            .or_insert_with(|| (error.clone(), BTreeSet::new()));
+        if payload::score(&error.arguments) > payload::score(&known.arguments) {
+            known.arguments = error.arguments;
+        }
+        functions.extend(selector);
+    }
+}
+
+pub(crate) struct ContractErrors {
+    /// Errors found in all functions and the fallback, sorted by kind and selector
+    pub errors: Vec<RevertError>,
+    /// Diagnostics of the per-selector passes that stopped early
+    pub diagnostics: BTreeMap<Selector, Vec<Diagnostic>>,
+    /// Diagnostics of the fallback pass
+    pub fallback_diagnostics: Vec<Diagnostic>,
+}
+
+pub(crate) fn contract_errors<I, D>(
+    code: &[u8],
+    functions: I,
+    gas_limit: u32,
+    tracer: Tracer,
+) -> ContractErrors
+where
+    I: IntoIterator<Item = (Selector, D)>,
+    D: AsRef<[DynSolType]> + Sync,
+{
+    let real_gas_limit = if gas_limit == 0 {
+        1e6 as u32
+    } else {
+        gas_limit
+    };
+
+    let functions: Vec<_> = functions.into_iter().collect();
+    let selectors: BTreeSet<Selector> = functions.iter().map(|(sel, _)| *sel).collect();
+    let fallback_selector = payload::fallback_selector(&selectors);
+
+    let analyzed = map_ordered(&functions, |(selector, arguments)| {
+        payload::collect_payloads(
+            code,
+            *selector,
+            arguments.as_ref(),
+            false,
+            real_gas_limit,
+            tracer,
+            reverted,
+        )
+    });
+
+    let mut found = BTreeMap::new();
+    let mut diagnostics = BTreeMap::new();
+    for ((selector, _), (errors, fn_diagnostics)) in functions.iter().zip(analyzed) {
+        if !fn_diagnostics.is_empty() {
+            diagnostics.insert(*selector, fn_diagnostics);
+        }
+        collect_errors(&mut found, Some(*selector), errors);
+    }
+
+    let (fallback, fallback_diagnostics) = payload::collect_payloads(
+        code,
+        fallback_selector,
+        &[],
+        true,
+        real_gas_limit,
+        tracer,
+        reverted,
+    );
+    collect_errors(&mut found, None, fallback);
+
+    ContractErrors {
+        errors: found
+            .into_values()
+            .map(|(mut error, functions)| {
+                error.functions = functions.into_iter().collect();
+                error
+            })
+            .collect(),
+        diagnostics,
+        fallback_diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnalysisPhase;
+
+    #[test]
+    fn test_panic_reason_and_custom() {
+        // 0x11223344(address): Panic(0x11) if the argument is zero,
+        //   else Unauthorized(address) (0x8e4a23d6)
+        // 0x55667788: revert("no")
+        // fallback: Panic(0x01)
+        let code = hex::decode(
+            "60003560e01c80631122334414602c57635566778814606f57\
+             634e487b7160e01b5f52600160045260245ffd\
+             5b60043573ffffffffffffffffffffffffffffffffffffffff1680605d57\
+             634e487b7160e01b5f52601160045260245ffd\
+             5b638e4a23d660e01b5f5260045260245ffd\
+             5b6308c379a060e01b5f52602060045260026024527f6e6f\
+             00000000000000000000000000000000000000000000000000000000000060445260645ffd",
+        )
+        .unwrap();
+        let r = contract_errors(
+            &code,
+            [
+                ([0x11, 0x22, 0x33, 0x44], vec![DynSolType::Address]),
+                ([0x55, 0x66, 0x77, 0x88], vec![]),
+            ],
+            0,
+            Tracer::disabled(AnalysisPhase::Errors),
+        );
+        assert!(r.diagnostics.is_empty());
+        assert!(r.fallback_diagnostics.is_empty());
+
+        let f1 = [0x11, 0x22, 0x33, 0x44];
+        let f2 = [0x55, 0x66, 0x77, 0x88];
+        let summary: Vec<_> = r
+            .errors
+            .iter()
+            .map(|e| {
+                (
+                    e.kind,
+                    hex::encode(e.selector),
+                    e.panic_code,
+                    e.reason.as_deref(),
+                    e.functions.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (RevertKind::Custom, "8e4a23d6".into(), None, None, vec![f1]),
+                (RevertKind::Panic, "4e487b71".into(), Some(1), None, vec![]),
+                (
+                    RevertKind::Panic,
+                    "4e487b71".into(),
+                    Some(0x11),
+                    None,
+                    vec![f1]
+                ),
+                (
+                    RevertKind::Reason,
+                    "08c379a0".into(),
+                    None,
+                    Some("no"),
+                    vec![f2]
+                ),
+            ]
+        );
+        assert_eq!(r.errors[0].arguments, vec![DynSolType::Address]);
+        assert_eq!(r.errors[3].arguments, vec![DynSolType::String]);
+    }
+}
@@ -0,0 +1,181 @@
+//! Extraction of the events a contract can emit, with their data layouts.
+
+use crate::{
+    DynSolType, Selector, Topic,
+    diagnostics::Diagnostic,
+    evm::{element::Element, op, vm::StepResult},
+    payload::{self, Label, MAX_PAYLOAD_SIZE, PayloadVm},
+    trace::Tracer,
+    utils::{elabel, map_ordered},
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Event emitted by the contract, identified by its signature hash
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Event {
+    /// Event signature hash, the first topic of the log
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::topic"))]
+    pub topic: Topic,
+
+    /// Number of indexed arguments, i.e. topics following the signature hash (0-3)
+    pub indexed: u8,
+
+    /// Types of the non-indexed arguments, ABI-encoded in the log data
+    ///
+    /// Words whose type could not be narrowed are reported as `uint256`, and dynamic
+    /// values of unknown type as `bytes`. Empty if the data is not a sequence of words.
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::types"))]
+    pub data: Vec<DynSolType>,
+}
+
+/// Returns the event of a LOG with a constant signature hash
+fn log_event(vm: &PayloadVm, ret: &StepResult<Label>) -> Option<Event> {
+    let StepResult {
+        op: op::LOG1..=op::LOG4,
+        args: [offset, size],
+        exargs,
+        ..
+    } = ret
+    else {
+        return None;
+    };
+    let Some(elabel!(Label::Constant)) = exargs.first() else {
+        return None;
+    };
+    let data = match (u32::try_from(offset), u32::try_from(size)) {
+        (Ok(off), Ok(size)) if size <= MAX_PAYLOAD_SIZE => {
+            let (data, chunks) = vm.memory.load(off, size);
+            payload::infer_types(&data, &chunks)
+        }
+        _ => Vec::new(),
+    };
+    Some(Event {
+        topic: exargs[0].data,
+        indexed: (exargs.len() - 1) as u8,
+        data,
+    })
+}
+
+pub(crate) struct ContractEvents {
+    /// Events found in all functions and the fallback, sorted by topic
+    pub events: Vec<Event>,
+    /// Topics of the events each function can emit
+    pub emitted: BTreeMap<Selector, Vec<Topic>>,
+    /// Diagnostics of the per-selector passes that stopped early
+    pub diagnostics: BTreeMap<Selector, Vec<Diagnostic>>,
+    /// Diagnostics of the fallback pass
+    pub fallback_diagnostics: Vec<Diagnostic>,
+}
+
+/// Keeps the most specific layout seen for every topic
+fn collect_events(found: &mut BTreeMap<Topic, Event>, events: &[Event]) -> Vec<Topic> {
+    let mut topics = BTreeSet::new();
+    for event in events {
+        topics.insert(event.topic);
+        match found.get(&event.topic) {
+            Some(known) if payload::score(&known.data) >= payload::score(&event.data) => {}
+            _ => {
+                found.insert(event.topic, event.clone());
+            }
+        }
+    }
+    topics.into_iter().collect()
+}
+
+pub(crate) fn contract_events<I, D>(
+    code: &[u8],
+    functions: I,
+    gas_limit: u32,
+    tracer: Tracer,
+) -> ContractEvents
+where
+    I: IntoIterator<Item = (Selector, D)>,
+    D: AsRef<[DynSolType]> + Sync,
+{
+    let real_gas_limit = if gas_limit == 0 {
+        1e6 as u32
+    } else {
+        gas_limit
+    };
+
+    let functions: Vec<_> = functions.into_iter().collect();
+    let selectors: BTreeSet<Selector> = functions.iter().map(|(sel, _)| *sel).collect();
+    let fallback_selector = payload::fallback_selector(&selectors);
+
+    let analyzed = map_ordered(&functions, |(selector, arguments)| {
+        payload::collect_payloads(
+            code,
+            *selector,
+            arguments.as_ref(),
+            false,
+            real_gas_limit,
+            tracer,
+            log_event,
+        )
+    });
+
+    let mut found = BTreeMap::new();
+    let mut emitted = BTreeMap::new();
+    let mut diagnostics = BTreeMap::new();
+    for ((selector, _), (events, fn_diagnostics)) in functions.iter().zip(analyzed) {
+        if !fn_diagnostics.is_empty() {
+            diagnostics.insert(*selector, fn_diagnostics);
+        }
+        emitted.insert(*selector, collect_events(&mut found, &events));
+    }
+
+    let (fallback, fallback_diagnostics) = payload::collect_payloads(
+        code,
+        fallback_selector,
+        &[],
+        true,
+        real_gas_limit,
+        tracer,
+        log_event,
+    );
+    collect_events(&mut found, &fallback);
+
+    ContractEvents {
+        events: found.into_values().collect(),
+        emitted,
+        diagnostics,
+        fallback_diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnalysisPhase;
+    use alloy_primitives::hex;
+
+    const TOPIC: Topic = hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+    #[test]
+    fn test_emitted_with_typed_data() {
+        // 0x11223344(address): emit E(indexed msg.sender, arg)
+        let code = hex::decode(format!(
+            "60003560e01c6311223344146010570\
+             05b6004355f52337f{}60205fa200",
+            hex::encode(TOPIC)
+        ))
+        .unwrap();
+        let r = contract_events(
+            &code,
+            [([0x11, 0x22, 0x33, 0x44], [DynSolType::Address])],
+            0,
+            Tracer::disabled(AnalysisPhase::Events),
+        );
+        assert_eq!(
+            r.events,
+            vec![Event {
+                topic: TOPIC,
+                indexed: 1,
+                data: vec![DynSolType::Address],
+            }]
+        );
+        assert_eq!(r.emitted[&[0x11, 0x22, 0x33, 0x44]], vec![TOPIC]);
+        assert!(r.diagnostics.is_empty());
+    }
+}
@@ -0,0 +1,67 @@
+//! Opcodes that inferred facts are based on, for reviewing surprising results.
+
+use crate::evm::op::{self, OpCode};
+use std::fmt;
+
+/// An executed opcode that an inferred fact is based on
+///
+/// Displayed as e.g. `mask: AND at pc 0x1a3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Evidence {
+    /// Bytecode offset of the opcode
+    pub pc: usize,
+
+    /// The opcode byte, see [`Evidence::opcode_name`]
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::opcode"))]
+    pub opcode: OpCode,
+
+    /// What the opcode shows, e.g. `mask` or `callvalue check`
+    pub reason: &'static str,
+}
+
+impl Evidence {
+    pub(crate) const fn new(pc: usize, opcode: OpCode, reason: &'static str) -> Self {
+        Self { pc, opcode, reason }
+    }
+
+    /// Returns the opcode mnemonic, e.g. `AND`
+    pub fn opcode_name(&self) -> &'static str {
+        op::info(self.opcode).name
+    }
+}
+
+impl fmt::Display for Evidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} at pc {:#x}",
+            self.reason,
+            self.opcode_name(),
+            self.pc
+        )
+    }
+}
+
+/// Evidence behind the facts inferred for a function, see [`crate::Function::evidence`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct FunctionEvidence {
+    /// Opcodes that narrowed the type of each of [`crate::Function::arguments`], in pc order
+    ///
+    /// Empty for words that were never narrowed and are reported as `uint256`. `None` if
+    /// arguments were not extracted.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub arguments: Option<Vec<Vec<Evidence>>>,
+
+    /// Opcodes behind [`crate::Function::state_mutability`]: the reverting callvalue check
+    /// for non-payable functions, and the first opcode ruling out `pure` or `view`
+    ///
+    /// Empty for `payable`, which is inferred from the absence of a callvalue check.
+    /// `None` if state mutability was not extracted.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "stateMutability", skip_serializing_if = "Option::is_none")
+    )]
+    pub state_mutability: Option<Vec<Evidence>>,
+}
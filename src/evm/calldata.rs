@@ -2,14 +2,71 @@ use super::{U256, VAL_131072, element::Element};
 use crate::DynSolType;
 use std::{collections::BTreeMap, error, marker::PhantomData};
 
+/// Source of the calldata read by a [`super::vm::Vm`]
+#[allow(clippy::len_without_is_empty)]
 pub trait CallData<T> {
+    /// Returns the word read by CALLDATALOAD at `offset`
     fn load32(&self, offset: U256) -> Element<T>;
+
+    /// Returns the bytes copied by CALLDATACOPY, labeling the whole memory chunk
     fn load(&self, offset: U256, size: U256)
     -> Result<(Vec<u8>, Option<T>), Box<dyn error::Error>>;
+
+    /// Returns CALLDATASIZE
     fn len(&self) -> U256;
+
+    /// Returns the function selector, the first 4 bytes
     fn selector(&self) -> [u8; 4];
 }
 
+/// Concrete calldata, read as zeros past its end
+pub(crate) struct ConcreteCallData {
+    pub data: Vec<u8>,
+}
+
+impl ConcreteCallData {
+    fn copy_to(&self, offset: U256, dst: &mut [u8]) {
+        if let Ok(off) = usize::try_from(offset)
+            && off < self.data.len()
+        {
+            let n = std::cmp::min(dst.len(), self.data.len() - off);
+            dst[..n].copy_from_slice(&self.data[off..off + n]);
+        }
+    }
+}
+
+impl<T> CallData<T> for ConcreteCallData {
+    fn load32(&self, offset: U256) -> Element<T> {
+        let mut data = [0; 32];
+        self.copy_to(offset, &mut data);
+        Element { data, label: None }
+    }
+
+    fn load(
+        &self,
+        offset: U256,
+        size: U256,
+    ) -> Result<(Vec<u8>, Option<T>), Box<dyn error::Error>> {
+        let sz = u16::try_from(size)?;
+        if sz > 512 {
+            return Err("unsupported size".into());
+        }
+        let mut data = vec![0; sz as usize];
+        self.copy_to(offset, &mut data);
+        Ok((data, None))
+    }
+
+    fn selector(&self) -> [u8; 4] {
+        let mut selector = [0; 4];
+        self.copy_to(U256::ZERO, &mut selector);
+        selector
+    }
+
+    fn len(&self) -> U256 {
+        U256::from(self.data.len())
+    }
+}
+
 /// Describes the type of data being labeled in the calldata.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -24,7 +81,9 @@ pub enum CallDataLabelType {
     RealValue,
 }
 
+/// Label type that [`CallDataImpl`] can attach to the argument words it returns
 pub trait CallDataLabel: Sized {
+    /// Returns the label of word `n` of the arguments, holding a value of type `tp`
     fn label(n: usize, tp: &DynSolType, label_type: CallDataLabelType) -> Option<Self>;
 }
 
@@ -226,13 +285,13 @@ mod tests {
     use super::*;
     use std::collections::BTreeMap;
 
-    fn encode_maps(
-        elements: &[DynSolType],
-    ) -> (
+    type EncodedMaps = (
         usize,
         BTreeMap<usize, (DynSolType, CallDataLabelType)>,
         BTreeMap<usize, U256>,
-    ) {
+    );
+
+    fn encode_maps(elements: &[DynSolType]) -> EncodedMaps {
         let (size, types, vals) = encode(elements);
         (size, BTreeMap::from_iter(types), BTreeMap::from_iter(vals))
     }
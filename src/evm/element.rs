@@ -1,9 +1,13 @@
 use crate::evm::U256;
 
+/// A 256-bit stack value with an optional label
 #[derive(Clone, Debug, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct Element<T> {
+    /// Big-endian value
     pub data: [u8; 32],
+
+    /// Where the value came from, as tracked by the analysis
     pub label: Option<T>,
 }
 
@@ -0,0 +1,139 @@
+//! Hooks for custom analyses driving a [`Vm`].
+
+use super::{
+    calldata::CallData,
+    vm::{StepResult, Vm},
+};
+use std::{error, fmt};
+
+/// Observes the steps of [`Vm::run`], and may relabel their results
+///
+/// The analysis passes of this crate follow the same pattern: a label type `T` marks the
+/// values of interest, and labels of new values are derived from the opcode arguments in
+/// [`Inspector::step_end`].
+///
+/// ```
+/// use evmole::evm::{
+///     U256,
+///     calldata::CallData,
+///     element::Element,
+///     inspector::Inspector,
+///     op,
+///     vm::{StepResult, Vm},
+/// };
+///
+/// #[derive(Clone, Debug, PartialEq, Eq)]
+/// enum Label {
+///     Arg(usize),
+///     Masked(usize),
+/// }
+///
+/// /// Calldata of zeros, labeling each loaded word with its offset
+/// struct Args;
+///
+/// impl CallData<Label> for Args {
+///     fn load32(&self, offset: U256) -> Element<Label> {
+///         Element { data: [0; 32], label: Some(Label::Arg(offset.to())) }
+///     }
+///     fn load(
+///         &self,
+///         _: U256,
+///         size: U256,
+///     ) -> Result<(Vec<u8>, Option<Label>), Box<dyn std::error::Error>> {
+///         Ok((vec![0; size.try_into()?], None))
+///     }
+///     fn len(&self) -> U256 {
+///         U256::from(36)
+///     }
+///     fn selector(&self) -> [u8; 4] {
+///         [0; 4]
+///     }
+/// }
+///
+/// /// Reports `AND`s masking an argument
+/// #[derive(Default)]
+/// struct Masks(Vec<(usize, usize)>);
+///
+/// impl Inspector<Label, Args> for Masks {
+///     fn step_end(&mut self, vm: &mut Vm<Label, Args>, pc: usize, ret: &StepResult<Label>) {
+///         if ret.op == op::AND
+///             && let Some(&Label::Arg(offset)) =
+///                 ret.args.iter().find_map(|arg| arg.label.as_ref())
+///         {
+///             self.0.push((pc, offset));
+///             vm.stack.peek_mut().unwrap().label = Some(Label::Masked(offset));
+///         }
+///     }
+/// }
+///
+/// // CALLDATALOAD(4) & 0xff; DUP1; STOP
+/// let code = [0x60, 0x04, 0x35, 0x60, 0xff, 0x16, 0x80, 0x00];
+/// let mut vm = Vm::new(&code, &Args);
+/// let mut masks = Masks::default();
+/// vm.run(&mut masks, 1000).unwrap();
+///
+/// assert_eq!(masks.0, [(5, 4)]);
+/// assert!(vm.stack.data.iter().all(|el| el.label == Some(Label::Masked(4))));
+/// ```
+pub trait Inspector<T, U> {
+    /// Called before the opcode at `vm.pc` is executed
+    fn step(&mut self, vm: &Vm<'_, T, U>) {
+        let _ = vm;
+    }
+
+    /// Called after the opcode at `pc` is executed
+    ///
+    /// Labels of the values it pushed can be set through [`Vm::stack`], and setting
+    /// [`Vm::stopped`] ends the run.
+    fn step_end(&mut self, vm: &mut Vm<'_, T, U>, pc: usize, result: &StepResult<T>) {
+        let _ = (vm, pc, result);
+    }
+}
+
+/// An opcode failed during [`Vm::run`]
+#[derive(Debug)]
+pub struct RunError {
+    /// Bytecode offset of the failed opcode
+    pub pc: usize,
+
+    /// Why it failed, e.g. [`super::vm::UnsupportedOpError`] or [`super::stack::IndexError`]
+    pub error: Box<dyn error::Error>,
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at pc {:#x}", self.error, self.pc)
+    }
+}
+
+impl error::Error for RunError {}
+
+impl<T, U> Vm<'_, T, U>
+where
+    T: fmt::Debug + Clone + Eq,
+    U: CallData<T>,
+{
+    /// Executes the code until it stops or uses more than `gas_limit`, calling `inspector`
+    /// around each step
+    ///
+    /// Only the branch taken with the current values is followed at JUMPI; the other
+    /// destination is reported in [`StepResult::args`] so an inspector can explore it on a
+    /// [`Vm::fork`]. Returns the gas used, which exceeds `gas_limit` if the run was cut
+    /// short, leaving [`Vm::stopped`] unset. The count saturates at `u32::MAX`, so with that
+    /// limit the run only ends when the code or the inspector stops it.
+    pub fn run<I: Inspector<T, U>>(
+        &mut self,
+        inspector: &mut I,
+        gas_limit: u32,
+    ) -> Result<u32, RunError> {
+        let mut gas_used = 0;
+        while !self.stopped && gas_used <= gas_limit {
+            inspector.step(self);
+            let pc = self.pc;
+            let ret = self.step().map_err(|error| RunError { pc, error })?;
+            gas_used = gas_used.saturating_add(ret.gas_used);
+            inspector.step_end(self, pc, &ret);
+        }
+        Ok(gas_used)
+    }
+}
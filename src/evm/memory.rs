@@ -1,28 +1,42 @@
 use super::element::Element;
 use std::{fmt, ops::Range};
 
+/// Bytes written to memory by one opcode
 #[derive(Clone)]
 pub struct LabeledVec<T> {
+    /// Written bytes
     pub data: Vec<u8>,
+
+    /// Label of the written value
     pub label: Option<T>,
 }
 
+/// EVM memory, kept as the list of writes so reads can tell which labeled values they cover
 #[derive(Clone)]
 pub struct Memory<T> {
+    /// Writes by offset, in execution order; later writes shadow earlier ones
     pub data: Vec<(u32, LabeledVec<T>)>,
 }
 
+/// Part of a memory read that comes from one labeled write
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemoryChunk<T> {
+    /// Byte range within the read
     pub dst_range: Range<usize>,
+
+    /// Byte range within the write
     pub src_range: Range<usize>,
+
+    /// Label of the write
     pub src_label: T,
 }
 
+/// Labeled parts of a memory read
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MemoryChunks<T> {
+    /// Labeled parts, in read order; unlabeled bytes are not listed
     pub chunks: Vec<MemoryChunk<T>>,
 
     // Total size of loaded memory, can be not equal to the sum of chunk sizes if some parts are loaded from zero memory.
@@ -89,6 +103,12 @@ impl<T: fmt::Debug> fmt::Debug for Memory<T> {
     }
 }
 
+impl<T: Clone + PartialEq> Default for Memory<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T> Memory<T>
 where
     T: Clone + PartialEq,
@@ -1,8 +1,17 @@
+//! Label-propagating EVM used by the analysis passes.
+//!
+//! [`vm::Vm`] executes bytecode over values that carry an optional label of a caller-chosen
+//! type `T`. Labels follow values through DUP/SWAP and memory, and calldata loads are labeled
+//! by the [`calldata::CallData`] source; results of other opcodes are unlabeled until an
+//! [`inspector::Inspector`] labels them. Values the VM cannot know (storage, balances, call
+//! results, gas) are replaced with constants.
+
 use alloy_primitives::uint;
 
 pub mod calldata;
-pub mod code_iterator;
+pub(crate) mod code_iterator;
 pub mod element;
+pub mod inspector;
 pub mod memory;
 pub mod op;
 pub mod stack;
@@ -10,22 +19,22 @@ pub mod vm;
 
 pub use alloy_primitives::{I256, U256};
 
-pub const VAL_0_B: [u8; 32] = U256::ZERO.to_be_bytes();
+pub(crate) const VAL_0_B: [u8; 32] = U256::ZERO.to_be_bytes();
 
-pub const VAL_1: U256 = uint!(1_U256);
-pub const VAL_1_B: [u8; 32] = VAL_1.to_be_bytes();
+pub(crate) const VAL_1: U256 = uint!(1_U256);
+pub(crate) const VAL_1_B: [u8; 32] = VAL_1.to_be_bytes();
 
-pub const VAL_4: U256 = uint!(4_U256);
+pub(crate) const VAL_4: U256 = uint!(4_U256);
 
-pub const VAL_32: U256 = uint!(32_U256);
-pub const VAL_32_B: [u8; 32] = VAL_32.to_be_bytes();
+pub(crate) const VAL_32: U256 = uint!(32_U256);
+pub(crate) const VAL_32_B: [u8; 32] = VAL_32.to_be_bytes();
 
-pub const VAL_256: U256 = uint!(256_U256);
+pub(crate) const VAL_256: U256 = uint!(256_U256);
 
-pub const VAL_1024: U256 = uint!(1024_U256);
-pub const VAL_1024_B: [u8; 32] = VAL_1024.to_be_bytes();
+pub(crate) const VAL_1024: U256 = uint!(1024_U256);
+pub(crate) const VAL_1024_B: [u8; 32] = VAL_1024.to_be_bytes();
 
-pub const VAL_131072: U256 = uint!(131072_U256);
+pub(crate) const VAL_131072: U256 = uint!(131072_U256);
 
-pub const VAL_1M: U256 = uint!(1000000_U256);
-pub const VAL_1M_B: [u8; 32] = VAL_1M.to_be_bytes();
+pub(crate) const VAL_1M: U256 = uint!(1000000_U256);
+pub(crate) const VAL_1M_B: [u8; 32] = VAL_1M.to_be_bytes();
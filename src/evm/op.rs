@@ -2,12 +2,18 @@
 
 pub type OpCode = u8;
 
+/// Static properties of an opcode, see [`info`]
 #[derive(Debug, Copy, Clone)]
 pub struct OpCodeInfo {
+    /// Mnemonic, e.g. `CALLDATALOAD`; `?` for unassigned opcodes
     pub name: &'static str,
+    /// Whether the opcode is assigned
     pub known: bool,
+    /// Size in bytes, including PUSH immediates
     pub size: usize,
+    /// Number of stack values consumed
     pub stack_in: usize,
+    /// Number of stack values produced
     pub stack_out: usize,
 }
 
@@ -33,6 +39,7 @@ macro_rules! declare_opcodes {
     };
 }
 
+/// Returns the static properties of `op`
 pub const fn info(op: OpCode) -> &'static OpCodeInfo {
     &INFOS[op as usize]
 }
@@ -1,8 +1,10 @@
 use super::{U256, element::Element};
 use std::fmt;
 
+/// EVM stack of labeled values
 #[derive(Clone)]
 pub struct Stack<T> {
+    /// Values, top at the end
     pub data: Vec<Element<T>>,
 }
 
@@ -25,6 +27,7 @@ impl<T: fmt::Debug> fmt::Debug for Stack<T> {
     }
 }
 
+/// The stack holds too few values for the opcode
 #[derive(Debug)]
 pub struct IndexError;
 
@@ -38,6 +41,12 @@ impl std::error::Error for IndexError {}
 
 type Result<T> = std::result::Result<T, IndexError>;
 
+impl<T: Clone> Default for Stack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<T: Clone> Stack<T> {
     pub fn new() -> Self {
         Self { data: Vec::new() }
@@ -9,8 +9,10 @@ use super::{
 use super::{VAL_0_B, VAL_1, VAL_1_B, VAL_1M_B, VAL_32, VAL_256, VAL_1024_B};
 use std::{error, fmt};
 
+/// The opcode is not implemented, or its arguments are out of the supported range
 #[derive(Debug)]
 pub struct UnsupportedOpError {
+    /// The failed opcode
     pub op: op::OpCode,
 }
 impl std::fmt::Display for UnsupportedOpError {
@@ -21,12 +23,25 @@ impl std::fmt::Display for UnsupportedOpError {
 
 impl std::error::Error for UnsupportedOpError {}
 
+/// An executed opcode with the values it consumed
 pub struct StepResult<T> {
+    /// The executed opcode
     pub op: op::OpCode,
+
+    /// Static gas cost of the opcode
     pub gas_used: u32,
+
+    /// First two stack arguments, with their labels
+    ///
+    /// For JUMPI, `args[0]` is the destination that was not taken (zero if it is out of
+    /// the code) and `args[1]` is the condition.
     pub args: [Element<T>; 2],
+
+    /// Remaining stack arguments, e.g. LOG topics or CALL memory ranges
     pub exargs: Vec<Element<T>>,
-    pub memory_load: Option<MemoryChunks<T>>, // for MLOAD & MCOPY
+
+    /// Labeled chunks of the memory read by MLOAD and MCOPY
+    pub memory_load: Option<MemoryChunks<T>>,
 }
 
 impl<T> StepResult<T> {
@@ -46,12 +61,27 @@ impl<T> StepResult<T> {
     }
 }
 
+/// EVM interpreter over values labeled with `T`, reading calldata from `U`
+///
+/// Environment and state opcodes (SLOAD, BALANCE, CALLER, ...) push zero, calls succeed
+/// without executing anything and GAS pushes a fixed amount.
 pub struct Vm<'a, T, U> {
+    /// Executed bytecode
     pub code: &'a [u8],
+
+    /// Offset of the next opcode
     pub pc: usize,
+
+    /// Stack, top at the end
     pub stack: Stack<T>,
+
+    /// Memory, as a log of labeled writes
     pub memory: Memory<T>,
+
+    /// Set once the code has stopped, returned, reverted or ran past its end
     pub stopped: bool,
+
+    /// Calldata source, see [`CallData`]
     pub calldata: &'a U,
 }
 
@@ -81,6 +111,7 @@ where
     T: std::fmt::Debug + Clone + Eq,
     U: CallData<T>,
 {
+    /// Creates a VM about to execute `code` from its start
     pub fn new(code: &'a [u8], calldata: &'a U) -> Self {
         Self {
             code,
@@ -92,6 +123,7 @@ where
         }
     }
 
+    /// Clones the VM state, e.g. to follow the other branch of a JUMPI
     pub fn fork(&self) -> Self {
         Vm {
             code: self.code,
@@ -103,6 +135,7 @@ where
         }
     }
 
+    /// Executes the opcode at `pc`
     pub fn step(&mut self) -> Result<StepResult<T>, Box<dyn error::Error>> {
         let op = self.code[self.pc];
         let ret = self.exec_opcode(op)?;
@@ -460,9 +493,10 @@ where
 
             op::CODECOPY => {
                 let raws0 = self.stack.pop()?;
-                let src_off: usize = self.stack.pop_uint()?.try_into()?;
+                let raws1 = self.stack.pop()?;
                 let raws2 = self.stack.pop()?;
                 let mem_off: u32 = (&raws0).try_into()?;
+                let src_off: usize = (&raws1).try_into()?;
                 let size: usize = (&raws2).try_into()?;
 
                 if size > 32768 {
@@ -477,6 +511,7 @@ where
                     let mut ret = StepResult::new(op, 3);
                     ret.args[0] = raws0;
                     ret.args[1] = raws2;
+                    ret.exargs.push(raws1); // code offset
                     self.memory.store(mem_off, data, None);
                     Ok(ret)
                 }
@@ -490,11 +525,12 @@ where
             }
 
             op::EXTCODECOPY => {
-                self.stack.pop()?;
-                self.stack.pop()?;
-                self.stack.pop()?;
-                self.stack.pop()?;
-                Ok(StepResult::new(op, 100))
+                let mut ret = StepResult::new(op, 100);
+                ret.args[0] = self.stack.pop()?; // address
+                ret.args[1] = self.stack.pop()?; // memory offset
+                ret.exargs.push(self.stack.pop()?); // code offset
+                ret.exargs.push(self.stack.pop()?); // size
+                Ok(ret)
             }
 
             op::RETURNDATASIZE => {
@@ -604,8 +640,10 @@ where
                 let dest_offset_num: U256 = (&dest_offset).into();
                 let dest_offset_u32: u32 = dest_offset_num.try_into()?;
 
-                let offset: u32 = self.stack.pop_uint()?.try_into()?;
-                let size: u32 = self.stack.pop_uint()?.try_into()?;
+                let offset_el = self.stack.pop()?;
+                let offset: u32 = (&offset_el).try_into()?;
+                let size_el = self.stack.pop()?;
+                let size: u32 = (&size_el).try_into()?;
                 if size > 2048 {
                     return Err(UnsupportedOpError { op }.into());
                 }
@@ -621,6 +659,8 @@ where
                 let mut ret = StepResult::new(op, gas_used);
                 ret.memory_load = Some(used);
                 ret.args[0] = dest_offset;
+                ret.exargs.push(offset_el); // source offset
+                ret.exargs.push(size_el);
                 Ok(ret)
             }
 
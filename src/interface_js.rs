@@ -18,36 +18,155 @@ const DOC_CONTRACT: &'static str = r#"
 /**
  * Contains the analysis results of a contract
  * @property functions - Array of functions found in the contract. Not present if no functions were extracted.
+ * @property specialFunctions - Fallback and receive functions. Not present if they were not detected.
  * @property storage - Array of persistent storage records found in the contract. Not present if storage layout was not extracted.
  * @property transientStorage - Array of transient storage records found in the contract. Not present if storage layout was not extracted.
+ * @property events - Array of events emitted by the contract, sorted by topic. Not present if events were not extracted.
+ * @property errors - Array of errors the contract can revert with. Not present if errors were not extracted.
  * @property disassembled - Array of bytecode instructions, where each element is a tuple of [offset: number, instruction: string]
  * @property basicBlocks - Array of basic blocks found in the contract. Not present if basic blocks were not analyzed.
  * @property controlFlowGraph - Control flow graph representation. Not present if CFG was not generated.
  * @property metadata - Terminal CBOR metadata. Not present unless requested and valid.
+ * @property proxy - Proxy recognized from the code. Not present unless requested and recognized.
+ * @property truncated - Contract-wide analysis phases that ran out of gas ('selectors' or 'storage'). Not present if nothing was truncated.
+ * @property diagnostics - Reasons why contract-wide analysis phases stopped early. Not present if nothing stopped early.
+ * @property trace - Recorded steps of the traced analysis phases. Not present if tracing was not requested.
  * @see ContractFunction
  * @see StorageRecord
  */
 export type Contract = {
     functions?: ContractFunction[],
+    specialFunctions?: SpecialFunctions,
     storage?: StorageRecord[],
     transientStorage?: StorageRecord[],
+    events?: Event[],
+    errors?: RevertError[],
     disassembled?: [number, string][],
     basicBlocks?: [number, number][],
     controlFlowGraph?: ControlFlowGraph,
     metadata?: CborMetadata,
+    proxy?: Proxy,
+    truncated?: AnalysisPhase[],
+    diagnostics?: Diagnostic[],
+    trace?: TraceStep[],
 };
 "#;
 /// @typedef {Object} Contract
 /// @description Contains the analysis results of a contract
 /// @property {ContractFunction[]} [functions] - Array of functions found in the contract. Not present if no functions were extracted
+/// @property {SpecialFunctions} [specialFunctions] - Fallback and receive functions. Not present if they were not detected
 /// @property {StorageRecord[]} [storage] - Array of persistent storage records found in the contract. Not present if storage layout was not extracted
 /// @property {StorageRecord[]} [transientStorage] - Array of transient storage records found in the contract. Not present if storage layout was not extracted
+/// @property {Event[]} [events] - Array of events emitted by the contract, sorted by topic. Not present if events were not extracted
+/// @property {RevertError[]} [errors] - Array of errors the contract can revert with. Not present if errors were not extracted
 /// @property {Array<Array<number|string>>} [disassembled] - Array of bytecode instructions, where each element is [offset, instruction]
 /// @property {Array<Array<number>>} [basicBlocks] - Array of basic blocks found in the contract. Not present if basic blocks were not analyzed.
 /// @property {ControlFlowGraph} [controlFlowGraph] - Control flow graph representation. Not present if CFG was not generated.
 /// @property {CborMetadata} [metadata] - Terminal CBOR metadata. Not present unless requested and valid.
+/// @property {Proxy} [proxy] - Proxy recognized from the code. Not present unless requested and recognized.
+/// @property {AnalysisPhase[]} [truncated] - Contract-wide analysis phases that ran out of gas ('selectors' or 'storage'). Not present if nothing was truncated.
+/// @property {Diagnostic[]} [diagnostics] - Reasons why contract-wide analysis phases stopped early. Not present if nothing stopped early.
+/// @property {TraceStep[]} [trace] - Recorded steps of the traced analysis phases. Not present if tracing was not requested.
 #[wasm_bindgen(skip_jsdoc)]
 pub fn dummy_contract() {}
+
+// {{{ TraceStep
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_TRACE_STEP: &'static str = r#"
+/**
+   A stack element or memory chunk of the abstract VM
+ * @property offset - Memory offset of the chunk; always 0 for stack elements
+ * @property data - Value as a hex string without '0x' prefix
+ * @property label - Pass-specific label tracking where the value came from. Not present if unlabeled
+ */
+export type TraceValue = {
+    offset: number,
+    data: string,
+    label?: string,
+};
+
+/**
+   The abstract VM state before executing one opcode
+ * @property phase - Analysis phase running the VM
+ * @property selector - Function being analyzed. Not present for contract-wide runs
+ * @property pc - Bytecode offset of the opcode
+ * @property opcode - Opcode mnemonic (e.g., 'CALLDATALOAD')
+ * @property stack - Stack elements, bottom to top
+ * @property memory - Memory chunks written so far
+ * @property state - Pass-specific analysis state. Not present if the phase records none
+ */
+export type TraceStep = {
+    phase: AnalysisPhase,
+    selector?: string,
+    pc: number,
+    opcode: string,
+    stack: TraceValue[],
+    memory: TraceValue[],
+    state?: string,
+};
+"#;
+/// @typedef {Object} TraceValue
+/// @description A stack element or memory chunk of the abstract VM
+/// @property {number} offset - Memory offset of the chunk; always 0 for stack elements
+/// @property {string} data - Value as a hex string without '0x' prefix
+/// @property {string} [label] - Pass-specific label tracking where the value came from. Not present if unlabeled
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_trace_value() {}
+
+/// @typedef {Object} TraceStep
+/// @description The abstract VM state before executing one opcode
+/// @property {AnalysisPhase} phase - Analysis phase running the VM
+/// @property {string} [selector] - Function being analyzed. Not present for contract-wide runs
+/// @property {number} pc - Bytecode offset of the opcode
+/// @property {string} opcode - Opcode mnemonic (e.g., 'CALLDATALOAD')
+/// @property {TraceValue[]} stack - Stack elements, bottom to top
+/// @property {TraceValue[]} memory - Memory chunks written so far
+/// @property {string} [state] - Pass-specific analysis state. Not present if the phase records none
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_trace_step() {}
+// }}}
+
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_ANALYSIS_PHASE: &'static str = r#"
+export type AnalysisPhase = 'selectors' | 'arguments' | 'stateMutability' | 'storage' | 'creation' | 'proxy' | 'events' | 'errors' | 'outputs' | 'specialFunctions' | 'argumentsValidation';
+"#;
+/// @typedef {('selectors'|'arguments'|'stateMutability'|'storage'|'creation'|'proxy'|'events'|'errors'|'outputs'|'specialFunctions'|'argumentsValidation')} AnalysisPhase
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_analysis_phase() {}
+
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_DIAGNOSTIC: &'static str = r#"
+/**
+ * Describes why an analysis phase stopped early
+ * @property phase - Analysis phase that was stopped
+ * @property pc - Bytecode offset of the opcode being executed when the phase stopped
+ * @property kind - What stopped the phase
+ */
+export type Diagnostic = {
+    phase: AnalysisPhase,
+    pc: number,
+    kind: DiagnosticKind,
+};
+export type DiagnosticKind =
+    | { type: 'unsupportedOpcode', value: number }
+    | { type: 'invalidJump' }
+    | { type: 'stackUnderflow' }
+    | { type: 'gasLimit' }
+    | { type: 'error', value: string };
+"#;
+/// @typedef {Object} Diagnostic
+/// @description Describes why an analysis phase stopped early
+/// @property {AnalysisPhase} phase - Analysis phase that was stopped
+/// @property {number} pc - Bytecode offset of the opcode being executed when the phase stopped
+/// @property {DiagnosticKind} kind - What stopped the phase
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_diagnostic() {}
+
+/// @typedef {Object} DiagnosticKind
+/// @property {('unsupportedOpcode'|'invalidJump'|'stackUnderflow'|'gasLimit'|'error')} type
+/// @property {(number|string)} [value] - Opcode byte for 'unsupportedOpcode', message for 'error'
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_diagnostic_kind() {}
 // }}}
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -84,6 +203,123 @@ pub fn dummy_cbor_entry() {}
 #[wasm_bindgen(skip_jsdoc)]
 pub fn dummy_cbor_value() {}
 
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_PROXY: &'static str = r#"
+/**
+ * Proxy recognized from the contract code
+ * @property kind - Proxy flavor
+ * @property implementation - Address all calls are delegated to, as a hex string without '0x' prefix. Null unless hardcoded in the bytecode
+ * @property slot - Storage slot holding the implementation or beacon address (the facet mapping for diamonds), as a hex string. Null for proxies hardcoding the implementation
+ * @property writers - Selectors of the proxy's own functions that can write to `slot`
+ * @property immutableArgs - Immutable args or ERC-3448 metadata appended to the code, as a hex string
+ */
+export type Proxy = {
+    kind: ProxyKind,
+    implementation: string | null,
+    slot: string | null,
+    writers: string[],
+    immutableArgs: string,
+};
+export type ProxyKind =
+    | 'eip1167'
+    | 'zeroAge'
+    | 'vyperForwarder'
+    | 'erc3448'
+    | 'clonesWithImmutableArgs'
+    | 'eip1967'
+    | 'eip1967Beacon'
+    | 'eip1822'
+    | 'openZeppelinLegacy'
+    | 'gnosisSafe'
+    | 'storageSlot'
+    | 'diamond';
+"#;
+/// @typedef {Object} Proxy
+/// @description Proxy recognized from the contract code
+/// @property {ProxyKind} kind - Proxy flavor
+/// @property {(string|null)} implementation - Address all calls are delegated to, as a hex string without '0x' prefix. Null unless hardcoded in the bytecode
+/// @property {(string|null)} slot - Storage slot holding the implementation or beacon address (the facet mapping for diamonds), as a hex string. Null for proxies hardcoding the implementation
+/// @property {string[]} writers - Selectors of the proxy's own functions that can write to `slot`
+/// @property {string} immutableArgs - Immutable args or ERC-3448 metadata appended to the code, as a hex string
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_proxy() {}
+
+/// @typedef {('eip1167'|'zeroAge'|'vyperForwarder'|'erc3448'|'clonesWithImmutableArgs'|'eip1967'|'eip1967Beacon'|'eip1822'|'openZeppelinLegacy'|'gnosisSafe'|'storageSlot'|'diamond')} ProxyKind
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_proxy_kind() {}
+
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_EVENT: &'static str = r#"
+/**
+ * Event emitted by the contract
+ * @property topic - Event signature hash (the first log topic) as a hex string without '0x' prefix
+ * @property indexed - Number of indexed arguments (0-3)
+ * @property data - Types of the non-indexed arguments in canonical format (e.g., 'address,uint256'); unnarrowed words are 'uint256'
+ */
+export type Event = {
+    topic: string,
+    indexed: number,
+    data: string,
+};
+"#;
+/// @typedef {Object} Event
+/// @description Event emitted by the contract
+/// @property {string} topic - Event signature hash (the first log topic) as a hex string without '0x' prefix
+/// @property {number} indexed - Number of indexed arguments (0-3)
+/// @property {string} data - Types of the non-indexed arguments in canonical format (e.g., 'address,uint256'); unnarrowed words are 'uint256'
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_event() {}
+
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_SPECIAL_FUNCTIONS: &'static str = r#"
+/**
+ * Entry points that handle calls matching no function selector
+ * @property fallback - State mutability of the fallback function ('payable' or 'nonpayable'), null if such calls revert
+ * @property receive - Whether plain ether transfers are accepted by a dedicated receive function
+ */
+export type SpecialFunctions = {
+    fallback: 'payable' | 'nonpayable' | null,
+    receive: boolean,
+};
+"#;
+/// @typedef {Object} SpecialFunctions
+/// @description Entry points that handle calls matching no function selector
+/// @property {(string|null)} fallback - State mutability of the fallback function ('payable' or 'nonpayable'), null if such calls revert
+/// @property {boolean} receive - Whether plain ether transfers are accepted by a dedicated receive function
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_special_functions() {}
+
+#[wasm_bindgen(typescript_custom_section)]
+const DOC_REVERT_ERROR: &'static str = r#"
+/**
+ * Error the contract can revert with
+ * @property kind - 'custom' for custom errors, 'panic' for Panic(uint256), 'reason' for Error(string) with a literal reason
+ * @property selector - Error selector as a 4-byte hex string without '0x' prefix
+ * @property arguments - Error argument types in canonical format (e.g., 'address,uint256')
+ * @property panicCode - Panic code. Only present for panics
+ * @property reason - Literal revert reason. Only present for Error(string)
+ * @property functions - Selectors of the functions that can revert with this error
+ */
+export type RevertError = {
+    kind: 'custom' | 'panic' | 'reason',
+    selector: string,
+    arguments: string,
+    panicCode?: number,
+    reason?: string,
+    functions: string[],
+};
+"#;
+/// @typedef {Object} RevertError
+/// @description Error the contract can revert with
+/// @property {('custom'|'panic'|'reason')} kind - 'custom' for custom errors, 'panic' for Panic(uint256), 'reason' for Error(string) with a literal reason
+/// @property {string} selector - Error selector as a 4-byte hex string without '0x' prefix
+/// @property {string} arguments - Error argument types in canonical format (e.g., 'address,uint256')
+/// @property {number} [panicCode] - Panic code. Only present for panics
+/// @property {string} [reason] - Literal revert reason. Only present for Error(string)
+/// @property {string[]} functions - Selectors of the functions that can revert with this error
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_revert_error() {}
+
 // {{{ Function
 #[wasm_bindgen(typescript_custom_section)]
 const DOC_FUNCTION: &'static str = r#"
@@ -93,14 +329,49 @@ const DOC_FUNCTION: &'static str = r#"
  * @property bytecodeOffset - Starting byte offset within the EVM bytecode for the function body.
  * @property dispatch - Whether the selector is handled by the normal ABI dispatcher or fallback dispatch logic.
  * @property arguments - Function argument types in canonical format (e.g., 'uint256,address[]'). Not present if arguments were not extracted
+ * @property argumentConfidence - Confidence (0-100) and alternative type of each argument, e.g. 'bool' for an inferred 'uint8'. Not present if arguments were not extracted
+ * @property argumentsValidation - Whether the function accepted calldata encoded with the inferred arguments. Not present if arguments were not validated
+ * @property outputs - Function return types in canonical format (e.g., 'address,string'). Not present if outputs were not extracted
  * @property stateMutability - Function's state mutability ("pure", "view", "payable", or "nonpayable"). Not present if state mutability were not extracted
+ * @property events - Topics of the events the function can emit, as hex strings without '0x' prefix. Not present if events were not extracted
+ * @property evidence - Opcodes that the arguments and state mutability are based on. Not present if evidence was not requested
+ * @property truncated - Per-function analysis phases that ran out of gas. Not present if nothing was truncated
+ * @property diagnostics - Reasons why per-function analysis phases stopped early. Not present if nothing stopped early
  */
 export type ContractFunction = {
     selector: string,
     bytecodeOffset: number,
     dispatch: 'abi' | 'fallback',
     arguments?: string,
+    argumentConfidence?: { confidence: number, alternative: string | null }[],
+    argumentsValidation?: 'validated' | 'contradicted' | 'inconclusive',
+    outputs?: string,
     stateMutability?: string,
+    events?: string[],
+    evidence?: FunctionEvidence,
+    truncated?: AnalysisPhase[],
+    diagnostics?: Diagnostic[],
+};
+
+/**
+ * An executed opcode that an inferred fact is based on
+ * @property pc - Bytecode offset of the opcode
+ * @property opcode - Opcode mnemonic (e.g., 'AND')
+ * @property reason - What the opcode shows (e.g., 'mask comparison', 'callvalue check')
+ */
+export type Evidence = {
+    pc: number,
+    opcode: string,
+    reason: string,
+};
+
+/**
+ * @property arguments - Opcodes that narrowed the type of each argument; empty for unnarrowed 'uint256' words. Not present if arguments were not extracted
+ * @property stateMutability - Reverting callvalue check and the first opcode ruling out 'pure' or 'view'. Not present if state mutability was not extracted
+ */
+export type FunctionEvidence = {
+    arguments?: Evidence[][],
+    stateMutability?: Evidence[],
 };
 "#;
 /// @typedef {Object} ContractFunction
@@ -109,9 +380,30 @@ export type ContractFunction = {
 /// @property {number} bytecodeOffset - Starting byte offset within the EVM bytecode for the function body
 /// @property {('abi'|'fallback')} dispatch - Whether the selector is handled by the normal ABI dispatcher or fallback dispatch logic
 /// @property {string} [arguments] - Function argument types in canonical format (e.g., 'uint256,address[]'). Not present if arguments were not extracted
+/// @property {Array<{confidence: number, alternative: (string|null)}>} [argumentConfidence] - Confidence (0-100) and alternative type of each argument, e.g. 'bool' for an inferred 'uint8'. Not present if arguments were not extracted
+/// @property {('validated'|'contradicted'|'inconclusive')} [argumentsValidation] - Whether the function accepted calldata encoded with the inferred arguments. Not present if arguments were not validated
+/// @property {string} [outputs] - Function return types in canonical format (e.g., 'address,string'). Not present if outputs were not extracted
 /// @property {string} [stateMutability] - Function's state mutability ("pure", "view", "payable", or "nonpayable"). Not present if state mutability were not extracted
+/// @property {string[]} [events] - Topics of the events the function can emit, as hex strings without '0x' prefix. Not present if events were not extracted
+/// @property {FunctionEvidence} [evidence] - Opcodes that the arguments and state mutability are based on. Not present if evidence was not requested
+/// @property {AnalysisPhase[]} [truncated] - Per-function analysis phases that ran out of gas. Not present if nothing was truncated
+/// @property {Diagnostic[]} [diagnostics] - Reasons why per-function analysis phases stopped early. Not present if nothing stopped early
 #[wasm_bindgen(skip_jsdoc)]
 pub fn dummy_function() {}
+
+/// @typedef {Object} Evidence
+/// @description An executed opcode that an inferred fact is based on
+/// @property {number} pc - Bytecode offset of the opcode
+/// @property {string} opcode - Opcode mnemonic (e.g., 'AND')
+/// @property {string} reason - What the opcode shows (e.g., 'mask comparison', 'callvalue check')
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_evidence() {}
+
+/// @typedef {Object} FunctionEvidence
+/// @property {Evidence[][]} [arguments] - Opcodes that narrowed the type of each argument; empty for unnarrowed 'uint256' words. Not present if arguments were not extracted
+/// @property {Evidence[]} [stateMutability] - Reverting callvalue check and the first opcode ruling out 'pure' or 'view'. Not present if state mutability was not extracted
+#[wasm_bindgen(skip_jsdoc)]
+pub fn dummy_function_evidence() {}
 // }}}
 
 // {{{ StorageRecord
@@ -124,13 +416,15 @@ const DOC_STORAGE: &'static str = r#"
  * @property type - Variable type (e.g., 'uint256', 'mapping(address => uint256)', 'bytes32').
  * @property reads - Array of function selectors that read from this storage location.
  * @property writes - Array of function selectors that write to this storage location.
+ * @property evidence - Opcodes accessing this storage location. Not present if evidence was not requested
  */
 export type StorageRecord = {
     slot: string,
     offset: number,
     type: string,
     reads: string[],
-    writes: string[]
+    writes: string[],
+    evidence?: Evidence[]
 };
 "#;
 /// Represents a storage record found in the contract
@@ -141,6 +435,7 @@ export type StorageRecord = {
 /// @property {string} type - Variable type (e.g., 'uint256', 'mapping(address => uint256)', 'bytes32')
 /// @property {string[]} reads - Array of function selectors that read from this storage location
 /// @property {string[]} writes - Array of function selectors that write to this storage location
+/// @property {Evidence[]} [evidence] - Opcodes accessing this storage location. Not present if evidence was not requested
 #[wasm_bindgen(skip_jsdoc)]
 pub fn dummy_storage_record() {}
 // }}}
@@ -278,18 +573,39 @@ struct ContractInfoArgs {
     #[serde(default)]
     metadata: bool,
 
+    #[serde(default)]
+    proxy: bool,
+
+    #[serde(default)]
+    evidence: bool,
+
     #[serde(default)]
     selectors: bool,
 
+    #[serde(default, rename = "specialFunctions")]
+    special_functions: bool,
+
     #[serde(default)]
     arguments: bool,
 
+    #[serde(default, rename = "argumentsValidation")]
+    arguments_validation: bool,
+
+    #[serde(default)]
+    outputs: bool,
+
     #[serde(default, rename = "stateMutability")]
     state_mutability: bool,
 
     #[serde(default)]
     storage: bool,
 
+    #[serde(default)]
+    events: bool,
+
+    #[serde(default)]
+    errors: bool,
+
     #[serde(default)]
     disassemble: bool,
 
@@ -298,6 +614,16 @@ struct ContractInfoArgs {
 
     #[serde(default, rename = "controlFlowGraph")]
     control_flow_graph: bool,
+
+    #[serde(default)]
+    trace: Option<Vec<String>>,
+}
+
+#[derive(serde::Serialize)]
+struct TracedContract {
+    #[serde(flatten)]
+    contract: crate::Contract,
+    trace: Vec<crate::TraceStep>,
 }
 
 #[wasm_bindgen(typescript_custom_section)]
@@ -308,24 +634,40 @@ const DOC_CONTRACT_INFO: &'static str = r#"
  * @param code - Runtime bytecode as a hex string
  * @param args - Configuration options for the analysis
  * @param args.selectors - When true, includes function selectors in the output
+ * @param args.specialFunctions - When true, detects fallback and receive functions; implies selectors
  * @param args.arguments - When true, includes function arguments information
+ * @param args.argumentsValidation - When true, calls each function with values of the inferred argument types to check them; implies arguments
+ * @param args.outputs - When true, includes function return types; implies arguments
  * @param args.stateMutability - When true, includes state mutability information for functions
  * @param args.storage - When true, includes persistent and transient storage layout information
+ * @param args.events - When true, includes emitted events and the events of each function; implies arguments
+ * @param args.errors - When true, includes custom errors, panic codes and revert reasons; implies arguments
  * @param args.disassemble - When true, includes disassembled bytecode
  * @param args.basicBlocks - When true, includes basic block analysis
  * @param args.controlFlowGraph - When true, includes control flow graph analysis
  * @param args.metadata - When true, extracts terminal CBOR metadata
+ * @param args.proxy - When true, recognizes minimal proxies, clones and storage-slot proxies; implies selectors
+ * @param args.evidence - When true, includes the opcodes that argument types, state mutability and storage records are based on
+ * @param args.trace - Analysis phases (e.g., ['arguments']) whose abstract VM steps are recorded into the output `trace`
  * @returns Analyzed contract information
  */
 export function contractInfo(code: string, args: {
     selectors?: boolean,
+    specialFunctions?: boolean,
     arguments?: boolean,
+    argumentsValidation?: boolean,
+    outputs?: boolean,
     stateMutability?: boolean,
     storage?: boolean,
+    events?: boolean,
+    errors?: boolean,
     disassemble?: boolean,
     basicBlocks?: boolean,
     controlFlowGraph?: boolean,
-    metadata?: boolean
+    metadata?: boolean,
+    proxy?: boolean,
+    evidence?: boolean,
+    trace?: AnalysisPhase[]
 }): Contract;
 "#;
 /// Analyzes contract bytecode and returns contract information based on specified options.
@@ -333,37 +675,78 @@ export function contractInfo(code: string, args: {
 /// @param {string} code - Runtime bytecode as a hex string
 /// @param {Object} args - Configuration options for the analysis
 /// @param {boolean} [args.selectors] - When true, includes function selectors in the output
+/// @param {boolean} [args.specialFunctions] - When true, detects fallback and receive functions; implies selectors
 /// @param {boolean} [args.arguments] - When true, includes function arguments information
+/// @param {boolean} [args.argumentsValidation] - When true, calls each function with values of the inferred argument types to check them; implies arguments
+/// @param {boolean} [args.outputs] - When true, includes function return types; implies arguments
 /// @param {boolean} [args.stateMutability] - When true, includes state mutability information for functions
 /// @param {boolean} [args.storage] - When true, includes persistent and transient storage layout information
+/// @param {boolean} [args.events] - When true, includes emitted events and the events of each function; implies arguments
+/// @param {boolean} [args.errors] - When true, includes custom errors, panic codes and revert reasons; implies arguments
 /// @param {boolean} [args.disassemble] - When true, includes disassembled bytecode
 /// @param {boolean} [args.basicBlocks] - When true, includes basic block analysis
 /// @param {boolean} [args.controlFlowGraph] - When true, includes control flow graph analysis
 /// @param {boolean} [args.metadata] - When true, extracts terminal CBOR metadata
+/// @param {boolean} [args.proxy] - When true, recognizes minimal proxies, clones and storage-slot proxies; implies selectors
+/// @param {boolean} [args.evidence] - When true, includes the opcodes that argument types, state mutability and storage records are based on
+/// @param {AnalysisPhase[]} [args.trace] - Analysis phases (e.g., ['arguments']) whose abstract VM steps are recorded into the output `trace`
 /// @returns {Contract} Analyzed contract information
 #[wasm_bindgen(js_name = contractInfo, skip_typescript, skip_jsdoc)]
 pub fn contract_info(code: &str, args: JsValue) -> Result<JsValue, JsError> {
     let c = decode_hex_code(code)?;
     let args: ContractInfoArgs = serde_wasm_bindgen::from_value(args)?;
+    let recorder = args
+        .trace
+        .map(|phases| {
+            phases
+                .iter()
+                .map(|name| {
+                    crate::AnalysisPhase::ALL
+                        .into_iter()
+                        .find(|phase| phase.as_str() == name)
+                        .ok_or_else(|| JsError::new(&format!("unknown analysis phase: {name}")))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?
+        .map(crate::TraceRecorder::with_phases);
 
     let mut cargs = crate::ContractInfoArgs::new(&c);
 
     if args.metadata {
         cargs = cargs.with_metadata();
     }
+    if args.proxy {
+        cargs = cargs.with_proxy();
+    }
 
     if args.selectors {
         cargs = cargs.with_selectors();
     }
+    if args.special_functions {
+        cargs = cargs.with_special_functions();
+    }
     if args.arguments {
         cargs = cargs.with_arguments();
     }
+    if args.arguments_validation {
+        cargs = cargs.with_arguments_validation();
+    }
+    if args.outputs {
+        cargs = cargs.with_outputs();
+    }
     if args.state_mutability {
         cargs = cargs.with_state_mutability();
     }
     if args.storage {
         cargs = cargs.with_storage();
     }
+    if args.events {
+        cargs = cargs.with_events();
+    }
+    if args.errors {
+        cargs = cargs.with_errors();
+    }
     if args.disassemble {
         cargs = cargs.with_disassemble();
     }
@@ -373,8 +756,25 @@ pub fn contract_info(code: &str, args: JsValue) -> Result<JsValue, JsError> {
     if args.control_flow_graph {
         cargs = cargs.with_control_flow_graph();
     }
+    if args.evidence {
+        cargs = cargs.with_evidence();
+    }
+    if let Some(recorder) = &recorder {
+        cargs = cargs.with_trace(recorder);
+    }
 
     let info = crate::contract_info(cargs);
-    Ok(serde_wasm_bindgen::to_value(&info)?)
+    match recorder {
+        Some(recorder) => {
+            let traced = TracedContract {
+                contract: info,
+                trace: recorder.into_steps(),
+            };
+            // flattening serializes a map, which would become a JS Map by default
+            let serializer = serde_wasm_bindgen::Serializer::new().serialize_maps_as_objects(true);
+            Ok(serde::Serialize::serialize(&traced, &serializer)?)
+        }
+        None => Ok(serde_wasm_bindgen::to_value(&info)?),
+    }
 }
 // }}}
@@ -22,13 +22,101 @@ fn input_to_bytes<'a>(code: &'a Bound<'a, PyAny>) -> PyResult<Cow<'a, [u8]>> {
     }
 }
 
+fn types_to_string(types: &[crate::DynSolType]) -> String {
+    types
+        .iter()
+        .map(|t| t.sol_type_name().to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn opt_repr(v: Option<&str>) -> String {
+    v.map_or_else(|| "None".to_string(), |v| format!("\"{v}\""))
+}
+
+fn list_repr<T>(v: &[T], repr: impl Fn(&T) -> String) -> String {
+    format!("[{}]", v.iter().map(repr).collect::<Vec<_>>().join(", "))
+}
+
 #[pymodule]
 mod evmole {
     use crate::control_flow_graph::BlockType;
 
     use super::*;
 
+    // {{{ Evidence
+    #[pyclass(name = "Evidence", get_all, skip_from_py_object)]
+    #[derive(Clone)]
+    struct PyEvidence {
+        pc: usize,
+        opcode: String,
+        reason: String,
+    }
+
+    #[pymethods]
+    impl PyEvidence {
+        fn __repr__(&self) -> String {
+            format!(
+                "Evidence(pc={}, opcode={:?}, reason={:?})",
+                self.pc, self.opcode, self.reason
+            )
+        }
+    }
+
+    impl From<crate::Evidence> for PyEvidence {
+        fn from(e: crate::Evidence) -> Self {
+            PyEvidence {
+                pc: e.pc,
+                opcode: e.opcode_name().to_string(),
+                reason: e.reason.to_string(),
+            }
+        }
+    }
+
+    #[pyclass(name = "FunctionEvidence", get_all, skip_from_py_object)]
+    #[derive(Clone)]
+    struct PyFunctionEvidence {
+        arguments: Option<Vec<Vec<PyEvidence>>>,
+        state_mutability: Option<Vec<PyEvidence>>,
+    }
+
+    #[pymethods]
+    impl PyFunctionEvidence {
+        fn __repr__(&self) -> String {
+            format!(
+                "FunctionEvidence(arguments={}, state_mutability={})",
+                self.arguments.as_ref().map_or_else(
+                    || "None".to_string(),
+                    |v| list_repr(v, |e| list_repr(e, PyEvidence::__repr__))
+                ),
+                self.state_mutability.as_ref().map_or_else(
+                    || "None".to_string(),
+                    |v| list_repr(v, PyEvidence::__repr__)
+                ),
+            )
+        }
+    }
+    // }}}
+
     // {{{ Function
+    #[pyclass(name = "ArgumentConfidence", get_all, skip_from_py_object)]
+    #[derive(Clone)]
+    struct PyArgumentConfidence {
+        confidence: u8,
+        alternative: Option<String>,
+    }
+
+    #[pymethods]
+    impl PyArgumentConfidence {
+        fn __repr__(&self) -> String {
+            format!(
+                "ArgumentConfidence(confidence={}, alternative={})",
+                self.confidence,
+                opt_repr(self.alternative.as_deref()),
+            )
+        }
+    }
+
     #[pyclass(name = "Function", get_all, skip_from_py_object)]
     #[derive(Clone)]
     struct PyFunction {
@@ -36,23 +124,107 @@ mod evmole {
         bytecode_offset: usize,
         dispatch: String,
         arguments: Option<String>,
+        argument_confidence: Option<Vec<PyArgumentConfidence>>,
+        arguments_validation: Option<String>,
+        outputs: Option<String>,
         state_mutability: Option<String>,
+        events: Option<Vec<String>>,
+        evidence: Option<PyFunctionEvidence>,
     }
 
     #[pymethods]
     impl PyFunction {
         fn __repr__(&self) -> String {
             format!(
-                "Function(selector={:?}, bytecode_offset={}, dispatch={:?}, arguments={}, state_mutability={})",
+                "Function(selector={:?}, bytecode_offset={}, dispatch={:?}, arguments={}, argument_confidence={}, arguments_validation={}, outputs={}, state_mutability={}, events={}, evidence={})",
                 self.selector,
                 self.bytecode_offset,
                 self.dispatch,
-                self.arguments
-                    .as_deref()
-                    .map_or_else(|| "None".to_string(), |v| format!("\"{v}\"")),
-                self.state_mutability
-                    .as_deref()
-                    .map_or_else(|| "None".to_string(), |v| format!("\"{v}\"")),
+                opt_repr(self.arguments.as_deref()),
+                self.argument_confidence.as_ref().map_or_else(
+                    || "None".to_string(),
+                    |v| list_repr(v, PyArgumentConfidence::__repr__)
+                ),
+                opt_repr(self.arguments_validation.as_deref()),
+                opt_repr(self.outputs.as_deref()),
+                opt_repr(self.state_mutability.as_deref()),
+                self.events
+                    .as_ref()
+                    .map_or_else(|| "None".to_string(), |v| format!("{v:?}")),
+                self.evidence
+                    .as_ref()
+                    .map_or_else(|| "None".to_string(), PyFunctionEvidence::__repr__),
+            )
+        }
+    }
+    // }}}
+
+    // {{{ SpecialFunctions
+    #[pyclass(name = "SpecialFunctions", get_all, skip_from_py_object)]
+    #[derive(Clone)]
+    struct PySpecialFunctions {
+        fallback: Option<String>,
+        receive: bool,
+    }
+
+    #[pymethods]
+    impl PySpecialFunctions {
+        fn __repr__(&self) -> String {
+            format!(
+                "SpecialFunctions(fallback={}, receive={})",
+                opt_repr(self.fallback.as_deref()),
+                if self.receive { "True" } else { "False" }
+            )
+        }
+    }
+    // }}}
+
+    // {{{ Event
+    #[pyclass(name = "Event", get_all, skip_from_py_object)]
+    #[derive(Clone)]
+    struct PyEvent {
+        topic: String,
+        indexed: u8,
+        data: String,
+    }
+
+    #[pymethods]
+    impl PyEvent {
+        fn __repr__(&self) -> String {
+            format!(
+                "Event(topic=\"{}\", indexed={}, data=\"{}\")",
+                self.topic, self.indexed, self.data
+            )
+        }
+    }
+    // }}}
+
+    // {{{ RevertError
+    #[pyclass(name = "RevertError", get_all, skip_from_py_object)]
+    #[derive(Clone)]
+    struct PyRevertError {
+        kind: String,
+        selector: String,
+        arguments: String,
+        panic_code: Option<u64>,
+        reason: Option<String>,
+        functions: Vec<String>,
+    }
+
+    #[pymethods]
+    impl PyRevertError {
+        fn __repr__(&self) -> String {
+            format!(
+                "RevertError(kind=\"{}\", selector=\"{}\", arguments=\"{}\", panic_code={}, reason={}, functions={:?})",
+                self.kind,
+                self.selector,
+                self.arguments,
+                self.panic_code
+                    .map_or_else(|| "None".to_string(), |v| v.to_string()),
+                self.reason
+                    .as_ref()
+                    .map_or_else(|| "None".to_string(), |v| format!("{v:?}")),
+                self.functions,
             )
         }
     }
@@ -67,17 +239,41 @@ mod evmole {
         r#type: String,
         reads: Vec<String>,
         writes: Vec<String>,
+        evidence: Option<Vec<PyEvidence>>,
     }
 
     #[pymethods]
     impl PyStorageRecord {
         fn __repr__(&self) -> String {
             format!(
-                "StorageRecord(slot=\"{}\", offset={}, type=\"{}\", reads={:?}, writes={:?})",
-                self.slot, self.offset, self.r#type, self.reads, self.writes
+                "StorageRecord(slot=\"{}\", offset={}, type=\"{}\", reads={:?}, writes={:?}, evidence={})",
+                self.slot,
+                self.offset,
+                self.r#type,
+                self.reads,
+                self.writes,
+                self.evidence.as_ref().map_or_else(
+                    || "None".to_string(),
+                    |v| list_repr(v, PyEvidence::__repr__)
+                ),
             )
         }
     }
+
+    impl From<crate::StorageRecord> for PyStorageRecord {
+        fn from(v: crate::StorageRecord) -> Self {
+            PyStorageRecord {
+                slot: hex::encode(v.slot),
+                offset: v.offset,
+                r#type: v.r#type,
+                reads: v.reads.into_iter().map(hex::encode).collect(),
+                writes: v.writes.into_iter().map(hex::encode).collect(),
+                evidence: v
+                    .evidence
+                    .map(|ev| ev.into_iter().map(PyEvidence::from).collect()),
+            }
+        }
+    }
     // }}}
 
     // {{{ DynamicJump
@@ -288,18 +484,22 @@ mod evmole {
     struct PyContract {
         metadata: Option<PyCborMetadata>,
         functions: Option<Vec<PyFunction>>,
+        special_functions: Option<PySpecialFunctions>,
         storage: Option<Vec<PyStorageRecord>>,
         transient_storage: Option<Vec<PyStorageRecord>>,
+        events: Option<Vec<PyEvent>>,
+        errors: Option<Vec<PyRevertError>>,
         disassembled: Option<Vec<(usize, String)>>,
         basic_blocks: Option<Vec<(usize, usize)>>,
         control_flow_graph: Option<PyControlFlowGraph>,
+        trace: Option<String>,
     }
 
     #[pymethods]
     impl PyContract {
         fn __repr__(&self) -> String {
             format!(
-                "Contract(functions={}, storage={}, transient_storage={}, disassembled={}, basic_blocks={}, control_flow_graph={}, metadata={})",
+                "Contract(functions={}, special_functions={}, storage={}, transient_storage={}, events={}, errors={}, disassembled={}, basic_blocks={}, control_flow_graph={}, metadata={})",
                 self.functions.as_ref().map_or_else(
                     || "None".to_string(),
                     |v| format!(
@@ -310,6 +510,9 @@ mod evmole {
                             .join(", ")
                     )
                 ),
+                self.special_functions
+                    .as_ref()
+                    .map_or_else(|| "None".to_string(), PySpecialFunctions::__repr__),
                 self.storage.as_ref().map_or_else(
                     || "None".to_string(),
                     |v| format!(
@@ -330,6 +533,13 @@ mod evmole {
                             .join(", ")
                     )
                 ),
+                self.events
+                    .as_ref()
+                    .map_or_else(|| "None".to_string(), |v| list_repr(v, PyEvent::__repr__)),
+                self.errors.as_ref().map_or_else(
+                    || "None".to_string(),
+                    |v| list_repr(v, PyRevertError::__repr__)
+                ),
                 self.disassembled
                     .as_ref()
                     .map_or_else(|| "None".to_string(), |v| format!("{v:?}")),
@@ -349,34 +559,72 @@ mod evmole {
 
     // {{{ contract_info
     #[pyfunction]
-    #[pyo3(signature = (code, *, selectors=false, arguments=false, state_mutability=false, storage=false, disassemble=false, basic_blocks=false, control_flow_graph=false, metadata=false))]
+    #[pyo3(signature = (code, *, selectors=false, special_functions=false, arguments=false, arguments_validation=false, outputs=false, state_mutability=false, storage=false, events=false, errors=false, disassemble=false, basic_blocks=false, control_flow_graph=false, metadata=false, evidence=false, trace=None))]
     #[allow(clippy::too_many_arguments)]
     fn contract_info(
         code: &Bound<'_, PyAny>,
         selectors: bool,
+        special_functions: bool,
         arguments: bool,
+        arguments_validation: bool,
+        outputs: bool,
         state_mutability: bool,
         storage: bool,
+        events: bool,
+        errors: bool,
         disassemble: bool,
         basic_blocks: bool,
         control_flow_graph: bool,
         metadata: bool,
+        evidence: bool,
+        trace: Option<Vec<String>>,
     ) -> PyResult<PyContract> {
         let code_bytes = input_to_bytes(code)?;
+        let recorder = trace
+            .map(|phases| {
+                phases
+                    .iter()
+                    .map(|name| {
+                        crate::AnalysisPhase::ALL
+                            .into_iter()
+                            .find(|phase| phase.as_str() == name)
+                            .ok_or_else(|| {
+                                PyValueError::new_err(format!("unknown analysis phase: {name}"))
+                            })
+                    })
+                    .collect::<PyResult<Vec<_>>>()
+            })
+            .transpose()?
+            .map(crate::TraceRecorder::with_phases);
         let mut args = crate::ContractInfoArgs::new(&code_bytes);
 
         if selectors {
             args = args.with_selectors();
         }
+        if special_functions {
+            args = args.with_special_functions();
+        }
         if arguments {
             args = args.with_arguments();
         }
+        if arguments_validation {
+            args = args.with_arguments_validation();
+        }
+        if outputs {
+            args = args.with_outputs();
+        }
         if state_mutability {
             args = args.with_state_mutability();
         }
         if storage {
             args = args.with_storage();
         }
+        if events {
+            args = args.with_events();
+        }
+        if errors {
+            args = args.with_errors();
+        }
         if disassemble {
             args = args.with_disassemble();
         }
@@ -389,8 +637,18 @@ mod evmole {
         if metadata {
             args = args.with_metadata();
         }
+        if evidence {
+            args = args.with_evidence();
+        }
+        if let Some(recorder) = &recorder {
+            args = args.with_trace(recorder);
+        }
 
         let info = crate::contract_info(args);
+        let trace = recorder
+            .map(|recorder| serde_json::to_string(&recorder.into_steps()))
+            .transpose()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
 
         let functions = info.functions.map(|fns| {
             fns.into_iter()
@@ -398,38 +656,69 @@ mod evmole {
                     selector: hex::encode(f.selector),
                     bytecode_offset: f.bytecode_offset,
                     dispatch: f.dispatch.as_str().to_string(),
-                    arguments: f.arguments.map(|fargs| {
-                        fargs
-                            .into_iter()
-                            .map(|t| t.sol_type_name().to_string())
-                            .collect::<Vec<String>>()
-                            .join(",")
+                    arguments: f.arguments.as_deref().map(types_to_string),
+                    argument_confidence: f.argument_confidence.map(|c| {
+                        c.into_iter()
+                            .map(|c| PyArgumentConfidence {
+                                confidence: c.confidence,
+                                alternative: c.alternative.map(|t| t.sol_type_name().to_string()),
+                            })
+                            .collect()
                     }),
+                    arguments_validation: f.arguments_validation.map(|v| v.as_str().to_string()),
+                    outputs: f.outputs.as_deref().map(types_to_string),
                     state_mutability: f.state_mutability.map(|sm| sm.as_json_str().to_string()),
+                    events: f
+                        .events
+                        .map(|topics| topics.into_iter().map(hex::encode).collect()),
+                    evidence: f.evidence.map(|e| PyFunctionEvidence {
+                        arguments: e.arguments.map(|args| {
+                            args.into_iter()
+                                .map(|ev| ev.into_iter().map(PyEvidence::from).collect())
+                                .collect()
+                        }),
+                        state_mutability: e
+                            .state_mutability
+                            .map(|ev| ev.into_iter().map(PyEvidence::from).collect()),
+                    }),
                 })
                 .collect()
         });
 
-        let storage = info.storage.map(|st| {
-            st.into_iter()
-                .map(|v| PyStorageRecord {
-                    slot: hex::encode(v.slot),
-                    offset: v.offset,
-                    r#type: v.r#type,
-                    reads: v.reads.into_iter().map(hex::encode).collect(),
-                    writes: v.writes.into_iter().map(hex::encode).collect(),
+        let storage = info
+            .storage
+            .map(|st| st.into_iter().map(PyStorageRecord::from).collect());
+
+        let transient_storage = info
+            .transient_storage
+            .map(|st| st.into_iter().map(PyStorageRecord::from).collect());
+
+        let special_functions = info.special_functions.map(|sf| PySpecialFunctions {
+            fallback: sf.fallback.map(|sm| sm.as_json_str().to_string()),
+            receive: sf.receive,
+        });
+
+        let events = info.events.map(|events| {
+            events
+                .into_iter()
+                .map(|e| PyEvent {
+                    topic: hex::encode(e.topic),
+                    indexed: e.indexed,
+                    data: types_to_string(&e.data),
                 })
                 .collect()
         });
 
-        let transient_storage = info.transient_storage.map(|st| {
-            st.into_iter()
-                .map(|v| PyStorageRecord {
-                    slot: hex::encode(v.slot),
-                    offset: v.offset,
-                    r#type: v.r#type,
-                    reads: v.reads.into_iter().map(hex::encode).collect(),
-                    writes: v.writes.into_iter().map(hex::encode).collect(),
+        let errors = info.errors.map(|errors| {
+            errors
+                .into_iter()
+                .map(|e| PyRevertError {
+                    kind: e.kind.as_str().to_string(),
+                    selector: hex::encode(e.selector),
+                    arguments: types_to_string(&e.arguments),
+                    panic_code: e.panic_code,
+                    reason: e.reason,
+                    functions: e.functions.into_iter().map(hex::encode).collect(),
                 })
                 .collect()
         });
@@ -488,11 +777,15 @@ mod evmole {
                     .collect(),
             }),
             functions,
+            special_functions,
             storage,
             transient_storage,
+            events,
+            errors,
             disassembled: info.disassembled,
             basic_blocks: info.basic_blocks,
             control_flow_graph,
+            trace,
         })
     }
     // }}}
@@ -5,21 +5,62 @@
 //! Accuracy and speed comparison with other tools, as well as Python and JavaScript libraries,
 //! are available on [GitHub](https://github.com/cdump/evmole/tree/master#benchmark)
 
+pub use arguments::ArgumentConfidence;
+pub use batch::{BatchStats, ContractBatch, contract_info_batch};
 pub use contract_info::contract_info;
-pub use contract_info::{Contract, ContractInfoArgs, Function, SelectorDispatch};
+pub use contract_info::{AnalysisPhase, Contract, ContractInfoArgs, Function, SelectorDispatch};
+pub use creation::{
+    ConstructorArguments, CreationArgs, CreationError, CreationInfo, constructor_arguments,
+    creation_info,
+};
+pub use decode::{DecodeError, DecodedCalldata, decode_calldata};
+pub use diagnostics::{Diagnostic, DiagnosticKind};
+pub use emulator::{
+    Account, BlockEnv, Call, CallResult, Deployment, Execution, ExitReason, Host, Immutable, Log,
+    StateHost, StorageChange, simulate_call, simulate_deployment,
+};
+pub use errors::{RevertError, RevertKind};
+pub use events::Event;
+pub use evidence::{Evidence, FunctionEvidence};
 pub use metadata::{CborEntry, CborMetadata, CborValue};
+pub use proxy::{
+    CodeOrigin, DiamondFunctions, FacetFunction, Proxy, ProxyContract, ProxyKind,
+    diamond_functions, proxy_contract_info,
+};
+pub use samples::{SampleKind, sample_calldata, sample_values};
+pub use signatures::{SignatureDb, SignatureResolver, rank_signatures};
+pub use special_functions::SpecialFunctions;
 pub use storage::StorageRecord;
+pub use trace::{TraceRecorder, TraceSink, TraceStep, TraceValue};
+pub use validation::ArgumentsValidation;
 
+pub mod abi;
 mod arguments;
+mod batch;
 mod collections;
 mod contract_info;
 pub mod control_flow_graph;
-mod evm;
+mod creation;
+mod decode;
+mod diagnostics;
+mod emulator;
+mod errors;
+mod events;
+mod evidence;
+pub mod evm;
 mod metadata;
+mod outputs;
+mod payload;
+mod proxy;
+mod samples;
 mod selectors;
+mod signatures;
+mod special_functions;
 mod state_mutability;
 mod storage;
+mod trace;
 mod utils;
+mod validation;
 
 #[cfg(feature = "serde")]
 mod serialize;
@@ -30,12 +71,21 @@ pub type Selector = [u8; 4];
 /// A 32-byte storage slot identifier in EVM storage.
 pub type Slot = [u8; 32];
 
+/// A 32-byte event topic
+pub type Topic = [u8; 32];
+
 /// Function's state mutability
 pub type StateMutability = alloy_dyn_abi::parser::StateMutability;
 
 /// A dynamic Solidity type
 pub type DynSolType = alloy_dyn_abi::DynSolType;
 
+/// A dynamic Solidity value
+pub type DynSolValue = alloy_dyn_abi::DynSolValue;
+
+/// A Solidity JSON ABI, see [`Contract::abi`]
+pub type JsonAbi = alloy_json_abi::JsonAbi;
+
 #[cfg(feature = "python")]
 mod interface_py;
 
@@ -0,0 +1,113 @@
+//! Inference of function return types from the memory passed to RETURN.
+
+use crate::{
+    DynSolType, Selector,
+    diagnostics::Diagnostic,
+    evm::{op, vm::StepResult},
+    payload::{self, Label, MAX_PAYLOAD_SIZE, PayloadVm},
+    trace::Tracer,
+};
+
+/// Infers the types of the returned data, if it is a sequence of words
+fn returned(vm: &PayloadVm, ret: &StepResult<Label>) -> Option<Vec<DynSolType>> {
+    let StepResult {
+        op: op::RETURN,
+        args: [offset, size],
+        ..
+    } = ret
+    else {
+        return None;
+    };
+    let (Ok(off), Ok(size)) = (u32::try_from(offset), u32::try_from(size)) else {
+        return None;
+    };
+    if size > MAX_PAYLOAD_SIZE || !size.is_multiple_of(32) {
+        return None;
+    }
+    let (data, chunks) = vm.memory.load(off, size);
+    Some(payload::infer_types(&data, &chunks))
+}
+
+/// Infers the return types of the function identified by `selector`
+///
+/// When paths return different layouts, the most specific non-empty one is kept.
+/// Functions that never return data have no outputs.
+pub(crate) fn function_outputs(
+    code: &[u8],
+    selector: Selector,
+    arguments: &[DynSolType],
+    gas_limit: u32,
+    tracer: Tracer,
+) -> (Vec<DynSolType>, Vec<Diagnostic>) {
+    let real_gas_limit = if gas_limit == 0 {
+        1e6 as u32
+    } else {
+        gas_limit
+    };
+
+    let (layouts, diagnostics) = payload::collect_payloads(
+        code,
+        selector,
+        arguments,
+        false,
+        real_gas_limit,
+        tracer,
+        returned,
+    );
+    let mut outputs = Vec::new();
+    for layout in layouts {
+        if (!layout.is_empty(), payload::score(&layout))
+            > (!outputs.is_empty(), payload::score(&outputs))
+        {
+            outputs = layout;
+        }
+    }
+    (outputs, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AnalysisPhase;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_address_and_dynamic() {
+        // 0x11223344: returns `address(uint160(sload(0)))`
+        // 0x55667788: returns "hi"
+        let code = hex::decode(
+            "60003560e01c80631122334414601a57635566778814603957005b\
+             5f5473ffffffffffffffffffffffffffffffffffffffff165f5260205ff3\
+             5b60205f52600260205261686960f01b60405260605ff3",
+        )
+        .unwrap();
+        let (outputs, diagnostics) = function_outputs(
+            &code,
+            [0x11, 0x22, 0x33, 0x44],
+            &[],
+            0,
+            Tracer::disabled(AnalysisPhase::Outputs),
+        );
+        assert_eq!(outputs, vec![DynSolType::Address]);
+        assert!(diagnostics.is_empty());
+
+        let (outputs, _) = function_outputs(
+            &code,
+            [0x55, 0x66, 0x77, 0x88],
+            &[],
+            0,
+            Tracer::disabled(AnalysisPhase::Outputs),
+        );
+        assert_eq!(outputs, vec![DynSolType::Bytes]);
+
+        // dispatcher falls through to STOP
+        let (outputs, _) = function_outputs(
+            &code,
+            [0; 4],
+            &[],
+            0,
+            Tracer::disabled(AnalysisPhase::Outputs),
+        );
+        assert!(outputs.is_empty());
+    }
+}
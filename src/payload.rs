@@ -0,0 +1,303 @@
+//! Layout inference for ABI-encoded payloads built in memory, shared by the log and
+//! revert passes.
+
+use crate::{
+    DynSolType, Selector,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{
+        U256, VAL_32_B,
+        calldata::{CallDataImpl, CallDataLabel, CallDataLabelType},
+        element::Element,
+        memory::MemoryChunks,
+        op,
+        vm::{StepResult, Vm},
+    },
+    trace::Tracer,
+    utils::{
+        and_mask_to_type, elabel, execute_until_function_start, explore_paths, match_first_two,
+    },
+};
+use std::collections::BTreeSet;
+
+const MAX_DEPTH: u32 = 8;
+
+/// Payloads longer than this are not decoded
+pub(crate) const MAX_PAYLOAD_SIZE: u32 = 4096;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum Label {
+    Constant,
+    Typed(DynSolType),
+    /// Length of a dynamic calldata argument of the given type
+    DynLen(DynSolType),
+}
+
+impl CallDataLabel for Label {
+    fn label(_: usize, tp: &DynSolType, label_type: CallDataLabelType) -> Option<Label> {
+        match label_type {
+            CallDataLabelType::RealValue => Some(Label::Typed(tp.clone())),
+            CallDataLabelType::DynLen => Some(Label::DynLen(tp.clone())),
+            CallDataLabelType::Offset => None,
+        }
+    }
+}
+
+pub(crate) type PayloadVm<'a> = Vm<'a, Label, CallDataImpl<Label>>;
+
+/// Number of types narrowed beyond the `uint256` and `bytes` defaults
+pub(crate) fn score(types: &[DynSolType]) -> usize {
+    types
+        .iter()
+        .filter(|t| !matches!(t, DynSolType::Uint(256) | DynSolType::Bytes))
+        .count()
+}
+
+/// Label of the 32-byte word stored at `off`, if a single labeled value covers it
+fn word_label(chunks: &MemoryChunks<Label>, off: usize) -> Option<&Label> {
+    chunks
+        .chunks
+        .iter()
+        .find(|c| c.dst_range.start <= off && c.dst_range.end >= off + 32)
+        .map(|c| &c.src_label)
+}
+
+/// Checks whether every byte of `range` was copied from a constant
+pub(crate) fn is_constant(chunks: &MemoryChunks<Label>, range: std::ops::Range<usize>) -> bool {
+    range.into_iter().all(|i| {
+        chunks
+            .chunks
+            .iter()
+            .any(|c| c.dst_range.contains(&i) && c.src_label == Label::Constant)
+    })
+}
+
+/// Returns the type of the dynamic value whose offset is stored in the word at `off`,
+/// with the position of its tail
+fn dynamic_tail(
+    data: &[u8],
+    chunks: &MemoryChunks<Label>,
+    off: usize,
+) -> Option<(DynSolType, usize)> {
+    let word = |o: usize| data.get(o..o.checked_add(32)?).map(U256::from_be_slice);
+    let tail = usize::try_from(word(off)?).ok()?;
+    if !tail.is_multiple_of(32) || tail <= off {
+        return None;
+    }
+    let len = usize::try_from(word(tail)?).ok()?;
+    let tp = match word_label(chunks, tail) {
+        Some(Label::DynLen(tp)) => tp.clone(),
+        _ => DynSolType::Bytes,
+    };
+    let tail_size = match tp {
+        DynSolType::Array(_) => len.checked_mul(32)?,
+        _ => len.checked_next_multiple_of(32)?,
+    };
+    if tail.checked_add(32)?.checked_add(tail_size)? > data.len() {
+        return None;
+    }
+    Some((tp, tail))
+}
+
+/// Infers the ABI types of `data` from the labels of the memory it was loaded from
+///
+/// Words whose type could not be narrowed are `uint256`, dynamic values of unknown
+/// type are `bytes`. Returns nothing if `data` is not a sequence of words.
+pub(crate) fn infer_types(data: &[u8], chunks: &MemoryChunks<Label>) -> Vec<DynSolType> {
+    if !data.len().is_multiple_of(32) {
+        return Vec::new();
+    }
+    let mut types = Vec::new();
+    let mut head_end = data.len();
+    let mut off = 0;
+    while off < head_end {
+        let tp = match word_label(chunks, off) {
+            Some(Label::Typed(tp)) if tp.is_dynamic() => DynSolType::Uint(256),
+            Some(Label::Typed(tp)) => tp.clone(),
+            _ => match dynamic_tail(data, chunks, off) {
+                Some((tp, tail)) => {
+                    head_end = head_end.min(tail);
+                    tp
+                }
+                None => DynSolType::Uint(256),
+            },
+        };
+        types.push(tp);
+        off += 32;
+    }
+    types
+}
+
+/// Labels constants and values whose type is implied by the opcode producing them
+fn propagate(
+    vm: &mut PayloadVm,
+    ret: &StepResult<Label>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match ret {
+        StepResult {
+            op: op::PUSH0..=op::PUSH32,
+            ..
+        } => {
+            vm.stack.peek_mut()?.label = Some(Label::Constant);
+        }
+
+        StepResult {
+            op: op::CODECOPY,
+            args: [mem_off, ..],
+            ..
+        } => {
+            let off: u32 = mem_off.try_into()?;
+            if let Some(entry) = vm.memory.get_mut(off) {
+                entry.label = Some(Label::Constant);
+            }
+        }
+
+        StepResult {
+            op: op::CALLER | op::ORIGIN | op::ADDRESS,
+            ..
+        } => {
+            vm.stack.peek_mut()?.label = Some(Label::Typed(DynSolType::Address));
+        }
+
+        StepResult { op: op::ISZERO, .. } => {
+            vm.stack.peek_mut()?.label = Some(Label::Typed(DynSolType::Bool));
+        }
+
+        StepResult {
+            op: op::SIGNEXTEND,
+            args: [s0, ..],
+            ..
+        } => {
+            if s0.data < VAL_32_B {
+                let bits = (s0.data[31] as usize + 1) * 8;
+                vm.stack.peek_mut()?.label = Some(Label::Typed(DynSolType::Int(bits)));
+            }
+        }
+
+        StepResult {
+            op: op::AND,
+            args: match_first_two!(elabel!(label @ Label::Typed(_)), _),
+            ..
+        } => {
+            vm.stack.peek_mut()?.label = Some(label.clone());
+        }
+
+        StepResult {
+            op: op::AND,
+            args: match_first_two!(mask @ elabel!(Label::Constant), _),
+            ..
+        } => {
+            if let Some(tp) = and_mask_to_type(mask.into()) {
+                vm.stack.peek_mut()?.label = Some(Label::Typed(tp));
+            }
+        }
+
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns a selector not in `selectors`, used to reach the fallback
+pub(crate) fn fallback_selector(selectors: &BTreeSet<Selector>) -> Selector {
+    let mut selector: Selector = [0xff, 0xff, 0xff, 0xff];
+    while selectors.contains(&selector) {
+        selector = (u32::from_be_bytes(selector) - 1).to_be_bytes();
+    }
+    selector
+}
+
+/// Explores all paths of the function identified by `selector` (or of the fallback),
+/// collecting what `found` returns for the steps that build a payload
+pub(crate) fn collect_payloads<R>(
+    code: &[u8],
+    selector: Selector,
+    arguments: &[DynSolType],
+    is_fallback: bool,
+    gas_limit: u32,
+    tracer: Tracer,
+    mut found: impl FnMut(&PayloadVm, &StepResult<Label>) -> Option<R>,
+) -> (Vec<R>, Vec<Diagnostic>) {
+    let phase = tracer.phase();
+    let tracer = if is_fallback {
+        tracer
+    } else {
+        tracer.with_selector(selector)
+    };
+
+    let calldata = CallDataImpl::<Label>::new(selector, arguments);
+    let mut vm = Vm::new(code, &calldata);
+    let mut diagnostics = Vec::new();
+
+    let mut gas_used = 0;
+    if !is_fallback {
+        match execute_until_function_start(&mut vm, gas_limit) {
+            Ok(g) => gas_used = g,
+            Err(e) => {
+                e.record(&mut diagnostics, phase);
+                return (Vec::new(), diagnostics);
+            }
+        }
+    }
+
+    let mut payloads = Vec::new();
+    let mut errors = Vec::new();
+    explore_paths(
+        vm,
+        gas_limit.saturating_sub(gas_used),
+        MAX_DEPTH,
+        tracer,
+        &mut diagnostics,
+        |vm, _: &mut (), pc, ret| {
+            if let Err(e) = propagate(vm, ret) {
+                errors.push((pc, DiagnosticKind::from_error(e.as_ref())));
+                vm.stopped = true;
+            } else if let Some(payload) = found(vm, ret) {
+                payloads.push(payload);
+            }
+            None::<()>
+        },
+    );
+    for (pc, kind) in errors {
+        record(&mut diagnostics, phase, pc, kind);
+    }
+    (payloads, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::memory::Memory;
+
+    fn word(v: u64) -> Vec<u8> {
+        U256::from(v).to_be_bytes::<32>().to_vec()
+    }
+
+    #[test]
+    fn test_infer_dynamic_types() {
+        let mut memory: Memory<Label> = Memory::new();
+        memory.store(0, word(7), None);
+        memory.store(32, word(0x40), Some(Label::Constant));
+        memory.store(64, word(3), None);
+        memory.store(96, b"abc".to_vec(), None);
+        memory.store(99, vec![0; 29], None);
+
+        let (data, chunks) = memory.load(0, 128);
+        assert_eq!(
+            infer_types(&data, &chunks),
+            vec![DynSolType::Uint(256), DynSolType::Bytes]
+        );
+        assert!(!is_constant(&chunks, 96..99));
+
+        memory.store(64, word(3), Some(Label::DynLen(DynSolType::String)));
+        memory.store(96, b"abc".to_vec(), Some(Label::Constant));
+        let (data, chunks) = memory.load(0, 128);
+        assert_eq!(
+            infer_types(&data, &chunks),
+            vec![DynSolType::Uint(256), DynSolType::String]
+        );
+        assert!(is_constant(&chunks, 96..99));
+
+        // not a sequence of words
+        let (data, chunks) = memory.load(0, 40);
+        assert!(infer_types(&data, &chunks).is_empty());
+    }
+}
@@ -0,0 +1,239 @@
+use super::{Proxy, ProxyKind};
+use crate::evm::op;
+use alloy_primitives::{Address, hex};
+
+// EIP-1167: PUSHn with a (possibly vanity-shortened) address between the two parts,
+// followed by the JUMPDEST offset byte and `57fd5bf3`
+const EIP1167_PREFIX: [u8; 9] = hex!("363d3d373d3d3d363d");
+const EIP1167_SUFFIX: [u8; 10] = hex!("5af43d82803e903d9160");
+const EIP1167_TAIL: [u8; 4] = hex!("57fd5bf3");
+
+const ZERO_AGE_PREFIX: [u8; 10] = hex!("3d3d3d3d363d3d37363d");
+const ZERO_AGE_SUFFIX: [u8; 13] = hex!("5af43d3d93803e602a57fd5bf3");
+
+const VYPER_PREFIX: [u8; 14] = hex!("3660006000376110006000366000");
+const VYPER_SUFFIX: [u8; 16] = hex!("5af4602c57600080fd5b6110006000f3");
+
+const ERC3448_PREFIX: [u8; 20] = hex!("363d3d373d3d3d3d60368038038091363936013d");
+const ERC3448_SUFFIX: [u8; 13] = hex!("5af43d3d93803e603457fd5bf3");
+
+// wighawag's ClonesWithImmutableArgs, `LL` is the 2-byte length of the args plus 2
+const CWIA_PREFIX: [u8; 9] = hex!("3d3d3d3d363d3d3761");
+const CWIA_MIDDLE: [u8; 6] = hex!("603736393661");
+const CWIA_ADDRESS: [u8; 2] = hex!("013d");
+const CWIA_SUFFIX: [u8; 13] = hex!("5af43d3d93803e603557fd5bf3");
+
+fn embedded(kind: ProxyKind, implementation: Address, immutable_args: Vec<u8>) -> Proxy {
+    Proxy {
+        kind,
+        implementation: Some(implementation),
+        slot: None,
+        writers: Vec::new(),
+        immutable_args,
+    }
+}
+
+pub(super) fn detect(code: &[u8]) -> Option<Proxy> {
+    eip1167(code)
+        .or_else(|| zero_age(code))
+        .or_else(|| vyper_forwarder(code))
+        .or_else(|| erc3448(code))
+        .or_else(|| clones_with_immutable_args(code))
+}
+
+/// Matches `prefix PUSH20 <address> suffix` and returns the address with the remaining code
+fn push20_template<'a>(
+    code: &'a [u8],
+    prefix: &[u8],
+    suffix: &[u8],
+) -> Option<(Address, &'a [u8])> {
+    let rest = code.strip_prefix(prefix)?.strip_prefix(&[op::PUSH20])?;
+    let (addr, rest) = rest.split_at_checked(20)?;
+    let rest = rest.strip_prefix(suffix)?;
+    Some((Address::from_slice(addr), rest))
+}
+
+fn eip1167(code: &[u8]) -> Option<Proxy> {
+    let rest = code.strip_prefix(&EIP1167_PREFIX)?;
+    let (&push, rest) = rest.split_first()?;
+    if !(op::PUSH1..=op::PUSH20).contains(&push) {
+        return None;
+    }
+    let len = (push - op::PUSH0) as usize;
+    let (addr, rest) = rest.split_at_checked(len)?;
+    let rest = rest.strip_prefix(&EIP1167_SUFFIX)?;
+    let (&jumpdest, rest) = rest.split_first()?;
+    // the JUMPDEST moves together with the address length
+    if jumpdest as usize != EIP1167_PREFIX.len() + 1 + len + EIP1167_SUFFIX.len() + 3 {
+        return None;
+    }
+    // Solady-style clones append immutable args right after the proxy code
+    let args = rest.strip_prefix(&EIP1167_TAIL)?;
+    Some(embedded(
+        ProxyKind::Eip1167,
+        Address::left_padding_from(addr),
+        args.to_vec(),
+    ))
+}
+
+fn zero_age(code: &[u8]) -> Option<Proxy> {
+    let (implementation, rest) = push20_template(code, &ZERO_AGE_PREFIX, &ZERO_AGE_SUFFIX)?;
+    Some(embedded(ProxyKind::ZeroAge, implementation, rest.to_vec()))
+}
+
+fn vyper_forwarder(code: &[u8]) -> Option<Proxy> {
+    let (implementation, rest) = push20_template(code, &VYPER_PREFIX, &VYPER_SUFFIX)?;
+    Some(embedded(
+        ProxyKind::VyperForwarder,
+        implementation,
+        rest.to_vec(),
+    ))
+}
+
+fn erc3448(code: &[u8]) -> Option<Proxy> {
+    let (implementation, rest) = push20_template(code, &ERC3448_PREFIX, &ERC3448_SUFFIX)?;
+    // metadata is followed by its length as a 32-byte word
+    let (metadata, len) = rest.split_at_checked(rest.len().checked_sub(32)?)?;
+    if len[..24].iter().any(|&b| b != 0)
+        || u64::from_be_bytes(len[24..].try_into().expect("8 bytes")) != metadata.len() as u64
+    {
+        return None;
+    }
+    Some(embedded(
+        ProxyKind::Erc3448,
+        implementation,
+        metadata.to_vec(),
+    ))
+}
+
+fn clones_with_immutable_args(code: &[u8]) -> Option<Proxy> {
+    let rest = code.strip_prefix(&CWIA_PREFIX)?;
+    let (extra_len, rest) = rest.split_at_checked(2)?;
+    let rest = rest.strip_prefix(&CWIA_MIDDLE)?;
+    let rest = rest.strip_prefix(extra_len)?;
+    let (implementation, rest) = push20_template(rest, &CWIA_ADDRESS, &CWIA_SUFFIX)?;
+    // `extra_len` covers the args and the trailing 2-byte length itself
+    let extra_len = u16::from_be_bytes([extra_len[0], extra_len[1]]) as usize;
+    let (args, trailer) = rest.split_at_checked(extra_len.checked_sub(2)?)?;
+    if trailer.len() != 2 || u16::from_be_bytes([trailer[0], trailer[1]]) as usize != extra_len {
+        return None;
+    }
+    Some(embedded(
+        ProxyKind::ClonesWithImmutableArgs,
+        implementation,
+        args.to_vec(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::address;
+
+    const IMPL: &str = "bebebebebebebebebebebebebebebebebebebebe";
+
+    fn code(hex_code: &str) -> Vec<u8> {
+        hex::decode(hex_code).unwrap()
+    }
+
+    #[test]
+    fn test_eip1167() {
+        let p = detect(&code(&format!(
+            "363d3d373d3d3d363d73{IMPL}5af43d82803e903d91602b57fd5bf3"
+        )))
+        .unwrap();
+        assert_eq!(p.kind, ProxyKind::Eip1167);
+        assert_eq!(
+            p.implementation.unwrap(),
+            address!("bebebebebebebebebebebebebebebebebebebebe")
+        );
+        assert!(p.immutable_args.is_empty());
+
+        // Solady clone with immutable args
+        let p = detect(&code(&format!(
+            "363d3d373d3d3d363d73{IMPL}5af43d82803e903d91602b57fd5bf3cafe"
+        )))
+        .unwrap();
+        assert_eq!(p.immutable_args, hex!("cafe"));
+    }
+
+    #[test]
+    fn test_eip1167_vanity() {
+        // address with two leading zero bytes pushed with PUSH18
+        let p = detect(&code(
+            "363d3d373d3d3d363d71bebebebebebebebebebebebebebebebebebe5af43d82803e903d91602957fd5bf3",
+        ))
+        .unwrap();
+        assert_eq!(p.kind, ProxyKind::Eip1167);
+        assert_eq!(
+            p.implementation.unwrap(),
+            address!("0000bebebebebebebebebebebebebebebebebebe")
+        );
+
+        // jumpdest offset does not match the address length
+        assert!(
+            detect(&code(
+                "363d3d373d3d3d363d71bebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3",
+            ))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_zero_age() {
+        let p = detect(&code(&format!(
+            "3d3d3d3d363d3d37363d73{IMPL}5af43d3d93803e602a57fd5bf3"
+        )))
+        .unwrap();
+        assert_eq!(p.kind, ProxyKind::ZeroAge);
+        assert_eq!(
+            p.implementation.unwrap(),
+            address!("bebebebebebebebebebebebebebebebebebebebe")
+        );
+    }
+
+    #[test]
+    fn test_vyper_forwarder() {
+        let p = detect(&code(&format!(
+            "366000600037611000600036600073{IMPL}5af4602c57600080fd5b6110006000f3"
+        )))
+        .unwrap();
+        assert_eq!(p.kind, ProxyKind::VyperForwarder);
+    }
+
+    #[test]
+    fn test_erc3448() {
+        let p = detect(&code(&format!(
+            "363d3d373d3d3d3d60368038038091363936013d73{IMPL}5af43d3d93803e603457fd5bf3aabbcc{:064x}",
+            3
+        )))
+        .unwrap();
+        assert_eq!(p.kind, ProxyKind::Erc3448);
+        assert_eq!(p.immutable_args, hex!("aabbcc"));
+
+        // wrong metadata length
+        assert!(
+            detect(&code(&format!(
+                "363d3d373d3d3d3d60368038038091363936013d73{IMPL}5af43d3d93803e603457fd5bf3aabbcc{:064x}",
+                4
+            )))
+            .is_none()
+        );
+    }
+
+    #[test]
+    fn test_clones_with_immutable_args() {
+        let p = detect(&code(&format!(
+            "3d3d3d3d363d3d376100056037363936610005013d73{IMPL}5af43d3d93803e603557fd5bf3aabbcc0005"
+        )))
+        .unwrap();
+        assert_eq!(p.kind, ProxyKind::ClonesWithImmutableArgs);
+        assert_eq!(p.immutable_args, hex!("aabbcc"));
+    }
+
+    #[test]
+    fn test_not_a_proxy() {
+        assert!(detect(&[]).is_none());
+        assert!(detect(&code("6080604052348015600e575f80fd5b50")).is_none());
+    }
+}
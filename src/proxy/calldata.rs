@@ -0,0 +1,45 @@
+use crate::{
+    Selector,
+    evm::{U256, calldata::CallData, element::Element},
+};
+use std::error;
+
+/// Calldata consisting of a bare selector
+pub(crate) struct CallDataImpl {
+    pub selector: Selector,
+}
+
+impl<T> CallData<T> for CallDataImpl {
+    fn load32(&self, offset: U256) -> Element<T> {
+        let mut data = [0; 32];
+        if offset == U256::ZERO {
+            data[..4].copy_from_slice(&self.selector);
+        }
+        Element { data, label: None }
+    }
+
+    fn load(
+        &self,
+        offset: U256,
+        size: U256,
+    ) -> Result<(Vec<u8>, Option<T>), Box<dyn error::Error>> {
+        let sz = u16::try_from(size)?;
+        if sz > 512 {
+            return Err("unsupported size".into());
+        }
+        let mut data = vec![0; sz as usize];
+        if offset == U256::ZERO {
+            let n = data.len().min(4);
+            data[..n].copy_from_slice(&self.selector[..n]);
+        }
+        Ok((data, None))
+    }
+
+    fn selector(&self) -> Selector {
+        self.selector
+    }
+
+    fn len(&self) -> U256 {
+        U256::from(4)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{
+    ContractInfoArgs, Diagnostic, Function, Selector, collections::HashMap, contract_info,
+    utils::map_ordered,
+};
+use alloy_primitives::Address;
+use std::collections::BTreeMap;
+
+/// Function of a diamond together with the facet serving it
+#[derive(Debug)]
+pub struct FacetFunction {
+    /// Address of the facet the diamond delegates the selector to
+    pub facet: Address,
+
+    /// Function as analyzed in the facet code
+    pub function: Function,
+}
+
+/// Merged function table of a diamond, see [`diamond_functions`]
+#[derive(Debug)]
+pub struct DiamondFunctions {
+    /// Functions of all facets, sorted by selector
+    pub functions: Vec<FacetFunction>,
+
+    /// Selectors found in more than one facet, with all facets having them
+    ///
+    /// The first facet in input order is the one listed in `functions`.
+    pub clashes: BTreeMap<Selector, Vec<Address>>,
+
+    /// Reasons why contract-wide analysis passes of a facet stopped early, by facet
+    pub diagnostics: Vec<(Address, Vec<Diagnostic>)>,
+}
+
+impl DiamondFunctions {
+    /// Returns the function and its facet for `selector`
+    pub fn get(&self, selector: &Selector) -> Option<&FacetFunction> {
+        self.functions
+            .binary_search_by(|f| f.function.selector.cmp(selector))
+            .ok()
+            .map(|i| &self.functions[i])
+    }
+}
+
+/// Analyzes the facets of an EIP-2535 diamond and merges their functions into one table
+///
+/// Each facet is analyzed with `args` (with selectors always enabled). Facets are
+/// matched to selectors offline, so a selector implemented by several facets is
+/// attributed to the first of them and reported in [`DiamondFunctions::clashes`];
+/// the diamond's own `facets()` loupe is the source of truth for which one is wired.
+///
+/// # Arguments
+///
+/// * `facets` - Facet addresses with their deployed code, e.g. from the `facets()` loupe
+/// * `args` - Analysis configuration; its code is ignored
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{ContractInfoArgs, diamond_functions};
+/// use alloy_primitives::{address, hex};
+///
+/// // dispatches 0x11223344 to STOP
+/// let facet = hex::decode("60003560e01c631122334414601057005b00").unwrap();
+/// let facet_address = address!("00000000000000000000000000000000000000fa");
+///
+/// let table = diamond_functions([(facet_address, &facet[..])], ContractInfoArgs::default());
+/// let f = table.get(&[0x11, 0x22, 0x33, 0x44]).unwrap();
+/// assert_eq!(f.facet, facet_address);
+/// ```
+pub fn diamond_functions<'a, I>(facets: I, args: ContractInfoArgs<'a>) -> DiamondFunctions
+where
+    I: IntoIterator<Item = (Address, &'a [u8])>,
+{
+    let facets: Vec<_> = facets.into_iter().collect();
+    let contracts = map_ordered(&facets, |(_, code)| {
+        contract_info(args.with_code(code).with_selectors())
+    });
+
+    let mut first: HashMap<Selector, usize> = HashMap::default();
+    let mut clashes: BTreeMap<Selector, Vec<Address>> = BTreeMap::new();
+    let mut functions = Vec::new();
+    let mut diagnostics = Vec::new();
+    for ((facet, _), contract) in facets.iter().zip(contracts) {
+        if !contract.diagnostics.is_empty() {
+            diagnostics.push((*facet, contract.diagnostics));
+        }
+        for function in contract.functions.expect("enabled with_selectors()") {
+            if let Some(&i) = first.get(&function.selector) {
+                let served_by: &FacetFunction = &functions[i];
+                clashes
+                    .entry(function.selector)
+                    .or_insert_with(|| vec![served_by.facet])
+                    .push(*facet);
+                continue;
+            }
+            first.insert(function.selector, functions.len());
+            functions.push(FacetFunction {
+                facet: *facet,
+                function,
+            });
+        }
+    }
+
+    functions.sort_by_key(|f| f.function.selector);
+    DiamondFunctions {
+        functions,
+        clashes,
+        diagnostics,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, hex};
+
+    #[test]
+    fn test_merge_facets() {
+        // 0x11223344
+        let a = hex::decode("60003560e01c631122334414601057005b00").unwrap();
+        // 0x11223344, 0x55667788
+        let b = hex::decode("60003560e01c80631122334414601b5780635566778814601b57005b00").unwrap();
+        let fa = address!("00000000000000000000000000000000000000fa");
+        let fb = address!("00000000000000000000000000000000000000fb");
+
+        let table = diamond_functions([(fa, &a[..]), (fb, &b[..])], ContractInfoArgs::default());
+        assert_eq!(table.functions.len(), 2);
+        assert_eq!(table.get(&[0x11, 0x22, 0x33, 0x44]).unwrap().facet, fa);
+        assert_eq!(table.get(&[0x55, 0x66, 0x77, 0x88]).unwrap().facet, fb);
+        assert!(table.get(&[0; 4]).is_none());
+        assert_eq!(
+            table.clashes,
+            BTreeMap::from([([0x11, 0x22, 0x33, 0x44], vec![fa, fb])])
+        );
+    }
+}
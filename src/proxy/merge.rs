@@ -0,0 +1,438 @@
+use crate::{
+    Contract, ContractInfoArgs, Diagnostic, Event, RevertError, Selector, Slot, StorageRecord,
+    contract_info, diagnostics::truncated_phases, utils::map_ordered,
+};
+use std::collections::BTreeSet;
+
+/// Code analyzed by [`proxy_contract_info`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CodeOrigin {
+    /// The proxy code
+    Proxy,
+    /// The implementation code
+    Implementation,
+}
+
+/// Effective interface of a proxy combined with its implementation, see [`proxy_contract_info`]
+#[derive(Debug)]
+pub struct ProxyContract {
+    /// Combined view of the contract at the proxy address
+    ///
+    /// * `functions` - proxy functions and the implementation functions reachable through it
+    /// * `storage`, `transient_storage` - layouts of both codes merged; records with the same
+    ///   slot, offset and type are combined. Accesses by `shadowed` implementation functions
+    ///   are dropped, and records only accessed by a fallback are kept separately per code
+    /// * `events` - events of both codes; the proxy's layout is kept for shared topics
+    /// * `errors` - errors of both codes; the functions raising the same error are combined
+    /// * `special_functions` - the implementation's fallback and receive, reached through the
+    ///   proxy's fallback
+    /// * `proxy` - the proxy recognized in the proxy code
+    /// * `metadata` - the implementation's metadata
+    ///
+    /// Code-level views (`disassembled`, `basic_blocks`, `control_flow_graph`) are not
+    /// meaningful for two codes and are always `None`.
+    pub contract: Contract,
+
+    /// Selectors dispatched by the proxy code itself
+    pub proxy_selectors: Vec<Selector>,
+
+    /// Implementation selectors that the proxy dispatches itself, so calls never reach
+    /// the implementation (selector clashes)
+    ///
+    /// Transparent proxies route by caller, so their admin functions only shadow
+    /// the implementation for the admin.
+    pub shadowed: Vec<Selector>,
+
+    /// Persistent slots accessed by both the proxy and the implementation
+    ///
+    /// Accesses by `shadowed` implementation functions are not counted. The slot holding
+    /// the implementation address is shared by design and not reported.
+    pub storage_collisions: Vec<Slot>,
+
+    /// `contract.diagnostics`, tagged with the code whose pcs they refer to
+    pub diagnostics: Vec<(CodeOrigin, Diagnostic)>,
+}
+
+/// Drops the implementation's storage accesses by `shadowed` functions, and the records
+/// left without accesses
+fn drop_shadowed(
+    implementation: Option<Vec<StorageRecord>>,
+    shadowed: &[Selector],
+) -> Option<Vec<StorageRecord>> {
+    implementation.map(|records| {
+        records
+            .into_iter()
+            .filter_map(|mut r| {
+                // records only accessed by a fallback have no selectors to drop
+                if is_fallback_only(&r) {
+                    return Some(r);
+                }
+                r.reads.retain(|s| !shadowed.contains(s));
+                r.writes.retain(|s| !shadowed.contains(s));
+                (!is_fallback_only(&r)).then_some(r)
+            })
+            .collect()
+    })
+}
+
+/// Records without selectors are only accessed by a fallback
+fn is_fallback_only(r: &StorageRecord) -> bool {
+    r.reads.is_empty() && r.writes.is_empty()
+}
+
+fn merge_storage(
+    proxy: Option<Vec<StorageRecord>>,
+    implementation: Option<Vec<StorageRecord>>,
+) -> Option<Vec<StorageRecord>> {
+    if proxy.is_none() && implementation.is_none() {
+        return None;
+    }
+    // the proxy's and the implementation's fallbacks are different code paths, so their
+    // records are not combined
+    let implementation = implementation
+        .into_iter()
+        .flatten()
+        .map(|r| (is_fallback_only(&r), r));
+    let mut records: Vec<_> = proxy
+        .into_iter()
+        .flatten()
+        .map(|r| (false, r))
+        .chain(implementation)
+        .collect();
+    records.sort_by(|(a_impl, a), (b_impl, b)| {
+        (a.slot, a.offset, &a.r#type, *a_impl).cmp(&(b.slot, b.offset, &b.r#type, *b_impl))
+    });
+
+    let mut merged: Vec<StorageRecord> = Vec::with_capacity(records.len());
+    for (_, record) in records {
+        match merged.last_mut() {
+            Some(last)
+                if last.slot == record.slot
+                    && last.offset == record.offset
+                    && last.r#type == record.r#type
+                    && !is_fallback_only(last)
+                    && !is_fallback_only(&record) =>
+            {
+                let reads: BTreeSet<_> = last.reads.drain(..).chain(record.reads).collect();
+                let writes: BTreeSet<_> = last.writes.drain(..).chain(record.writes).collect();
+                last.reads = reads.into_iter().collect();
+                last.writes = writes.into_iter().collect();
+            }
+            _ => merged.push(record),
+        }
+    }
+    Some(merged)
+}
+
+fn merge_events(
+    proxy: Option<Vec<Event>>,
+    implementation: Option<Vec<Event>>,
+) -> Option<Vec<Event>> {
+    if proxy.is_none() && implementation.is_none() {
+        return None;
+    }
+    let mut events: Vec<_> = proxy.into_iter().chain(implementation).flatten().collect();
+    events.sort_by_key(|e| e.topic);
+    events.dedup_by_key(|e| e.topic);
+    Some(events)
+}
+
+fn merge_errors(
+    proxy: Option<Vec<RevertError>>,
+    implementation: Option<Vec<RevertError>>,
+) -> Option<Vec<RevertError>> {
+    if proxy.is_none() && implementation.is_none() {
+        return None;
+    }
+    let mut errors: Vec<_> = proxy.into_iter().chain(implementation).flatten().collect();
+    errors.sort_by(|a, b| {
+        (a.kind, a.selector, a.panic_code, &a.reason).cmp(&(
+            b.kind,
+            b.selector,
+            b.panic_code,
+            &b.reason,
+        ))
+    });
+    let mut merged: Vec<RevertError> = Vec::with_capacity(errors.len());
+    for error in errors {
+        match merged.last_mut() {
+            Some(last)
+                if (last.kind, last.selector, last.panic_code, &last.reason)
+                    == (error.kind, error.selector, error.panic_code, &error.reason) =>
+            {
+                let functions: BTreeSet<_> =
+                    last.functions.drain(..).chain(error.functions).collect();
+                last.functions = functions.into_iter().collect();
+            }
+            _ => merged.push(error),
+        }
+    }
+    Some(merged)
+}
+
+/// Analyzes a proxy together with its implementation as a single contract
+///
+/// Both codes are analyzed with `args`. Selectors and proxy recognition are always
+/// enabled for the proxy code.
+///
+/// # Arguments
+///
+/// * `proxy_code` - Deployed code of the proxy
+/// * `implementation_code` - Deployed code of the implementation it delegates to
+/// * `args` - Analysis configuration; its code is ignored
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{ContractInfoArgs, proxy_contract_info};
+/// use alloy_primitives::hex;
+///
+/// let proxy = hex::decode(
+///     "363d3d373d3d3d363d73bebebebebebebebebebebebebebebebebebebebe5af43d82803e903d91602b57fd5bf3",
+/// )
+/// .unwrap();
+/// // dispatches 0x11223344 to STOP
+/// let implementation = hex::decode("60003560e01c631122334414601057005b00").unwrap();
+///
+/// let view = proxy_contract_info(&proxy, &implementation, ContractInfoArgs::default());
+/// assert!(view.contract.proxy.is_some());
+/// assert_eq!(view.contract.functions.unwrap()[0].selector, [0x11, 0x22, 0x33, 0x44]);
+/// assert!(view.shadowed.is_empty());
+/// ```
+pub fn proxy_contract_info<'a>(
+    proxy_code: &'a [u8],
+    implementation_code: &'a [u8],
+    args: ContractInfoArgs<'a>,
+) -> ProxyContract {
+    let codes = [
+        args.with_code(proxy_code).with_proxy(),
+        args.with_code(implementation_code).with_selectors(),
+    ];
+    let [proxy, implementation]: [Contract; 2] = map_ordered(&codes, |a| contract_info(a.clone()))
+        .try_into()
+        .expect("two codes");
+
+    let proxy_functions = proxy.functions.expect("enabled on with_proxy()");
+    let proxy_selectors: Vec<Selector> = proxy_functions.iter().map(|f| f.selector).collect();
+
+    let mut shadowed = Vec::new();
+    let mut functions = proxy_functions;
+    for function in implementation
+        .functions
+        .expect("enabled on with_selectors()")
+    {
+        if proxy_selectors.contains(&function.selector) {
+            shadowed.push(function.selector);
+        } else {
+            functions.push(function);
+        }
+    }
+    functions.sort_by_key(|f| f.selector);
+
+    let implementation_storage = drop_shadowed(implementation.storage, &shadowed);
+    let implementation_transient_storage =
+        drop_shadowed(implementation.transient_storage, &shadowed);
+
+    let shared_slot = proxy.proxy.as_ref().and_then(|p| p.slot);
+    let storage_collisions = match (&proxy.storage, &implementation_storage) {
+        (Some(p), Some(i)) => {
+            let proxy_slots: BTreeSet<Slot> = p.iter().map(|r| r.slot).collect();
+            let implementation_slots: BTreeSet<Slot> = i.iter().map(|r| r.slot).collect();
+            proxy_slots
+                .intersection(&implementation_slots)
+                .filter(|slot| Some(**slot) != shared_slot)
+                .copied()
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    let tagged: Vec<_> = (proxy.diagnostics.into_iter())
+        .map(|d| (CodeOrigin::Proxy, d))
+        .chain(
+            implementation
+                .diagnostics
+                .into_iter()
+                .map(|d| (CodeOrigin::Implementation, d)),
+        )
+        .collect();
+    let diagnostics: Vec<_> = tagged.iter().map(|(_, d)| d.clone()).collect();
+
+    ProxyContract {
+        contract: Contract {
+            functions: Some(functions),
+            special_functions: implementation.special_functions,
+            storage: merge_storage(proxy.storage, implementation_storage),
+            transient_storage: merge_storage(
+                proxy.transient_storage,
+                implementation_transient_storage,
+            ),
+            events: merge_events(proxy.events, implementation.events),
+            errors: merge_errors(proxy.errors, implementation.errors),
+            disassembled: None,
+            basic_blocks: None,
+            control_flow_graph: None,
+            metadata: implementation.metadata,
+            proxy: proxy.proxy,
+            truncated: truncated_phases(&diagnostics),
+            diagnostics,
+        },
+        proxy_selectors,
+        shadowed,
+        storage_collisions,
+        diagnostics: tagged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_shadowed_and_collisions() {
+        // proxy: 0x11223344 writes slot 0, anything else is delegated to SLOAD(1)
+        let proxy = hex::decode(
+            "60003560e01c63112233441461001e57365f5f375f5f365f6001545af4005b6004355f5500",
+        )
+        .unwrap();
+        // implementation: 0x11223344 and 0x55667788 both write slot 0
+        let implementation =
+            hex::decode("60003560e01c80631122334414601b5780635566778814601b57005b6004355f5500")
+                .unwrap();
+
+        let view = proxy_contract_info(
+            &proxy,
+            &implementation,
+            ContractInfoArgs::default().with_storage(),
+        );
+        let proxy_info = view.contract.proxy.as_ref().unwrap();
+        assert_eq!(proxy_info.kind, crate::ProxyKind::StorageSlot);
+        let mut slot1 = [0; 32];
+        slot1[31] = 1;
+        assert_eq!(proxy_info.slot, Some(slot1));
+
+        let selectors: Vec<_> = view
+            .contract
+            .functions
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|f| f.selector)
+            .collect();
+        assert_eq!(
+            selectors,
+            vec![[0x11, 0x22, 0x33, 0x44], [0x55, 0x66, 0x77, 0x88]]
+        );
+        assert_eq!(view.proxy_selectors, vec![[0x11, 0x22, 0x33, 0x44]]);
+        assert_eq!(view.shadowed, vec![[0x11, 0x22, 0x33, 0x44]]);
+        assert_eq!(view.storage_collisions, vec![[0; 32]]);
+
+        let storage = view.contract.storage.unwrap();
+        let slot0: Vec<_> = storage.iter().filter(|r| r.slot == [0; 32]).collect();
+        assert_eq!(slot0.len(), 1);
+        assert_eq!(
+            slot0[0].writes,
+            vec![[0x11, 0x22, 0x33, 0x44], [0x55, 0x66, 0x77, 0x88]]
+        );
+    }
+
+    #[test]
+    fn test_shadowed_storage() {
+        // proxy: 0x11223344 writes slot 0, the fallback reads slot 1 and delegates to SLOAD(1)
+        let proxy = hex::decode(
+            "60003560e01c6311223344146100225760015450365f5f375f5f365f6001545af4005b6004355f5500",
+        )
+        .unwrap();
+        // implementation: 0x11223344 writes slot 2, 0x55667788 writes slot 0,
+        // the fallback reads slot 1
+        let implementation = hex::decode(
+            "60003560e01c80631122334414601f578063556677881460275760015450005b600435600255005b6004355f5500",
+        )
+        .unwrap();
+
+        let view = proxy_contract_info(
+            &proxy,
+            &implementation,
+            ContractInfoArgs::default().with_storage(),
+        );
+        assert_eq!(view.shadowed, vec![[0x11, 0x22, 0x33, 0x44]]);
+
+        let storage = view.contract.storage.unwrap();
+        let mut slot1 = [0; 32];
+        slot1[31] = 1;
+        let mut slot2 = [0; 32];
+        slot2[31] = 2;
+        // only the shadowed implementation function writes slot 2
+        assert!(storage.iter().all(|r| r.slot != slot2));
+
+        let slot0: Vec<_> = storage.iter().filter(|r| r.slot == [0; 32]).collect();
+        assert_eq!(slot0.len(), 1);
+        assert_eq!(
+            slot0[0].writes,
+            vec![[0x11, 0x22, 0x33, 0x44], [0x55, 0x66, 0x77, 0x88]]
+        );
+
+        // one record for each fallback
+        let slot1: Vec<_> = storage.iter().filter(|r| r.slot == slot1).collect();
+        assert_eq!(slot1.len(), 2);
+        assert!(
+            slot1
+                .iter()
+                .all(|r| r.reads.is_empty() && r.writes.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_shadowed_collisions() {
+        // proxy: 0x11223344 writes slot 0, anything else is delegated to SLOAD(1)
+        let proxy = hex::decode(
+            "60003560e01c63112233441461001e57365f5f375f5f365f6001545af4005b6004355f5500",
+        )
+        .unwrap();
+        // implementation: 0x11223344 writes slot 0, 0x55667788 writes slot 2
+        let implementation = hex::decode(
+            "60003560e01c80631122334414601b5780635566778814602257005b6004355f55005b600435600255005b",
+        )
+        .unwrap();
+
+        let view = proxy_contract_info(
+            &proxy,
+            &implementation,
+            ContractInfoArgs::default().with_storage(),
+        );
+        assert_eq!(view.shadowed, vec![[0x11, 0x22, 0x33, 0x44]]);
+        // slot 0 is only written by the shadowed implementation function
+        assert!(view.storage_collisions.is_empty());
+    }
+
+    #[test]
+    fn test_diagnostics_origin() {
+        // proxy: 0x11223344 hits an unsupported opcode at pc 0x20, anything else is
+        // delegated to SLOAD(1)
+        let proxy =
+            hex::decode("60003560e01c63112233441461001e57365f5f375f5f365f6001545af4005b5f0c")
+                .unwrap();
+        // implementation: the fallback hits an unsupported opcode at pc 0x0f
+        let implementation = hex::decode("60003560e01c6355667788146010570c5b00").unwrap();
+
+        let view = proxy_contract_info(
+            &proxy,
+            &implementation,
+            ContractInfoArgs::default().with_special_functions(),
+        );
+        let tagged: Vec<_> = view
+            .diagnostics
+            .iter()
+            .map(|(origin, d)| (*origin, d.pc))
+            .collect();
+        assert!(tagged.contains(&(CodeOrigin::Proxy, 0x20)));
+        assert!(tagged.contains(&(CodeOrigin::Implementation, 0x0f)));
+        assert!(tagged.iter().all(|t| matches!(
+            t,
+            (CodeOrigin::Proxy, 0x20) | (CodeOrigin::Implementation, 0x0f)
+        )));
+        let diagnostics: Vec<_> = view.diagnostics.into_iter().map(|(_, d)| d).collect();
+        assert_eq!(view.contract.diagnostics, diagnostics);
+    }
+}
@@ -0,0 +1,172 @@
+//! Recognition of proxy contracts and their implementation addresses.
+
+use crate::{Selector, Slot, diagnostics::Diagnostic, trace::Tracer};
+use alloy_primitives::Address;
+
+mod bytecode;
+mod calldata;
+mod diamond;
+mod merge;
+mod slot;
+
+pub use diamond::{DiamondFunctions, FacetFunction, diamond_functions};
+pub use merge::{CodeOrigin, ProxyContract, proxy_contract_info};
+
+/// Kind of proxy recognized in the contract code
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ProxyKind {
+    /// EIP-1167 minimal proxy, including vanity variants that push a shorter address
+    Eip1167,
+    /// 0age's 44-byte "more-minimal" proxy
+    ZeroAge,
+    /// Forwarder deployed by Vyper's `create_forwarder_to()`
+    VyperForwarder,
+    /// ERC-3448 MetaProxy, with metadata appended after the proxy code
+    Erc3448,
+    /// Clones with immutable args, which append the args to the forwarded calldata
+    ClonesWithImmutableArgs,
+    /// EIP-1967 implementation slot, used by transparent and UUPS proxies
+    Eip1967,
+    /// EIP-1967 beacon slot; the implementation is queried from the beacon
+    Eip1967Beacon,
+    /// EIP-1822 (UUPS) `PROXIABLE` slot
+    Eip1822,
+    /// Pre-EIP-1967 OpenZeppelin (ZeppelinOS) implementation slot
+    OpenZeppelinLegacy,
+    /// Gnosis Safe proxy, with the singleton in slot 0 and a `masterCopy()` getter
+    ///
+    /// Other proxies delegating to the address in slot 0 are [`Self::StorageSlot`].
+    GnosisSafe,
+    /// Implementation loaded from a constant slot that is not a well-known one
+    StorageSlot,
+    /// EIP-2535 diamond, looking up the facet for `msg.sig` in a mapping at `slot`
+    ///
+    /// Use [`crate::diamond_functions`] to merge the functions of its facets.
+    Diamond,
+}
+
+impl ProxyKind {
+    /// Returns the kind as a camelCase string, matching its serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Eip1167 => "eip1167",
+            Self::ZeroAge => "zeroAge",
+            Self::VyperForwarder => "vyperForwarder",
+            Self::Erc3448 => "erc3448",
+            Self::ClonesWithImmutableArgs => "clonesWithImmutableArgs",
+            Self::Eip1967 => "eip1967",
+            Self::Eip1967Beacon => "eip1967Beacon",
+            Self::Eip1822 => "eip1822",
+            Self::OpenZeppelinLegacy => "openZeppelinLegacy",
+            Self::GnosisSafe => "gnosisSafe",
+            Self::StorageSlot => "storageSlot",
+            Self::Diamond => "diamond",
+        }
+    }
+}
+
+/// Proxy recognized in the contract code
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Proxy {
+    /// Proxy flavor
+    pub kind: ProxyKind,
+
+    /// Address all calls are delegated to, when hardcoded in the bytecode
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::opt_address")
+    )]
+    pub implementation: Option<Address>,
+
+    /// Storage slot holding the implementation (or beacon) address; for diamonds,
+    /// the slot of the `selector => facet` mapping
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::opt_slot")
+    )]
+    pub slot: Option<Slot>,
+
+    /// Function selectors of the proxy itself that can write to `slot`
+    ///
+    /// Empty for UUPS proxies, whose upgrade logic lives in the implementation.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::vec_selector")
+    )]
+    pub writers: Vec<Selector>,
+
+    /// Data appended to the proxy code: immutable args of clones, or ERC-3448 metadata.
+    ///
+    /// Length suffixes written by the deployer are not included.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "immutableArgs", serialize_with = "crate::serialize::bytes")
+    )]
+    pub immutable_args: Vec<u8>,
+}
+
+/// Recognizes proxies hardcoding their implementation, or delegating from the fallback
+/// to an address kept in storage.
+///
+/// `selectors` are the functions dispatched by the proxy itself; they are checked for
+/// writes to the implementation slot.
+pub(crate) fn detect(
+    code: &[u8],
+    selectors: &[Selector],
+    gas_limit: u32,
+    tracer: Tracer,
+) -> (Option<Proxy>, Vec<Diagnostic>) {
+    let mut diagnostics = Vec::new();
+    if let Some(proxy) = bytecode::detect(code) {
+        return (Some(proxy), diagnostics);
+    }
+
+    let real_gas_limit = if gas_limit == 0 {
+        5e5 as u32
+    } else {
+        gas_limit
+    };
+
+    let mut fallback_selector: Selector = [0xff, 0xff, 0xff, 0xff];
+    while selectors.contains(&fallback_selector) {
+        let val = u32::from_be_bytes(fallback_selector) - 1;
+        fallback_selector = val.to_be_bytes();
+    }
+
+    let Some((kind, slot)) = slot::delegate_slot(
+        code,
+        fallback_selector,
+        real_gas_limit,
+        tracer,
+        &mut diagnostics,
+    ) else {
+        return (None, diagnostics);
+    };
+
+    let writers = selectors
+        .iter()
+        .filter(|selector| {
+            slot::writes_slot(
+                code,
+                **selector,
+                &slot,
+                real_gas_limit,
+                tracer,
+                &mut diagnostics,
+            )
+        })
+        .copied()
+        .collect();
+
+    let proxy = Proxy {
+        kind,
+        implementation: None,
+        slot: Some(slot),
+        writers,
+        immutable_args: Vec::new(),
+    };
+    (Some(proxy), diagnostics)
+}
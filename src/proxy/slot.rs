@@ -0,0 +1,432 @@
+use super::{ProxyKind, calldata::CallDataImpl};
+use crate::{
+    AnalysisPhase, Selector, Slot,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{
+        element::Element,
+        op,
+        vm::{StepResult, Vm},
+    },
+    trace::Tracer,
+    utils::{elabel, execute_until_function_start, explore_paths, match_first_two},
+};
+use alloy_primitives::hex;
+
+/// `keccak256("eip1967.proxy.implementation") - 1`
+pub(super) const EIP1967_IMPLEMENTATION_SLOT: Slot =
+    hex!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// `keccak256("eip1967.proxy.beacon") - 1`
+pub(super) const EIP1967_BEACON_SLOT: Slot =
+    hex!("a3f0ad74e5423aebfd80d3ef4346578335a9a72aeaee59ff6cb3582b35133d50");
+
+/// `keccak256("PROXIABLE")`
+pub(super) const EIP1822_SLOT: Slot =
+    hex!("c5f16f0fcc639fa48a6947836d9850f504798523bf8c9a3a87d5876cf622bcf7");
+
+/// `keccak256("org.zeppelinos.proxy.implementation")`
+pub(super) const ZEPPELINOS_IMPLEMENTATION_SLOT: Slot =
+    hex!("7050c9e0f4ca769c69bd3a8ef740bc37934f8e2c036e5a723fd8ee048ed3f8c3");
+
+/// Gnosis Safe keeps its singleton (`masterCopy`) in the first slot
+pub(super) const SAFE_SINGLETON_SLOT: Slot = [0; 32];
+
+/// `masterCopy()`, answered by the Safe proxy fallback without delegating
+const SAFE_MASTER_COPY_SELECTOR: Selector = hex!("a619486e");
+
+const MAX_DEPTH: u32 = 8;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Label {
+    /// Value loaded from a constant storage slot
+    Loaded(Slot),
+    /// Value returned by a call to an address loaded from a storage slot
+    CallResult(Slot),
+    /// `keccak256(key . base)`: location of a mapping entry
+    MappingEntry(Slot),
+    /// `keccak256(msg.sig . base)`: location of the entry for the called selector
+    SelectorEntry(Slot),
+    /// Value loaded from a `SelectorEntry`, i.e. the facet serving the selector
+    Facet(Slot),
+    /// `CALLDATALOAD(0)`: the selector in the high bytes
+    CallData,
+    /// The selector shifted into the low bytes
+    Signature,
+}
+
+/// Per-path state of [`track_delegate`]
+#[derive(Clone, Default)]
+struct PathState {
+    /// Slot the target of the latest external call was loaded from
+    last_call: Option<Slot>,
+    /// Whether the calldata selector was compared against `masterCopy()`
+    master_copy_getter: bool,
+}
+
+fn implementation_kind(slot: &Slot, state: &PathState) -> ProxyKind {
+    match *slot {
+        EIP1967_IMPLEMENTATION_SLOT => ProxyKind::Eip1967,
+        EIP1822_SLOT => ProxyKind::Eip1822,
+        ZEPPELINOS_IMPLEMENTATION_SLOT => ProxyKind::OpenZeppelinLegacy,
+        SAFE_SINGLETON_SLOT if state.master_copy_getter => ProxyKind::GnosisSafe,
+        _ => ProxyKind::StorageSlot,
+    }
+}
+
+/// Tracks addresses loaded from storage and reports the slot the fallback DELEGATECALLs to
+fn track_delegate(
+    vm: &mut Vm<Label, CallDataImpl>,
+    state: &mut PathState,
+    ret: &StepResult<Label>,
+) -> Result<Option<(ProxyKind, Slot)>, Box<dyn std::error::Error>> {
+    match ret {
+        StepResult {
+            op: op::CALLDATALOAD,
+            args: [offset, ..],
+            ..
+        } if offset.data == [0; 32] => {
+            vm.stack.peek_mut()?.label = Some(Label::CallData);
+        }
+
+        StepResult {
+            op: op::SHR,
+            args: [shift, elabel!(Label::CallData)],
+            ..
+        } if usize::try_from(shift).is_ok_and(|v| v == 224) => {
+            vm.stack.peek_mut()?.label = Some(Label::Signature);
+        }
+
+        // selector comparison of the dispatcher, on the shifted selector or the whole word
+        StepResult {
+            op: op::XOR | op::EQ | op::SUB,
+            args: match_first_two!(elabel!(lb @ (Label::CallData | Label::Signature)), other),
+            ..
+        } => {
+            let compared = match lb {
+                Label::Signature => other.data[28..] == SAFE_MASTER_COPY_SELECTOR,
+                _ => {
+                    other.data[..4] == SAFE_MASTER_COPY_SELECTOR
+                        && other.data[4..].iter().all(|b| *b == 0)
+                }
+            };
+            if compared {
+                state.master_copy_getter = true;
+            }
+        }
+
+        StepResult {
+            op: op::SLOAD,
+            args: [slot, ..],
+            ..
+        } if slot.label.is_none() => {
+            vm.stack.peek_mut()?.label = Some(Label::Loaded(slot.data));
+        }
+
+        StepResult {
+            op: op::SLOAD,
+            args: [elabel!(Label::SelectorEntry(base)), ..],
+            ..
+        } => {
+            vm.stack.peek_mut()?.label = Some(Label::Facet(*base));
+        }
+
+        // mapping lookup, `keccak256(key . base)`
+        StepResult {
+            op: op::KECCAK256,
+            args: [offset, size],
+            ..
+        } => {
+            if let (Ok(off), Ok(64)) = (u32::try_from(offset), u32::try_from(size)) {
+                let (preimage, _) = vm.memory.load(off, 64);
+                let base: Slot = preimage[32..].try_into().expect("32 bytes");
+                let by_sig = preimage[..4] == vm.calldata.selector
+                    && preimage[4..32].iter().all(|b| *b == 0);
+                vm.stack.peek_mut()?.label = Some(if by_sig {
+                    Label::SelectorEntry(base)
+                } else {
+                    Label::MappingEntry(base)
+                });
+            }
+        }
+
+        // facet address packed in the high bytes, as in gas-optimized diamonds
+        StepResult {
+            op: op::SHR,
+            args: [_, elabel!(lb @ (Label::Loaded(_) | Label::Facet(_)))],
+            ..
+        } => {
+            vm.stack.peek_mut()?.label = Some(lb.clone());
+        }
+
+        // address masking
+        StepResult {
+            op: op::AND,
+            args: match_first_two!(elabel!(lb), _),
+            ..
+        } => {
+            vm.stack.peek_mut()?.label = Some(lb.clone());
+        }
+
+        StepResult {
+            op: op::DELEGATECALL,
+            args: [elabel!(lb), ..],
+            ..
+        } => {
+            return Ok(match lb {
+                Label::Loaded(slot) => Some((implementation_kind(slot, state), *slot)),
+                Label::CallResult(slot) => {
+                    (*slot == EIP1967_BEACON_SLOT).then_some((ProxyKind::Eip1967Beacon, *slot))
+                }
+                Label::Facet(base) => Some((ProxyKind::Diamond, *base)),
+                Label::MappingEntry(_)
+                | Label::SelectorEntry(_)
+                | Label::CallData
+                | Label::Signature => None,
+            });
+        }
+
+        // beacon's `implementation()`, returned into memory by old compilers
+        StepResult {
+            op: op::CALL | op::STATICCALL,
+            args: [elabel!(Label::Loaded(slot)), ..],
+            exargs,
+            ..
+        } => {
+            let (Ok(ret_off), Ok(ret_size)) = (
+                u32::try_from(&exargs[exargs.len() - 2]),
+                u32::try_from(&exargs[exargs.len() - 1]),
+            ) else {
+                return Ok(None);
+            };
+            if ret_size >= 32 {
+                vm.memory
+                    .store(ret_off, vec![0; 32], Some(Label::CallResult(*slot)));
+            }
+            state.last_call = Some(*slot);
+        }
+
+        StepResult {
+            op: op::CALL | op::STATICCALL,
+            ..
+        } => {
+            state.last_call = None;
+        }
+
+        StepResult {
+            op: op::RETURNDATACOPY,
+            args: [mem_off, ..],
+            exargs,
+            ..
+        } => {
+            if let (Some(slot), Ok(off), Ok(size)) = (
+                state.last_call,
+                u32::try_from(mem_off),
+                u32::try_from(&exargs[0]),
+            ) && size >= 32
+            {
+                vm.memory
+                    .store(off, vec![0; 32], Some(Label::CallResult(slot)));
+            }
+        }
+
+        StepResult {
+            op: op::MLOAD,
+            memory_load: Some(memory_load),
+            ..
+        } => {
+            if let Some(lb) = memory_load
+                .chunks
+                .iter()
+                .find(|c| matches!(c.src_label, Label::CallResult(_)))
+                .map(|c| c.src_label.clone())
+            {
+                vm.stack.peek_mut()?.label = Some(lb);
+            }
+        }
+
+        _ => {}
+    }
+    Ok(None)
+}
+
+/// Finds the storage slot that the fallback path DELEGATECALLs to
+pub(super) fn delegate_slot(
+    code: &[u8],
+    fallback_selector: Selector,
+    gas_limit: u32,
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<(ProxyKind, Slot)> {
+    let calldata = CallDataImpl {
+        selector: fallback_selector,
+    };
+    let vm = Vm::new(code, &calldata);
+    let mut errors = Vec::new();
+    let found = explore_paths(
+        vm,
+        gas_limit,
+        MAX_DEPTH,
+        tracer,
+        diagnostics,
+        |vm, state, pc, ret| {
+            track_delegate(vm, state, ret).unwrap_or_else(|e| {
+                errors.push((pc, DiagnosticKind::from_error(e.as_ref())));
+                vm.stopped = true;
+                None
+            })
+        },
+    );
+    for (pc, kind) in errors {
+        record(diagnostics, AnalysisPhase::Proxy, pc, kind);
+    }
+    found
+}
+
+/// Checks whether the function identified by `selector` can SSTORE to `slot`,
+/// or to an entry of the mapping at `slot` for diamonds
+pub(super) fn writes_slot(
+    code: &[u8],
+    selector: Selector,
+    slot: &Slot,
+    gas_limit: u32,
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> bool {
+    let calldata = CallDataImpl { selector };
+    let mut vm = Vm::new(code, &calldata);
+    let gas_used = match execute_until_function_start(&mut vm, gas_limit) {
+        Ok(g) => g,
+        Err(e) => {
+            e.record(diagnostics, AnalysisPhase::Proxy);
+            return false;
+        }
+    };
+    explore_paths(
+        vm,
+        gas_limit.saturating_sub(gas_used),
+        MAX_DEPTH,
+        tracer.with_selector(selector),
+        diagnostics,
+        |vm, state, _, ret| {
+            if ret.op == op::SSTORE {
+                let written = match &ret.args[0].label {
+                    Some(Label::MappingEntry(base) | Label::SelectorEntry(base)) => base == slot,
+                    _ => ret.args[0].data == *slot,
+                };
+                if written {
+                    return Some(());
+                }
+            }
+            // only the labels matter here, a failed step just leaves the result unlabeled
+            let _ = track_delegate(vm, state, ret);
+            None
+        },
+    )
+    .is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{U256, keccak256};
+
+    /// `keccak256("diamond.standard.diamond.storage")`, the EIP-2535 reference storage position
+    const DIAMOND_STORAGE_SLOT: Slot =
+        hex!("c8fcad8db84d3cc18b4c41d551ea0ee66dd599cde068d998e57d5e09332c131c");
+
+    fn delegate(code: &str) -> Option<(ProxyKind, Slot)> {
+        let code = hex::decode(code).unwrap();
+        let mut diagnostics = Vec::new();
+        let r = delegate_slot(
+            &code,
+            [0xff; 4],
+            5e5 as u32,
+            Tracer::disabled(AnalysisPhase::Proxy),
+            &mut diagnostics,
+        );
+        assert!(diagnostics.is_empty(), "{diagnostics:?}");
+        r
+    }
+
+    #[test]
+    fn test_well_known_slots() {
+        let minus_one =
+            |s: &str| (U256::from_be_bytes(keccak256(s).0) - U256::from(1)).to_be_bytes::<32>();
+        assert_eq!(
+            EIP1967_IMPLEMENTATION_SLOT,
+            minus_one("eip1967.proxy.implementation")
+        );
+        assert_eq!(EIP1967_BEACON_SLOT, minus_one("eip1967.proxy.beacon"));
+        assert_eq!(EIP1822_SLOT, keccak256("PROXIABLE").0);
+        assert_eq!(
+            ZEPPELINOS_IMPLEMENTATION_SLOT,
+            keccak256("org.zeppelinos.proxy.implementation").0
+        );
+    }
+
+    #[test]
+    fn test_eip1967() {
+        let slot = hex::encode(EIP1967_IMPLEMENTATION_SLOT);
+        // CALLDATACOPY(0, 0, CALLDATASIZE); DELEGATECALL(GAS, SLOAD(slot), 0, CALLDATASIZE, 0, 0)
+        let r = delegate(&format!("365f5f375f5f365f7f{slot}545af400"));
+        assert_eq!(r, Some((ProxyKind::Eip1967, EIP1967_IMPLEMENTATION_SLOT)));
+    }
+
+    #[test]
+    fn test_safe_masked() {
+        let mask = "ff".repeat(20);
+        let master_copy = format!("a619486e{}", "00".repeat(28));
+        // singleton = SLOAD(0) & mask; if CALLDATALOAD(0) == masterCopy() { return singleton }
+        let r = delegate(&format!(
+            "5f5473{mask}167f{master_copy}5f3514604b57\
+             365f5f375f5f365f845af4005b5f5260205ff3"
+        ));
+        assert_eq!(r, Some((ProxyKind::GnosisSafe, SAFE_SINGLETON_SLOT)));
+
+        // if CALLDATALOAD(0) >> 224 == masterCopy() { return singleton }
+        let r = delegate(&format!(
+            "5f5473{mask}165f3560e01c63a619486e14603257\
+             365f5f375f5f365f845af4005b5f5260205ff3"
+        ));
+        assert_eq!(r, Some((ProxyKind::GnosisSafe, SAFE_SINGLETON_SLOT)));
+
+        // slot 0 without the `masterCopy()` getter
+        let r = delegate(&format!("365f5f375f5f365f5f5473{mask}165af400"));
+        assert_eq!(r, Some((ProxyKind::StorageSlot, SAFE_SINGLETON_SLOT)));
+
+        // the selector bytes pushed but not compared against the calldata
+        let r = delegate(&format!("63a619486e50365f5f375f5f365f5f5473{mask}165af400"));
+        assert_eq!(r, Some((ProxyKind::StorageSlot, SAFE_SINGLETON_SLOT)));
+    }
+
+    #[test]
+    fn test_beacon() {
+        let slot = hex::encode(EIP1967_BEACON_SLOT);
+        // STATICCALL(GAS, SLOAD(slot), 0, 4, 0, 32); DELEGATECALL(GAS, MLOAD(0), 0, CALLDATASIZE, 0, 0)
+        let r = delegate(&format!("60205f60045f7f{slot}545afa505f5f365f5f515af400"));
+        assert_eq!(r, Some((ProxyKind::Eip1967Beacon, EIP1967_BEACON_SLOT)));
+    }
+
+    #[test]
+    fn test_diamond() {
+        let pos = hex::encode(DIAMOND_STORAGE_SLOT);
+        let mask = "ff".repeat(20);
+        // MSTORE(0, msg.sig); MSTORE(32, pos); DELEGATECALL(GAS, SLOAD(KECCAK256(0, 64)) & mask, ..)
+        let r = delegate(&format!(
+            "5f5f365f5f3563ffffffff60e01b165f527f{pos}60205260405f205473{mask}165af400"
+        ));
+        assert_eq!(r, Some((ProxyKind::Diamond, DIAMOND_STORAGE_SLOT)));
+        assert_eq!(
+            DIAMOND_STORAGE_SLOT,
+            keccak256("diamond.standard.diamond.storage").0
+        );
+    }
+
+    #[test]
+    fn test_not_delegating() {
+        assert_eq!(delegate("00"), None);
+        // DELEGATECALL to a hardcoded address
+        let addr = "be".repeat(20);
+        assert_eq!(delegate(&format!("365f5f375f5f365f73{addr}5af400")), None);
+    }
+}
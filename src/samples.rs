@@ -0,0 +1,213 @@
+//! Generation of sample calldata matching inferred argument types, e.g. as fuzzing seeds.
+
+use crate::{DynSolType, DynSolValue, Function, Selector};
+use alloy_primitives::{Address, I256, U256};
+
+/// Kind of argument values in generated calldata
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SampleKind {
+    /// Zero numbers and addresses, `false`, empty bytes, strings and arrays
+    Zero,
+    /// Largest unsigned and smallest signed numbers, all-ones addresses and fixed bytes,
+    /// `true`, 33-byte bytes and strings (crossing a word boundary), 2-item arrays
+    Boundary,
+    /// Random values and lengths derived from a seed; the same seed gives the same values
+    Random(u64),
+}
+
+/// splitmix64 generator, enough for reproducible seeds without an extra dependency
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: u64) -> usize {
+        (self.next() % n) as usize
+    }
+
+    fn word(&mut self) -> U256 {
+        U256::from_limbs([self.next(), self.next(), self.next(), self.next()])
+    }
+
+    fn bytes(&mut self, len: usize) -> Vec<u8> {
+        (0..len).map(|_| self.next() as u8).collect()
+    }
+}
+
+fn low_bits(bits: usize) -> U256 {
+    U256::MAX >> (256 - bits)
+}
+
+fn sample(tp: &DynSolType, kind: SampleKind, rng: &mut Rng) -> DynSolValue {
+    let boundary = kind == SampleKind::Boundary;
+    let random = matches!(kind, SampleKind::Random(_));
+    let many = |rng: &mut Rng, boundary_len: usize, max_random_len: u64| match kind {
+        SampleKind::Zero => 0,
+        SampleKind::Boundary => boundary_len,
+        SampleKind::Random(_) => rng.below(max_random_len + 1),
+    };
+
+    match tp {
+        DynSolType::Bool => DynSolValue::Bool(boundary || (random && rng.next() & 1 == 1)),
+        DynSolType::Uint(bits) => {
+            let v = match kind {
+                SampleKind::Zero => U256::ZERO,
+                SampleKind::Boundary => low_bits(*bits),
+                SampleKind::Random(_) => rng.word() & low_bits(*bits),
+            };
+            DynSolValue::Uint(v, *bits)
+        }
+        DynSolType::Int(bits) => {
+            let raw = match kind {
+                SampleKind::Zero => U256::ZERO,
+                SampleKind::Boundary => U256::MAX << (bits - 1),
+                SampleKind::Random(_) => {
+                    let v = rng.word() & low_bits(*bits);
+                    if v.bit(bits - 1) {
+                        v | !low_bits(*bits)
+                    } else {
+                        v
+                    }
+                }
+            };
+            DynSolValue::Int(I256::from_raw(raw), *bits)
+        }
+        DynSolType::Address => DynSolValue::Address(match kind {
+            SampleKind::Zero => Address::ZERO,
+            SampleKind::Boundary => Address::repeat_byte(0xff),
+            SampleKind::Random(_) => Address::from_slice(&rng.bytes(20)),
+        }),
+        DynSolType::FixedBytes(size) => {
+            let mut word = [0; 32];
+            match kind {
+                SampleKind::Zero => {}
+                SampleKind::Boundary => word[..*size].fill(0xff),
+                SampleKind::Random(_) => word[..*size].copy_from_slice(&rng.bytes(*size)),
+            }
+            DynSolValue::FixedBytes(word.into(), *size)
+        }
+        DynSolType::Function => {
+            let data = match kind {
+                SampleKind::Zero => vec![0; 24],
+                SampleKind::Boundary => vec![0xff; 24],
+                SampleKind::Random(_) => rng.bytes(24),
+            };
+            DynSolValue::Function(alloy_primitives::Function::from_slice(&data))
+        }
+        DynSolType::Bytes => {
+            let len = many(rng, 33, 64);
+            DynSolValue::Bytes(match kind {
+                SampleKind::Random(_) => rng.bytes(len),
+                _ => vec![0xff; len],
+            })
+        }
+        DynSolType::String => {
+            let len = many(rng, 33, 64);
+            let s = (0..len)
+                .map(|_| match kind {
+                    SampleKind::Random(_) => (b' ' + rng.below(95) as u8) as char,
+                    _ => 'A',
+                })
+                .collect();
+            DynSolValue::String(s)
+        }
+        DynSolType::Array(inner) => {
+            let len = many(rng, 2, 4);
+            DynSolValue::Array((0..len).map(|_| sample(inner, kind, rng)).collect())
+        }
+        DynSolType::FixedArray(inner, size) => {
+            DynSolValue::FixedArray((0..*size).map(|_| sample(inner, kind, rng)).collect())
+        }
+        DynSolType::Tuple(types) => {
+            DynSolValue::Tuple(types.iter().map(|t| sample(t, kind, rng)).collect())
+        }
+    }
+}
+
+/// Generates argument values of the given types
+pub fn sample_values(arguments: &[DynSolType], kind: SampleKind) -> Vec<DynSolValue> {
+    let seed = match kind {
+        SampleKind::Random(seed) => seed,
+        _ => 0,
+    };
+    let mut rng = Rng(seed);
+    arguments
+        .iter()
+        .map(|t| sample(t, kind, &mut rng))
+        .collect()
+}
+
+/// Generates calldata calling `selector` with ABI-encoded arguments of the given types
+///
+/// # Examples
+///
+/// ```
+/// use evmole::{DynSolType, SampleKind, sample_calldata};
+///
+/// let calldata = sample_calldata([0x11, 0x22, 0x33, 0x44], &[DynSolType::Bytes], SampleKind::Zero);
+/// // selector, offset of the bytes, zero length
+/// assert_eq!(calldata.len(), 4 + 32 + 32);
+/// assert_eq!(calldata[35], 0x20);
+/// ```
+pub fn sample_calldata(selector: Selector, arguments: &[DynSolType], kind: SampleKind) -> Vec<u8> {
+    let values = sample_values(arguments, kind);
+    [
+        selector.as_slice(),
+        &DynSolValue::Tuple(values).abi_encode_params(),
+    ]
+    .concat()
+}
+
+impl Function {
+    /// Generates calldata calling this function, see [`sample_calldata`]
+    ///
+    /// Returns `None` if arguments were not extracted.
+    pub fn sample_calldata(&self, kind: SampleKind) -> Option<Vec<u8>> {
+        let arguments = self.arguments.as_ref()?;
+        Some(sample_calldata(self.selector, arguments, kind))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_samples_decode_back() {
+        let types: Vec<DynSolType> = ["uint8", "int16", "bytes", "(address,string)[]", "bool[2]"]
+            .iter()
+            .map(|t| t.parse().unwrap())
+            .collect();
+        let tuple = DynSolType::Tuple(types.clone());
+        for kind in [
+            SampleKind::Zero,
+            SampleKind::Boundary,
+            SampleKind::Random(1),
+            SampleKind::Random(2),
+        ] {
+            let calldata = sample_calldata([1, 2, 3, 4], &types, kind);
+            assert_eq!(calldata[..4], [1, 2, 3, 4]);
+            let decoded = tuple.abi_decode_params(&calldata[4..]).unwrap();
+            assert_eq!(decoded, DynSolValue::Tuple(sample_values(&types, kind)));
+        }
+
+        let values = sample_values(&types, SampleKind::Boundary);
+        assert_eq!(values[0], DynSolValue::Uint(U256::from(255), 8));
+        assert_eq!(
+            values[1],
+            DynSolValue::Int(I256::try_from(-32768).unwrap(), 16)
+        );
+        assert_eq!(values[2], DynSolValue::Bytes(vec![0xff; 33]));
+
+        assert_ne!(
+            sample_values(&types, SampleKind::Random(1)),
+            sample_values(&types, SampleKind::Random(2))
+        );
+    }
+}
@@ -1,4 +1,5 @@
-use crate::{CborMetadata, Selector, SelectorDispatch};
+use crate::diagnostics::{Diagnostic, DiagnosticKind, record};
+use crate::{AnalysisPhase, CborMetadata, Selector, SelectorDispatch};
 use crate::{
     evm::{
         U256, VAL_0_B, VAL_1_B,
@@ -7,6 +8,7 @@ use crate::{
         op,
         vm::{StepResult, Vm},
     },
+    trace::Tracer,
     utils::{elabel, match_first_two},
 };
 use alloy_primitives::{hex, uint};
@@ -237,29 +239,35 @@ fn process(
     mut vm: Vm<Label, CallDataImpl>,
     selectors: &mut BTreeMap<Selector, usize>,
     gas_limit: u32,
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> u32 {
     let mut gas_used = 0;
     while !vm.stopped {
-        if cfg!(feature = "trace_selectors") {
-            println!(
-                "selectors: {:?}",
-                selectors
-                    .iter()
-                    .map(|(s, p)| (hex::encode(s), *p))
-                    .collect::<Vec<(String, usize)>>()
-            );
-            println!("{vm:?}\n");
-        }
+        tracer.step_with_state(&vm, || {
+            selectors
+                .iter()
+                .map(|(s, p)| format!("{}: {p}", hex::encode(s)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        let pc = vm.pc;
         let ret = match vm.step() {
             Ok(v) => v,
-            Err(_e) => {
-                // eprintln!("vm error: {:?}", _e);
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(diagnostics, AnalysisPhase::Selectors, pc, kind);
                 break;
             }
         };
         gas_used += ret.gas_used;
         if gas_used > gas_limit {
-            // eprintln!("gas overflow");
+            record(
+                diagnostics,
+                AnalysisPhase::Selectors,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
             break;
         }
 
@@ -270,16 +278,28 @@ fn process(
                     let mut vm_clone = vm.fork();
                     vm_clone.stack.peek_mut().expect("already unwraped").data =
                         U256::from(m).to_be_bytes();
-                    let gas = process(vm_clone, selectors, (gas_limit - gas_used) / (to as u32));
+                    let gas = process(
+                        vm_clone,
+                        selectors,
+                        (gas_limit - gas_used) / (to as u32),
+                        tracer,
+                        diagnostics,
+                    );
                     gas_used += gas;
                     if gas_used > gas_limit {
-                        // eprintln!("gas overflow");
+                        record(
+                            diagnostics,
+                            AnalysisPhase::Selectors,
+                            pc,
+                            DiagnosticKind::GasLimit,
+                        );
                         return gas_used;
                     }
                 }
             }
-            Err(_e) => {
-                // eprintln!("analyze error: {:?}", _e);
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(diagnostics, AnalysisPhase::Selectors, pc, kind);
                 return gas_used;
             }
         }
@@ -291,11 +311,13 @@ fn function_selectors_with_calldata_len(
     code: &[u8],
     gas_limit: u32,
     calldata_len: usize,
-) -> (BTreeMap<Selector, usize>, u32) {
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> BTreeMap<Selector, usize> {
     let calldata = CallDataImpl::new(calldata_len);
     let vm = Vm::new(code, &calldata);
     let mut selectors = BTreeMap::new();
-    let gas_used = process(
+    process(
         vm,
         &mut selectors,
         if gas_limit == 0 {
@@ -303,22 +325,34 @@ fn function_selectors_with_calldata_len(
         } else {
             gas_limit
         },
+        tracer,
+        diagnostics,
     );
-    (selectors, gas_used)
+    selectors
+}
+
+pub(crate) struct SelectorsResult {
+    pub selectors: BTreeMap<Selector, (usize, SelectorDispatch)>,
+    pub diagnostics: Vec<Diagnostic>,
 }
 
 pub(crate) fn function_selectors(
     code: &[u8],
     gas_limit: u32,
     metadata: Option<&CborMetadata>,
-) -> (BTreeMap<Selector, (usize, SelectorDispatch)>, u32) {
-    let (all, mut gas_used) = function_selectors_with_calldata_len(code, gas_limit, 4);
+    tracer: Tracer,
+) -> SelectorsResult {
+    let mut diagnostics = Vec::new();
+    let all = function_selectors_with_calldata_len(code, gas_limit, 4, tracer, &mut diagnostics);
     if all.is_empty() {
-        return (BTreeMap::new(), gas_used);
+        return SelectorsResult {
+            selectors: BTreeMap::new(),
+            diagnostics,
+        };
     }
 
-    let (mut short, short_gas_used) = function_selectors_with_calldata_len(code, gas_limit, 3);
-    gas_used = gas_used.saturating_add(short_gas_used);
+    let mut short =
+        function_selectors_with_calldata_len(code, gas_limit, 3, tracer, &mut diagnostics);
     short.retain(|selector, _| all.contains_key(selector));
 
     let has_four_byte_only = all.keys().any(|selector| !short.contains_key(selector));
@@ -336,7 +370,10 @@ pub(crate) fn function_selectors(
             (selector, (bytecode_offset, dispatch))
         })
         .collect();
-    (classified, gas_used)
+    SelectorsResult {
+        selectors: classified,
+        diagnostics,
+    }
 }
 
 #[cfg(test)]
@@ -345,7 +382,8 @@ mod tests {
 
     #[test]
     fn test_empty_code() {
-        let (s, _) = function_selectors(&[], 0, None);
-        assert_eq!(s.len(), 0);
+        let r = function_selectors(&[], 0, None, Tracer::disabled(AnalysisPhase::Selectors));
+        assert_eq!(r.selectors.len(), 0);
+        assert!(r.diagnostics.is_empty());
     }
 }
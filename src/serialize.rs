@@ -1,14 +1,24 @@
 use std::collections::BTreeMap;
 
-use alloy_primitives::hex;
+use alloy_primitives::{Address, U256, hex};
 use serde::{Serializer, ser::SerializeSeq};
 
-use crate::{DynSolType, Selector, Slot, StateMutability, control_flow_graph::Block};
+use crate::{DynSolType, Selector, Slot, StateMutability, Topic, control_flow_graph::Block};
 
 pub fn selector<S: Serializer>(val: &Selector, serializer: S) -> Result<S::Ok, S::Error> {
     serializer.serialize_str(&hex::encode(val))
 }
 
+pub fn opt_selector<S: Serializer>(
+    val: &Option<Selector>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match val {
+        Some(sel) => serializer.serialize_str(&hex::encode(sel)),
+        None => serializer.serialize_none(),
+    }
+}
+
 pub fn arguments<S: Serializer>(
     val: &Option<Vec<DynSolType>>,
     serializer: S,
@@ -26,6 +36,17 @@ pub fn arguments<S: Serializer>(
     }
 }
 
+pub fn opt_type<S: Serializer>(val: &Option<DynSolType>, serializer: S) -> Result<S::Ok, S::Error> {
+    match val {
+        Some(t) => serializer.serialize_str(&t.sol_type_name()),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn opcode<S: Serializer>(val: &u8, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(crate::evm::op::info(*val).name)
+}
+
 pub fn state_mutability<S: Serializer>(
     val: &Option<StateMutability>,
     serializer: S,
@@ -58,3 +79,63 @@ pub fn blocks<S: Serializer>(
     }
     s.end()
 }
+
+pub fn address<S: Serializer>(val: &Address, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(val))
+}
+
+pub fn opt_address<S: Serializer>(val: &Option<Address>, serializer: S) -> Result<S::Ok, S::Error> {
+    match val {
+        Some(addr) => serializer.serialize_str(&hex::encode(addr)),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn opt_slot<S: Serializer>(val: &Option<Slot>, serializer: S) -> Result<S::Ok, S::Error> {
+    match val {
+        Some(slot) => serializer.serialize_str(&hex::encode(slot)),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn topic<S: Serializer>(val: &Topic, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(val))
+}
+
+pub fn topics<S: Serializer>(val: &Option<Vec<Topic>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match val {
+        Some(topics) => {
+            let mut s = serializer.serialize_seq(Some(topics.len()))?;
+            for topic in topics {
+                s.serialize_element(&hex::encode(topic))?;
+            }
+            s.end()
+        }
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn vec_topic<S: Serializer>(val: &Vec<Topic>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut s = serializer.serialize_seq(Some(val.len()))?;
+    for topic in val {
+        s.serialize_element(&hex::encode(topic))?;
+    }
+    s.end()
+}
+
+pub fn uint<S: Serializer>(val: &U256, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(val.to_be_bytes::<32>()))
+}
+
+pub fn types<S: Serializer>(val: &[DynSolType], serializer: S) -> Result<S::Ok, S::Error> {
+    let s: String = val
+        .iter()
+        .map(|t| t.sol_type_name().to_string())
+        .collect::<Vec<String>>()
+        .join(",");
+    serializer.serialize_str(&s)
+}
+
+pub fn bytes<S: Serializer>(val: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&hex::encode(val))
+}
@@ -0,0 +1,177 @@
+//! Resolution of function selectors to text signatures from offline databases.
+
+use crate::{DynSolType, Selector};
+use alloy_primitives::{hex, keccak256};
+use std::{
+    cmp::Reverse,
+    io::{self, BufRead},
+    path::Path,
+};
+
+/// Source of text signatures for selectors, e.g. a 4byte or openchain dump
+///
+/// See [`crate::ContractInfoArgs::with_signature_resolver`].
+pub trait SignatureResolver: Sync {
+    /// Returns the known text signatures with the given selector, e.g. `transfer(address,uint256)`
+    fn signatures(&self, selector: Selector) -> Vec<String>;
+}
+
+/// In-memory signature database, loaded from a text dump
+///
+/// Each line holds a text signature, optionally preceded by its selector in hex and a
+/// space, tab or comma (`a9059cbb transfer(address,uint256)`). When the selector is
+/// missing, it is computed from the signature. Empty lines and lines starting with `#`
+/// are skipped.
+#[derive(Clone, Debug, Default)]
+pub struct SignatureDb {
+    /// Entries sorted by selector
+    entries: Vec<(Selector, Box<str>)>,
+}
+
+fn parse_line(line: &str) -> Result<Option<(Selector, Box<str>)>, String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return Ok(None);
+    }
+    let (selector, signature) = match line.split_once([' ', '\t', ',']) {
+        Some((selector, signature)) if !selector.contains('(') => {
+            let selector = hex::decode_to_array(selector)
+                .map_err(|e| format!("invalid selector '{selector}': {e}"))?;
+            (selector, signature.trim())
+        }
+        _ => (keccak256(line)[..4].try_into().expect("4 bytes"), line),
+    };
+    if !signature.ends_with(')') || !signature.contains('(') {
+        return Err(format!("invalid signature '{signature}'"));
+    }
+    Ok(Some((selector, signature.into())))
+}
+
+impl SignatureDb {
+    /// Reads a database from `reader`, one signature per line
+    pub fn from_reader<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut entries = Vec::new();
+        for (i, line) in reader.lines().enumerate() {
+            let entry = parse_line(&line?).map_err(|e| {
+                io::Error::new(io::ErrorKind::InvalidData, format!("line {}: {e}", i + 1))
+            })?;
+            entries.extend(entry);
+        }
+        Ok(Self::from_entries(entries))
+    }
+
+    /// Loads a database from a file, see [`SignatureDb::from_reader`]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let file = std::fs::File::open(path)?;
+        Self::from_reader(io::BufReader::new(file))
+    }
+
+    fn from_entries(mut entries: Vec<(Selector, Box<str>)>) -> Self {
+        entries.sort();
+        entries.dedup();
+        entries.shrink_to_fit();
+        Self { entries }
+    }
+
+    /// Returns the number of signatures
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the database holds no signatures
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl SignatureResolver for SignatureDb {
+    fn signatures(&self, selector: Selector) -> Vec<String> {
+        let start = self.entries.partition_point(|(s, _)| *s < selector);
+        self.entries[start..]
+            .iter()
+            .take_while(|(s, _)| *s == selector)
+            .map(|(_, signature)| signature.to_string())
+            .collect()
+    }
+}
+
+/// Parses the argument types of a text signature
+pub(crate) fn signature_types(signature: &str) -> Option<Vec<DynSolType>> {
+    let open = signature.find('(')?;
+    match DynSolType::parse(&signature[open..]).ok()? {
+        DynSolType::Tuple(types) => Some(types),
+        _ => None,
+    }
+}
+
+/// Orders the `candidates` for `selector` by agreement with the inferred `arguments`,
+/// best first
+///
+/// Candidates whose hash doesn't match `selector` are dropped. Ties keep their order.
+pub fn rank_signatures(
+    selector: Selector,
+    candidates: Vec<String>,
+    arguments: Option<&[DynSolType]>,
+) -> Vec<String> {
+    let mut ranked: Vec<_> = candidates
+        .into_iter()
+        .filter(|c| keccak256(c)[..4] == selector)
+        .map(|c| {
+            let score = match (signature_types(&c), arguments) {
+                (Some(types), Some(args)) => {
+                    let matching = types.iter().zip(args).filter(|(t, a)| t == a).count();
+                    let len_diff = types.len().abs_diff(args.len());
+                    (true, types == args, matching, Reverse(len_diff))
+                }
+                (types, _) => (types.is_some(), false, 0, Reverse(0)),
+            };
+            (score, c)
+        })
+        .collect();
+    ranked.sort_by_key(|(score, _)| Reverse(*score));
+    ranked.into_iter().map(|(_, c)| c).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSFER: Selector = [0xa9, 0x05, 0x9c, 0xbb];
+
+    #[test]
+    fn test_db_and_ranking() {
+        let dump = "# selector collisions\n\
+                    a9059cbb,many_msg_babbage(bytes1)\n\
+                    \n\
+                    transfer(address,uint256)\n\
+                    0x095ea7b3 approve(address,uint256)\n";
+        let db = SignatureDb::from_reader(dump.as_bytes()).unwrap();
+        assert_eq!(db.len(), 3);
+        assert_eq!(
+            db.signatures([0x09, 0x5e, 0xa7, 0xb3]),
+            vec!["approve(address,uint256)"]
+        );
+
+        let candidates = db.signatures(TRANSFER);
+        assert_eq!(
+            candidates,
+            vec!["many_msg_babbage(bytes1)", "transfer(address,uint256)"]
+        );
+        let args = [DynSolType::Address, DynSolType::Uint(256)];
+        assert_eq!(
+            rank_signatures(TRANSFER, candidates.clone(), Some(&args)),
+            vec!["transfer(address,uint256)", "many_msg_babbage(bytes1)"]
+        );
+        let args = [DynSolType::FixedBytes(1)];
+        assert_eq!(
+            rank_signatures(TRANSFER, candidates, Some(&args)),
+            vec!["many_msg_babbage(bytes1)", "transfer(address,uint256)"]
+        );
+
+        // listed under a selector that doesn't match its hash
+        let db = SignatureDb::from_reader("a9059cbb approve(address,uint256)".as_bytes()).unwrap();
+        assert!(rank_signatures(TRANSFER, db.signatures(TRANSFER), None).is_empty());
+
+        assert!(SignatureDb::from_reader("zz transfer()".as_bytes()).is_err());
+    }
+}
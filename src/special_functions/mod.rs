@@ -0,0 +1,174 @@
+//! Detection of the `fallback` and `receive` functions, which handle calls matching no selector.
+
+use crate::{
+    AnalysisPhase, Selector, StateMutability,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{U256, calldata::ConcreteCallData, op, vm::Vm},
+    payload::fallback_selector,
+};
+use std::collections::BTreeSet;
+
+/// Entry points that handle calls matching no function selector
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct SpecialFunctions {
+    /// State mutability of the fallback function, `None` if such calls revert
+    ///
+    /// Only `payable` and `nonpayable` are reported.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serialize::state_mutability")
+    )]
+    pub fallback: Option<StateMutability>,
+
+    /// Whether plain ether transfers (empty calldata) are accepted by a dedicated
+    /// `receive` function rather than the fallback
+    pub receive: bool,
+}
+
+/// Runs the code concretely, returning the pc of the terminating opcode if the call succeeds
+fn run(
+    code: &[u8],
+    calldata: &[u8],
+    call_value: u32,
+    gas_limit: u32,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<usize> {
+    let calldata = ConcreteCallData {
+        data: calldata.to_vec(),
+    };
+    let mut vm = Vm::<(), _>::new(code, &calldata);
+    let mut gas_used = 0;
+    let mut pc = 0;
+
+    while !vm.stopped {
+        pc = vm.pc;
+        let ret = match vm.step() {
+            Ok(v) => v,
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(diagnostics, AnalysisPhase::SpecialFunctions, pc, kind);
+                return None;
+            }
+        };
+        gas_used += ret.gas_used;
+        if gas_used > gas_limit {
+            record(
+                diagnostics,
+                AnalysisPhase::SpecialFunctions,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
+            return None;
+        }
+
+        match ret.op {
+            op::CALLVALUE => {
+                vm.stack.peek_mut().ok()?.data = U256::from(call_value).to_be_bytes();
+            }
+            op::STOP | op::RETURN | op::SELFDESTRUCT => return Some(pc),
+            op::REVERT | op::INVALID => return None,
+            _ => {}
+        }
+    }
+    // running past the end of the code is an implicit STOP
+    Some(pc)
+}
+
+/// Detects the fallback and receive functions of the code dispatching `selectors`
+///
+/// The fallback is called with an unknown selector, with and without value. A receive
+/// function is reported when a call with empty calldata and value succeeds without
+/// ending where the payable fallback does.
+pub(crate) fn special_functions(
+    code: &[u8],
+    selectors: &BTreeSet<Selector>,
+    gas_limit: u32,
+) -> (SpecialFunctions, Vec<Diagnostic>) {
+    let real_gas_limit = if gas_limit == 0 {
+        5e5 as u32
+    } else {
+        gas_limit
+    };
+    let mut diagnostics = Vec::new();
+
+    let unknown = fallback_selector(selectors);
+    let payable = run(code, &unknown, 1, real_gas_limit, &mut diagnostics);
+    let fallback = if payable.is_some() {
+        Some(StateMutability::Payable)
+    } else if run(code, &unknown, 0, real_gas_limit, &mut diagnostics).is_some() {
+        Some(StateMutability::NonPayable)
+    } else {
+        None
+    };
+
+    let receive = match run(code, &[], 1, real_gas_limit, &mut diagnostics) {
+        Some(pc) => payable != Some(pc),
+        None => false,
+    };
+
+    (SpecialFunctions { fallback, receive }, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    fn detect(code: &str) -> SpecialFunctions {
+        let (special, diagnostics) =
+            special_functions(&hex::decode(code).unwrap(), &BTreeSet::new(), 0);
+        assert!(diagnostics.is_empty());
+        special
+    }
+
+    #[test]
+    fn test_fallback_and_receive() {
+        // receive() and a nonpayable fallback()
+        assert_eq!(
+            detect("3615600b573415600d57fe5b005b00"),
+            SpecialFunctions {
+                fallback: Some(StateMutability::NonPayable),
+                receive: true,
+            }
+        );
+
+        // payable fallback() handling empty calldata too
+        assert_eq!(
+            detect("00"),
+            SpecialFunctions {
+                fallback: Some(StateMutability::Payable),
+                receive: false,
+            }
+        );
+
+        // receive() only
+        assert_eq!(
+            detect("36600557005b5f5ffd"),
+            SpecialFunctions {
+                fallback: None,
+                receive: true,
+            }
+        );
+
+        assert_eq!(detect("5f5ffd"), SpecialFunctions::default());
+
+        // payable fallback() running past the end of the code
+        assert_eq!(
+            detect("5f50"),
+            SpecialFunctions {
+                fallback: Some(StateMutability::Payable),
+                receive: false,
+            }
+        );
+
+        // receive() running past the end of the code
+        assert_eq!(
+            detect("3615600657005b"),
+            SpecialFunctions {
+                fallback: Some(StateMutability::Payable),
+                receive: true,
+            }
+        );
+    }
+}
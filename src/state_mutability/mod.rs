@@ -1,11 +1,13 @@
 use crate::{
-    Selector, StateMutability,
+    AnalysisPhase, Evidence, Selector, StateMutability,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
     evm::{
         U256, VAL_0_B,
         element::Element,
         op,
         vm::{StepResult, Vm},
     },
+    trace::Tracer,
     utils::{elabel, execute_until_function_start},
 };
 
@@ -71,28 +73,39 @@ const OP_NOT_PURE: [bool; 256] = create_opcode_lookup_table([
     op::TIMESTAMP,
 ]);
 
+/// Returns `(callvalue_check, gas_used)`; `callvalue_check` holds the CALLVALUE, JUMPI
+/// and REVERT opcodes rejecting the call, `None` if the function is payable
 fn analyze_payable(
     mut vm: Vm<Label, CallDataImpl>,
     gas_limit: u32,
     call_value: u32,
-) -> (bool, u32) {
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> (Option<Vec<Evidence>>, u32) {
     let mut gas_used = 0;
-    let mut last_jumpi_callvalue = false;
+    let mut last_callvalue = None;
+    let mut last_jumpi_callvalue = None;
 
     while !vm.stopped {
-        if cfg!(feature = "trace_mutability") {
-            println!("{vm:?}\n");
-        }
+        tracer.step(&vm);
+        let pc = vm.pc;
         let ret = match vm.step() {
             Ok(v) => v,
-            Err(_e) => {
-                // println!("{}", _e);
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(diagnostics, AnalysisPhase::StateMutability, pc, kind);
                 break;
             }
         };
         gas_used += ret.gas_used;
         if gas_used > gas_limit {
-            break;
+            record(
+                diagnostics,
+                AnalysisPhase::StateMutability,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
+            return (None, gas_used);
         }
 
         match ret {
@@ -102,6 +115,7 @@ fn analyze_payable(
                 if let Ok(s) = vm.stack.peek_mut() {
                     s.data = U256::from(call_value).to_be_bytes();
                     s.label = Some(Label::CallValue);
+                    last_callvalue = Some(Evidence::new(pc, op::CALLVALUE, "callvalue check"));
                 } else {
                     break;
                 }
@@ -137,27 +151,37 @@ fn analyze_payable(
                 args: [_, sa, ..],
                 ..
             } => {
-                last_jumpi_callvalue =
-                    sa.label == Some(Label::IsZero) || sa.label == Some(Label::CallValue);
+                last_jumpi_callvalue = (sa.label == Some(Label::IsZero)
+                    || sa.label == Some(Label::CallValue))
+                .then(|| Evidence::new(pc, op::JUMPI, "callvalue check"));
             }
 
             StepResult {
                 op: op::REVERT,
                 args: [_, sa, ..],
                 ..
-            } if last_jumpi_callvalue && sa.data == VAL_0_B => {
-                return (false, gas_used);
+            } if last_jumpi_callvalue.is_some() && sa.data == VAL_0_B => {
+                let evidence = last_callvalue
+                    .into_iter()
+                    .chain(last_jumpi_callvalue)
+                    .chain([Evidence::new(pc, op::REVERT, "reverts on callvalue")])
+                    .collect();
+                return (Some(evidence), gas_used);
             }
 
             _ => (),
         }
     }
-    (true, gas_used)
+    (None, gas_used)
 }
 
 struct ViewPureResult {
     pub view: bool,
     pub pure: bool,
+    /// First opcode ruling out `view`
+    pub not_view: Option<Evidence>,
+    /// First opcode ruling out `pure`
+    pub not_pure: Option<Evidence>,
 }
 
 fn analyze_view_pure_internal(
@@ -165,30 +189,40 @@ fn analyze_view_pure_internal(
     vpr: &mut ViewPureResult,
     gas_limit: u32,
     depth: u32,
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
 ) -> u32 {
     let mut gas_used = 0;
 
     if depth == 0 {
-        if let Some(g) = execute_until_function_start(&mut vm, gas_limit) {
-            gas_used += g;
-        } else {
-            return gas_used;
+        match execute_until_function_start(&mut vm, gas_limit) {
+            Ok(g) => gas_used += g,
+            Err(e) => {
+                e.record(diagnostics, AnalysisPhase::StateMutability);
+                return gas_used;
+            }
         }
     }
 
     while !vm.stopped && vpr.view {
-        if cfg!(feature = "trace_mutability") {
-            println!("{vm:?}\n");
-        }
+        tracer.step(&vm);
+        let pc = vm.pc;
         let ret = match vm.step() {
             Ok(v) => v,
-            Err(_e) => {
-                // println!("{}", _e);
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(diagnostics, AnalysisPhase::StateMutability, pc, kind);
                 break;
             }
         };
         gas_used += ret.gas_used;
         if gas_used > gas_limit {
+            record(
+                diagnostics,
+                AnalysisPhase::StateMutability,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
             break;
         }
 
@@ -204,6 +238,8 @@ fn analyze_view_pure_internal(
                         vpr,
                         (gas_limit - gas_used) / 2,
                         depth + 1,
+                        tracer,
+                        diagnostics,
                     );
                 } else {
                     // println!("depth overflow");
@@ -214,8 +250,10 @@ fn analyze_view_pure_internal(
                 if OP_NOT_VIEW[ret.op as usize] {
                     vpr.view = false;
                     vpr.pure = false;
-                } else if OP_NOT_PURE[ret.op as usize] {
+                    vpr.not_view = Some(Evidence::new(pc, ret.op, "modifies state"));
+                } else if OP_NOT_PURE[ret.op as usize] && vpr.pure {
                     vpr.pure = false;
+                    vpr.not_pure = Some(Evidence::new(pc, ret.op, "reads state or environment"));
                 }
             }
         };
@@ -223,17 +261,28 @@ fn analyze_view_pure_internal(
     gas_used
 }
 
-fn analyze_view_pure(vm: Vm<Label, CallDataImpl>, gas_limit: u32) -> ViewPureResult {
+fn analyze_view_pure(
+    vm: Vm<Label, CallDataImpl>,
+    gas_limit: u32,
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> ViewPureResult {
     let mut ret = ViewPureResult {
         view: true,
         pure: true,
+        not_view: None,
+        not_pure: None,
     };
-    analyze_view_pure_internal(vm, &mut ret, gas_limit, 0);
+    analyze_view_pure_internal(vm, &mut ret, gas_limit, 0, tracer, diagnostics);
     ret
 }
 
 /// Extracts function state mutability
 ///
+/// Returns the inferred mutability, the opcodes it is based on (see
+/// [`crate::FunctionEvidence::state_mutability`]) and the diagnostics explaining
+/// why execution stopped early, if it did.
+///
 /// # Arguments
 ///
 /// * `code` - A slice of deployed contract bytecode
@@ -244,7 +293,9 @@ pub fn function_state_mutability(
     code: &[u8],
     selector: &Selector,
     gas_limit: u32,
-) -> StateMutability {
+    tracer: Tracer,
+) -> (StateMutability, Vec<Evidence>, Vec<Diagnostic>) {
+    let tracer = tracer.with_selector(*selector);
     let calldata = CallDataImpl {
         selector: *selector,
     };
@@ -256,18 +307,22 @@ pub fn function_state_mutability(
         gas_limit
     };
 
-    let (is_payable, gas_used) = analyze_payable(vm.fork(), real_gas_limit / 2, 1);
-    if is_payable {
-        StateMutability::Payable
+    let mut diagnostics = Vec::new();
+    let (callvalue_check, gas_used) =
+        analyze_payable(vm.fork(), real_gas_limit / 2, 1, tracer, &mut diagnostics);
+    let Some(mut evidence) = callvalue_check else {
+        return (StateMutability::Payable, Vec::new(), diagnostics);
+    };
+    let gas_remaining = real_gas_limit - gas_used.min(real_gas_limit / 2);
+    let vpr = analyze_view_pure(vm, gas_remaining, tracer, &mut diagnostics);
+    let sm = if vpr.pure {
+        StateMutability::Pure
+    } else if vpr.view {
+        evidence.extend(vpr.not_pure);
+        StateMutability::View
     } else {
-        let gas_remaining = real_gas_limit - gas_used.min(real_gas_limit / 2);
-        let vpr = analyze_view_pure(vm, gas_remaining);
-        if vpr.pure {
-            StateMutability::Pure
-        } else if vpr.view {
-            StateMutability::View
-        } else {
-            StateMutability::NonPayable
-        }
-    }
+        evidence.extend(vpr.not_view);
+        StateMutability::NonPayable
+    };
+    (sm, evidence, diagnostics)
 }
@@ -2,16 +2,18 @@
 //! This code is in an experimental state and under active development.
 //! Code structure are subject to change.
 use crate::{
-    DynSolType, Selector, Slot,
+    AnalysisPhase, DynSolType, Evidence, Selector, Slot,
     collections::HashMap,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
     evm::{
         U256, VAL_1, VAL_1_B, VAL_32_B,
         calldata::{CallDataImpl, CallDataLabel, CallDataLabelType},
         element::Element,
-        op,
+        op::{self, OpCode},
         vm::{StepResult, Vm},
     },
-    utils::{and_mask_to_type, elabel, execute_until_function_start, match_first_two},
+    trace::Tracer,
+    utils::{and_mask_to_type, elabel, execute_until_function_start, map_ordered, match_first_two},
 };
 use alloy_primitives::keccak256;
 use std::{
@@ -49,6 +51,11 @@ pub struct StorageRecord {
         serde(serialize_with = "crate::serialize::vec_selector")
     )]
     pub writes: Vec<Selector>,
+
+    /// Opcodes accessing this storage location in any function, in pc order, when
+    /// requested with [`crate::ContractInfoArgs::with_evidence`]
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub evidence: Option<Vec<Evidence>>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -249,6 +256,8 @@ struct StorageElement {
     stype: StorageType,
     rshift: u8, // in bytes
     is_write: bool,
+    // the access itself, and the reads of a value written back to the slot
+    access: Vec<Evidence>,
     last_and: Option<U256>,
     last_or2: Option<Element<Label>>,
 }
@@ -399,7 +408,7 @@ fn is_legitimate_packed_root(stype: &StorageType) -> bool {
     matches!(stype, StorageType::Base(DynSolType::Address))
 }
 
-fn looks_like_opaque_bitfield_slot(entries: &[(Selector, StorageElement)]) -> bool {
+fn looks_like_opaque_bitfield_slot(entries: &[(Selector, SlotAccess)]) -> bool {
     let mut nonzero_offsets: BTreeSet<u8> = BTreeSet::new();
     let mut min_nonzero_offset: Option<u8> = None;
     let mut has_suspicious_root = false;
@@ -430,6 +439,11 @@ fn looks_like_opaque_bitfield_slot(entries: &[(Selector, StorageElement)]) -> bo
 #[derive(Default)]
 struct Storage {
     loaded: SlotHashMap,
+    diagnostics: Vec<Diagnostic>,
+    // step being analyzed, for the evidence of accesses
+    pc: usize,
+    opcode: OpCode,
+    with_evidence: bool,
 }
 impl Storage {
     fn remove(&mut self, val: &Rc<RefCell<StorageElement>>) {
@@ -446,10 +460,11 @@ impl Storage {
         slot: Element<Label>,
         rshift: u8,
         vtype: DynSolType,
-    ) {
+    ) -> Rc<RefCell<StorageElement>> {
         let x = self.get(domain, slot, true);
         x.borrow_mut().stype.set_type(vtype);
         x.borrow_mut().rshift = rshift;
+        x
     }
 
     fn load(&mut self, domain: StorageDomain, slot: Element<Label>) -> Rc<RefCell<StorageElement>> {
@@ -467,6 +482,16 @@ impl Storage {
             _ => SlotExpr::Plain(slot.data),
         };
         let (slot_key, canonical_slot, stype) = normalize_slot_expr(&slot_expr);
+        let access = if self.with_evidence {
+            let reason = match self.opcode {
+                _ if is_write => "write",
+                op::SLOAD | op::TLOAD => "read",
+                _ => "packed field read",
+            };
+            vec![Evidence::new(self.pc, self.opcode, reason)]
+        } else {
+            Vec::new()
+        };
 
         let v = Rc::new(RefCell::new(StorageElement {
             domain,
@@ -476,6 +501,7 @@ impl Storage {
             stype,
             rshift: 0,
             is_write,
+            access,
             last_and: None,
             last_or2: None,
         }));
@@ -716,13 +742,15 @@ fn analyze(
                 StorageDomain::Transient
             };
 
+            let mut merged_access = Vec::new();
             if let Some(Label::Loaded(ref sl)) = value.label
                 && sl.borrow().domain == domain
             {
                 st.remove(sl);
+                merged_access = sl.borrow().access.clone();
             }
 
-            match value.label {
+            let stored = match value.label {
                 Some(Label::Typed(t)) => st.store(domain, slot, 0, t),
                 Some(Label::Loaded(sl)) => {
                     let sbr = sl.borrow();
@@ -744,16 +772,17 @@ fn analyze(
                                     }
                                 }
                             };
-                            st.store(domain, slot, (tv / 8) as u8, dt);
+                            st.store(domain, slot, (tv / 8) as u8, dt)
                         } else {
-                            st.store(domain, slot, 0, sbr.stype.get_internal_type());
+                            st.store(domain, slot, 0, sbr.stype.get_internal_type())
                         }
                     } else {
-                        st.store(domain, slot, 0, sbr.stype.get_internal_type());
+                        st.store(domain, slot, 0, sbr.stype.get_internal_type())
                     }
                 }
                 _ => st.store(domain, slot, 0, DynSolType::Uint(256)),
-            }
+            };
+            stored.borrow_mut().access.extend(merged_access);
         }
 
         StepResult {
@@ -901,36 +930,46 @@ fn analyze_rec(
     st: &mut Storage,
     gas_limit: u32,
     depth: u32,
+    tracer: Tracer,
 ) -> u32 {
     let mut gas_used = 0;
 
     while !vm.stopped {
-        if cfg!(feature = "trace_storage") {
-            println!("{vm:?}\n");
-            println!("storage: {:?}\n", st.loaded);
-        }
+        tracer.step_with_state(&vm, || format!("{:?}", st.loaded));
+        let pc = vm.pc;
         let ret = match vm.step() {
             Ok(v) => v,
-            Err(_e) => {
-                // println!("{}", _e);
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(&mut st.diagnostics, AnalysisPhase::Storage, pc, kind);
                 break;
             }
         };
         gas_used += ret.gas_used;
         if gas_used > gas_limit {
+            record(
+                &mut st.diagnostics,
+                AnalysisPhase::Storage,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
             break;
         }
 
+        st.pc = pc;
+        st.opcode = ret.op;
         match analyze(&mut vm, st, ret) {
-            Err(_) => {
-                // println!("errbrk");
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(&mut st.diagnostics, AnalysisPhase::Storage, pc, kind);
                 break;
             }
             Ok(Some(other_pc)) => {
                 if depth < 8 && other_pc < vm.code.len() {
                     let mut cloned = vm.fork();
                     cloned.pc = other_pc;
-                    gas_used += analyze_rec(cloned, st, (gas_limit - gas_used) / 2, depth + 1);
+                    gas_used +=
+                        analyze_rec(cloned, st, (gas_limit - gas_used) / 2, depth + 1, tracer);
                 }
             }
             Ok(None) => {}
@@ -946,34 +985,35 @@ fn analyze_one_function(
     arguments: &[DynSolType],
     is_fallback: bool,
     gas_limit: u32,
-) -> SlotHashMap {
-    if cfg!(feature = "trace_storage") {
-        println!(
-            "analyze selector {}\n",
-            alloy_primitives::hex::encode(selector)
-        );
-    }
-
+    with_evidence: bool,
+    tracer: Tracer,
+) -> (Vec<SlotAccess>, Vec<Diagnostic>) {
     let calldata = CallDataImpl::<Label>::new(selector, arguments);
     let mut vm = Vm::new(code, &calldata);
 
-    let mut st = Storage::default();
+    let mut st = Storage {
+        with_evidence,
+        ..Storage::default()
+    };
     let mut gas_used = 0;
 
     if !is_fallback {
-        if let Some(g) = execute_until_function_start(&mut vm, gas_limit) {
-            gas_used += g;
-        } else {
-            return st.loaded;
+        match execute_until_function_start(&mut vm, gas_limit) {
+            Ok(g) => gas_used += g,
+            Err(e) => {
+                e.record(&mut st.diagnostics, AnalysisPhase::Storage);
+                return (slot_accesses(st.loaded), st.diagnostics);
+            }
         }
     }
 
     #[allow(unused_assignments)]
     if gas_used < gas_limit {
-        gas_used += analyze_rec(vm, &mut st, gas_limit - gas_used, 0);
+        gas_used += analyze_rec(vm, &mut st, gas_limit - gas_used, 0, tracer);
     }
 
-    st.loaded
+    let loaded = st
+        .loaded
         .into_iter()
         .map(|(k, v)| {
             // Filter out impossible packed entries: full-slot/container types cannot start mid-slot.
@@ -1012,10 +1052,40 @@ fn analyze_one_function(
                 },
             )
         })
+        .collect();
+    (slot_accesses(loaded), st.diagnostics)
+}
+
+/// Storage access with a resolved slot, detached from the VM labels
+#[derive(Clone)]
+struct SlotAccess {
+    domain: StorageDomain,
+    slot: Slot,
+    stype: StorageType,
+    rshift: u8,
+    is_write: bool,
+    evidence: Vec<Evidence>,
+}
+
+fn slot_accesses(loaded: SlotHashMap) -> Vec<SlotAccess> {
+    loaded
+        .into_values()
+        .flatten()
+        .filter_map(|element| {
+            let value = element.borrow();
+            Some(SlotAccess {
+                domain: value.domain,
+                slot: value.slot?,
+                stype: value.stype.clone(),
+                rshift: value.rshift,
+                is_write: value.is_write,
+                evidence: value.access.clone(),
+            })
+        })
         .collect()
 }
 
-type SlotRecords = BTreeMap<(Slot, u8), Vec<(Selector, StorageElement)>>;
+type SlotRecords = BTreeMap<(Slot, u8), Vec<(Selector, SlotAccess)>>;
 
 #[derive(Default)]
 struct DomainSlotRecords {
@@ -1026,31 +1096,33 @@ struct DomainSlotRecords {
 pub(crate) struct StorageLayouts {
     pub storage: Vec<StorageRecord>,
     pub transient_storage: Vec<StorageRecord>,
+    /// Diagnostics of the per-selector passes that stopped early
+    pub diagnostics: BTreeMap<Selector, Vec<Diagnostic>>,
+    /// Diagnostics of the fallback pass
+    pub fallback_diagnostics: Vec<Diagnostic>,
 }
 
-fn collect_slot_records(records: &mut DomainSlotRecords, selector: Selector, loaded: SlotHashMap) {
-    for elements in loaded.into_values() {
-        for element in elements {
-            let value = element.borrow();
-            let Some(slot) = value.slot else {
-                continue;
-            };
-            let domain_records = match value.domain {
-                StorageDomain::Persistent => &mut records.persistent,
-                StorageDomain::Transient => &mut records.transient,
-            };
-            domain_records
-                .entry((slot, value.rshift))
-                .or_default()
-                .push((selector, value.clone()));
-        }
+fn collect_slot_records(
+    records: &mut DomainSlotRecords,
+    selector: Selector,
+    accesses: Vec<SlotAccess>,
+) {
+    for access in accesses {
+        let domain_records = match access.domain {
+            StorageDomain::Persistent => &mut records.persistent,
+            StorageDomain::Transient => &mut records.transient,
+        };
+        domain_records
+            .entry((access.slot, access.rshift))
+            .or_default()
+            .push((selector, access));
     }
 }
 
 fn finalize_slot_records(
     slot_records: SlotRecords,
     fallback_selector: Selector,
-    domain_name: &str,
+    with_evidence: bool,
 ) -> Vec<StorageRecord> {
     let mut normalized_slot_records = BTreeMap::new();
     let mut grouped_by_slot: BTreeMap<Slot, Vec<_>> = BTreeMap::new();
@@ -1089,6 +1161,7 @@ fn finalize_slot_records(
     for ((slot, offset), entries) in normalized_slot_records {
         let mut reads = BTreeSet::new();
         let mut writes = BTreeSet::new();
+        let mut evidence = BTreeSet::new();
         let mut best_type = StorageType::Base(DynSolType::Uint(256));
         let mut best_score = best_type.get_score();
 
@@ -1101,6 +1174,8 @@ fn finalize_slot_records(
                 }
             }
 
+            evidence.extend(element.evidence);
+
             let score = element.stype.get_score();
             if score > best_score {
                 best_type = element.stype;
@@ -1114,44 +1189,23 @@ fn finalize_slot_records(
             r#type: format!("{best_type:?}"),
             reads: reads.into_iter().collect(),
             writes: writes.into_iter().collect(),
+            evidence: with_evidence.then(|| evidence.into_iter().collect()),
         });
     }
 
-    if cfg!(feature = "trace_storage") {
-        println!("{domain_name} storage:");
-        for record in &records {
-            println!(
-                "slot {} off {}",
-                alloy_primitives::hex::encode(record.slot),
-                record.offset
-            );
-            println!(" type: {}", record.r#type);
-            println!(
-                " reads: {:?}",
-                record
-                    .reads
-                    .iter()
-                    .map(alloy_primitives::hex::encode)
-                    .collect::<Vec<_>>()
-            );
-            println!(
-                " writes: {:?}",
-                record
-                    .writes
-                    .iter()
-                    .map(alloy_primitives::hex::encode)
-                    .collect::<Vec<_>>()
-            );
-        }
-    }
-
     records
 }
 
-pub(crate) fn contract_storage<I, D>(code: &[u8], functions: I, gas_limit: u32) -> StorageLayouts
+pub(crate) fn contract_storage<I, D>(
+    code: &[u8],
+    functions: I,
+    gas_limit: u32,
+    with_evidence: bool,
+    tracer: Tracer,
+) -> StorageLayouts
 where
     I: IntoIterator<Item = (Selector, usize, D)>,
-    D: AsRef<[DynSolType]>,
+    D: AsRef<[DynSolType]> + Sync,
 {
     let real_gas_limit = if gas_limit == 0 {
         1e6 as u32
@@ -1169,21 +1223,45 @@ where
         fallback_selector = val.to_be_bytes();
     }
 
-    for &(selector, _, ref arguments) in &functions {
-        let loaded =
-            analyze_one_function(code, selector, arguments.as_ref(), false, real_gas_limit);
-        collect_slot_records(&mut slot_records, selector, loaded);
+    let analyzed = map_ordered(&functions, |(selector, _, arguments)| {
+        analyze_one_function(
+            code,
+            *selector,
+            arguments.as_ref(),
+            false,
+            real_gas_limit,
+            with_evidence,
+            tracer.with_selector(*selector),
+        )
+    });
+
+    let mut diagnostics = BTreeMap::new();
+    for ((selector, _, _), (accesses, fn_diagnostics)) in functions.iter().zip(analyzed) {
+        if !fn_diagnostics.is_empty() {
+            diagnostics.insert(*selector, fn_diagnostics);
+        }
+        collect_slot_records(&mut slot_records, *selector, accesses);
     }
 
-    let fallback = analyze_one_function(code, fallback_selector, &[], true, real_gas_limit);
+    let (fallback, fallback_diagnostics) = analyze_one_function(
+        code,
+        fallback_selector,
+        &[],
+        true,
+        real_gas_limit,
+        with_evidence,
+        tracer,
+    );
     collect_slot_records(&mut slot_records, fallback_selector, fallback);
 
     StorageLayouts {
-        storage: finalize_slot_records(slot_records.persistent, fallback_selector, "persistent"),
+        storage: finalize_slot_records(slot_records.persistent, fallback_selector, with_evidence),
         transient_storage: finalize_slot_records(
             slot_records.transient,
             fallback_selector,
-            "transient",
+            with_evidence,
         ),
+        diagnostics,
+        fallback_diagnostics,
     }
 }
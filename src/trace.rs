@@ -0,0 +1,291 @@
+//! Step-by-step records of the abstract VM runs behind the analysis passes, for debugging
+//! misclassified bytecode without rebuilding the library.
+
+use crate::{
+    AnalysisPhase, Selector,
+    evm::{op, vm::Vm},
+};
+use std::{collections::BTreeSet, fmt, sync::Mutex};
+
+/// Receives the steps executed by the analysis passes, see [`crate::ContractInfoArgs::with_trace`]
+///
+/// Steps are recorded before the opcode is executed. With the `parallel` feature, per-function
+/// passes record from several threads at once.
+pub trait TraceSink: Sync {
+    /// Returns whether steps of `phase` should be recorded; all phases are by default
+    fn enabled(&self, phase: AnalysisPhase) -> bool {
+        let _ = phase;
+        true
+    }
+
+    /// Receives one step
+    fn record(&self, step: TraceStep);
+}
+
+/// A stack element or memory chunk of the abstract VM
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TraceValue {
+    /// Memory offset of the chunk; always `0` for stack elements
+    pub offset: u32,
+
+    /// Concrete value the analysis assumed
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::bytes"))]
+    pub data: Vec<u8>,
+
+    /// Pass-specific label tracking where the value came from, e.g. `Some(CallData)`
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub label: Option<String>,
+}
+
+/// The abstract VM state before executing one opcode
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TraceStep {
+    /// Analysis pass running the VM
+    pub phase: AnalysisPhase,
+
+    /// Function being analyzed, `None` for contract-wide runs
+    #[cfg_attr(
+        feature = "serde",
+        serde(
+            serialize_with = "crate::serialize::opt_selector",
+            skip_serializing_if = "Option::is_none"
+        )
+    )]
+    pub selector: Option<Selector>,
+
+    /// Bytecode offset of the opcode
+    pub pc: usize,
+
+    /// The opcode byte
+    #[cfg_attr(feature = "serde", serde(serialize_with = "crate::serialize::opcode"))]
+    pub opcode: op::OpCode,
+
+    /// Stack elements, bottom to top
+    pub stack: Vec<TraceValue>,
+
+    /// Memory chunks written so far, in write order
+    pub memory: Vec<TraceValue>,
+
+    /// Pass-specific analysis state, e.g. the selectors found so far
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub state: Option<String>,
+}
+
+/// A [`TraceSink`] collecting steps in memory
+///
+/// ```
+/// use evmole::{AnalysisPhase, ContractInfoArgs, TraceRecorder, contract_info};
+/// use alloy_primitives::hex;
+///
+/// let code = hex::decode("6080604052348015600e575f80fd5b50600436106030575f3560e01c80632125b65b146034575b5f80fd5b00").unwrap();
+/// let recorder = TraceRecorder::with_phases([AnalysisPhase::Selectors]);
+/// contract_info(ContractInfoArgs::new(&code).with_selectors().with_trace(&recorder));
+///
+/// let steps = recorder.into_steps();
+/// assert!(steps.iter().all(|s| s.phase == AnalysisPhase::Selectors));
+/// assert_eq!(steps[0].pc, 0);
+/// ```
+#[derive(Default)]
+pub struct TraceRecorder {
+    phases: Option<BTreeSet<AnalysisPhase>>,
+    steps: Mutex<Vec<TraceStep>>,
+}
+
+impl TraceRecorder {
+    /// Creates a recorder for steps of all phases
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a recorder for steps of `phases` only
+    pub fn with_phases(phases: impl IntoIterator<Item = AnalysisPhase>) -> Self {
+        Self {
+            phases: Some(phases.into_iter().collect()),
+            ..Default::default()
+        }
+    }
+
+    /// Returns the recorded steps in recording order
+    pub fn into_steps(self) -> Vec<TraceStep> {
+        self.steps.into_inner().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl TraceSink for TraceRecorder {
+    fn enabled(&self, phase: AnalysisPhase) -> bool {
+        self.phases.as_ref().is_none_or(|p| p.contains(&phase))
+    }
+
+    fn record(&self, step: TraceStep) {
+        self.steps
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(step);
+    }
+}
+
+/// Handle passed down to the VM loops of a pass, recording into the sink if it enabled the pass
+#[derive(Clone, Copy)]
+pub(crate) struct Tracer<'a> {
+    sink: Option<&'a dyn TraceSink>,
+    phase: AnalysisPhase,
+    selector: Option<Selector>,
+}
+
+impl<'a> Tracer<'a> {
+    pub(crate) fn new(sink: Option<&'a dyn TraceSink>, phase: AnalysisPhase) -> Self {
+        Self {
+            sink: sink.filter(|s| s.enabled(phase)),
+            phase,
+            selector: None,
+        }
+    }
+
+    /// A tracer recording nothing, for public entry points taking no sink
+    pub(crate) const fn disabled(phase: AnalysisPhase) -> Self {
+        Self {
+            sink: None,
+            phase,
+            selector: None,
+        }
+    }
+
+    pub(crate) const fn phase(&self) -> AnalysisPhase {
+        self.phase
+    }
+
+    pub(crate) const fn with_selector(mut self, selector: Selector) -> Self {
+        self.selector = Some(selector);
+        self
+    }
+
+    /// Records the state of `vm` before its next step
+    pub(crate) fn step<T: fmt::Debug, U>(&self, vm: &Vm<T, U>) {
+        self.step_with_state(vm, String::new);
+    }
+
+    /// Like [`Tracer::step`], also recording the analysis state built by `state`
+    pub(crate) fn step_with_state<T: fmt::Debug, U>(
+        &self,
+        vm: &Vm<T, U>,
+        state: impl FnOnce() -> String,
+    ) {
+        let Some(sink) = self.sink else {
+            return;
+        };
+        let label = |l: &Option<T>| l.as_ref().map(|v| format!("{v:?}"));
+        let state = state();
+        sink.record(TraceStep {
+            phase: self.phase,
+            selector: self.selector,
+            pc: vm.pc,
+            opcode: vm.code.get(vm.pc).copied().unwrap_or(op::STOP),
+            stack: vm
+                .stack
+                .data
+                .iter()
+                .map(|el| TraceValue {
+                    offset: 0,
+                    data: el.data.to_vec(),
+                    label: label(&el.label),
+                })
+                .collect(),
+            memory: vm
+                .memory
+                .data
+                .iter()
+                .map(|(offset, chunk)| TraceValue {
+                    offset: *offset,
+                    data: chunk.data.clone(),
+                    label: label(&chunk.label),
+                })
+                .collect(),
+            state: (!state.is_empty()).then_some(state),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ContractInfoArgs, contract_info};
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_recorder() {
+        // 0x11223344(uint8)
+        let code = hex::decode(
+            "60003560e01c63112233441460105700\
+             5b60043560ff16600055",
+        )
+        .unwrap();
+        let recorder = TraceRecorder::with_phases([AnalysisPhase::Arguments]);
+        let info = contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_trace(&recorder),
+        );
+        assert_eq!(
+            info.functions.unwrap()[0].arguments,
+            Some(vec![crate::DynSolType::Uint(8)])
+        );
+
+        let steps = recorder.into_steps();
+        assert!(
+            steps.iter().all(|s| s.phase == AnalysisPhase::Arguments
+                && s.selector == Some([0x11, 0x22, 0x33, 0x44]))
+        );
+
+        let and = steps
+            .iter()
+            .find(|s| s.opcode == op::AND)
+            .expect("AND is executed");
+        assert_eq!(and.pc, 0x16);
+        let top = and.stack.last().unwrap();
+        assert_eq!(top.data[31], 0xff);
+        let arg = &and.stack[and.stack.len() - 2];
+        assert_eq!(
+            arg.label.as_deref(),
+            Some("Arg(Val { offset: 0, path: [], add_val: 0, and_mask: None })")
+        );
+        assert!(and.state.is_some());
+
+        let recorder = TraceRecorder::with_phases([AnalysisPhase::Storage]);
+        contract_info(
+            ContractInfoArgs::new(&code)
+                .with_arguments()
+                .with_trace(&recorder),
+        );
+        assert!(recorder.into_steps().is_empty());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_step_json() {
+        let step = TraceStep {
+            phase: AnalysisPhase::Selectors,
+            selector: None,
+            pc: 0x10,
+            opcode: op::JUMPDEST,
+            stack: vec![TraceValue {
+                offset: 0,
+                data: vec![0xab],
+                label: Some("CallData".into()),
+            }],
+            memory: Vec::new(),
+            state: None,
+        };
+        assert_eq!(
+            serde_json::to_value(step).unwrap(),
+            serde_json::json!({
+                "phase": "selectors",
+                "pc": 16,
+                "opcode": "JUMPDEST",
+                "stack": [{ "offset": 0, "data": "ab", "label": "CallData" }],
+                "memory": [],
+            })
+        );
+    }
+}
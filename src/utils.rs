@@ -1,6 +1,13 @@
 use crate::{
-    DynSolType,
-    evm::{U256, VAL_0_B, VAL_1, VAL_1_B, calldata::CallData, op, vm::Vm},
+    AnalysisPhase, DynSolType,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{
+        U256, VAL_0_B, VAL_1, VAL_1_B,
+        calldata::CallData,
+        op,
+        vm::{StepResult, Vm},
+    },
+    trace::Tracer,
 };
 
 macro_rules! match_first_two {
@@ -21,8 +28,29 @@ macro_rules! elabel {
 pub(crate) use elabel;
 pub(crate) use match_first_two;
 
+/// Reason why [`execute_until_function_start`] did not reach the function body
+#[derive(Debug, PartialEq, Eq)]
+pub enum FunctionStartError {
+    /// Execution stopped before the selector was dispatched
+    NotFound,
+    /// Execution was aborted at `pc` during dispatch
+    Aborted(usize, DiagnosticKind),
+}
+
+impl FunctionStartError {
+    /// Records the abort reason, if any, as a diagnostic of `phase`
+    pub fn record(self, diagnostics: &mut Vec<Diagnostic>, phase: AnalysisPhase) {
+        if let Self::Aborted(pc, kind) = self {
+            record(diagnostics, phase, pc, kind);
+        }
+    }
+}
+
 /// Executes the EVM until it reaches the start of a function identified by its selector
-pub fn execute_until_function_start<T, U>(vm: &mut Vm<T, U>, gas_limit: u32) -> Option<u32>
+pub fn execute_until_function_start<T, U>(
+    vm: &mut Vm<T, U>,
+    gas_limit: u32,
+) -> Result<u32, FunctionStartError>
 where
     T: Clone + std::fmt::Debug + std::cmp::Eq,
     U: CallData<T>,
@@ -30,21 +58,24 @@ where
     let mut gas_used = 0;
     let mut found = false;
     while !vm.stopped {
+        let pc = vm.pc;
         let ret = match vm.step() {
             Ok(v) => v,
-            Err(_e) => {
-                // println!("{}", _e);
-                return None;
+            Err(e) => {
+                return Err(FunctionStartError::Aborted(
+                    pc,
+                    DiagnosticKind::from_error(e.as_ref()),
+                ));
             }
         };
 
         gas_used += ret.gas_used;
         if gas_used > gas_limit {
-            return None;
+            return Err(FunctionStartError::Aborted(pc, DiagnosticKind::GasLimit));
         }
 
         if found && ret.op == op::JUMPI {
-            return Some(gas_used);
+            return Ok(gas_used);
         }
 
         // Look for selector comparison operations
@@ -66,7 +97,7 @@ where
             }
         }
     }
-    None
+    Err(FunctionStartError::NotFound)
 }
 
 /// Determines the Solidity type based on a bit mask pattern
@@ -105,6 +136,104 @@ pub fn and_mask_to_type(mask: U256) -> Option<DynSolType> {
     None
 }
 
+struct Path<'a, T, U, S> {
+    vm: Vm<'a, T, U>,
+    state: S,
+    depth: u32,
+}
+
+/// Runs `vm` forking on every JUMPI, calling `visit` with the pc and result of each step
+/// until it returns a value
+///
+/// `S` is per-path state, cloned together with the VM on forks. Forks are nested at most
+/// `max_depth` times. Failed steps end their path and are recorded as diagnostics of the
+/// `tracer` phase; exceeding `gas_limit` (shared by all paths) ends the exploration.
+pub(crate) fn explore_paths<'a, T, U, S, R>(
+    vm: Vm<'a, T, U>,
+    gas_limit: u32,
+    max_depth: u32,
+    tracer: Tracer,
+    diagnostics: &mut Vec<Diagnostic>,
+    mut visit: impl FnMut(&mut Vm<'a, T, U>, &mut S, usize, &StepResult<T>) -> Option<R>,
+) -> Option<R>
+where
+    T: Clone + std::fmt::Debug + std::cmp::Eq,
+    U: CallData<T>,
+    S: Clone + Default,
+{
+    let phase = tracer.phase();
+    let mut gas_used = 0;
+    let mut pending = vec![Path {
+        vm,
+        state: S::default(),
+        depth: 0,
+    }];
+
+    while let Some(mut path) = pending.pop() {
+        while !path.vm.stopped {
+            tracer.step(&path.vm);
+            let pc = path.vm.pc;
+            let ret = match path.vm.step() {
+                Ok(v) => v,
+                Err(e) => {
+                    record(
+                        diagnostics,
+                        phase,
+                        pc,
+                        DiagnosticKind::from_error(e.as_ref()),
+                    );
+                    break;
+                }
+            };
+            gas_used += ret.gas_used;
+            if gas_used > gas_limit {
+                record(diagnostics, phase, pc, DiagnosticKind::GasLimit);
+                return None;
+            }
+
+            if ret.op == op::JUMPI {
+                let other_pc = usize::try_from(&ret.args[0]).expect("set to usize in vm.rs");
+                if path.depth < max_depth && other_pc != 0 {
+                    let mut vm = path.vm.fork();
+                    vm.pc = other_pc;
+                    pending.push(Path {
+                        vm,
+                        state: path.state.clone(),
+                        depth: path.depth + 1,
+                    });
+                }
+            }
+
+            if let Some(r) = visit(&mut path.vm, &mut path.state, pc, &ret) {
+                return Some(r);
+            }
+        }
+    }
+    None
+}
+
+/// Maps `items` preserving their order
+///
+/// With the `parallel` feature the calls to `f` are spread across the rayon
+/// thread pool; the result is identical to the sequential one.
+pub(crate) fn map_ordered<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync + Send,
+{
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+        items.par_iter().map(f).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        items.iter().map(f).collect()
+    }
+}
+
 #[allow(dead_code)]
 pub fn log(s: String) {
     #[cfg(feature = "javascript")]
@@ -144,4 +273,11 @@ mod tests {
         let invalid_mask = uint!(0b1010_U256);
         assert_eq!(and_mask_to_type(invalid_mask), None);
     }
+
+    #[test]
+    fn test_map_ordered() {
+        let items: Vec<u32> = (0..1000).collect();
+        let squares = map_ordered(&items, |x| x * x);
+        assert_eq!(squares, items.iter().map(|x| x * x).collect::<Vec<_>>());
+    }
 }
@@ -0,0 +1,166 @@
+//! Self-check of inferred argument types: the function is called with values of these
+//! types, which its ABI decoder must accept.
+
+use crate::{
+    AnalysisPhase, DynSolType, Selector,
+    diagnostics::{Diagnostic, DiagnosticKind, record},
+    evm::{VAL_0_B, calldata::ConcreteCallData, op, vm::Vm},
+    samples::{SampleKind, sample_calldata},
+    utils::execute_until_function_start,
+};
+
+/// Outcome of re-executing a function with calldata built from its inferred arguments
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum ArgumentsValidation {
+    /// The function got past argument decoding
+    Validated,
+    /// The function reverted without data before reading any state, like ABI decoder
+    /// guards on calldata size, offsets and value ranges do
+    Contradicted,
+    /// The run stopped early, see the function diagnostics
+    Inconclusive,
+}
+
+impl ArgumentsValidation {
+    /// Returns the validation outcome as a camelCase string, matching its serialized form.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Validated => "validated",
+            Self::Contradicted => "contradicted",
+            Self::Inconclusive => "inconclusive",
+        }
+    }
+}
+
+/// Opcodes that only the function body executes, ending argument decoding
+fn is_body(opcode: op::OpCode) -> bool {
+    matches!(
+        opcode,
+        op::SLOAD
+            | op::SSTORE
+            | op::TLOAD
+            | op::TSTORE
+            | op::KECCAK256
+            | op::ADDRESS
+            | op::BALANCE
+            | op::SELFBALANCE
+            | op::ORIGIN
+            | op::CALLER
+            | op::TIMESTAMP
+            | op::NUMBER
+            | op::EXTCODESIZE
+            | op::EXTCODECOPY
+            | op::EXTCODEHASH
+            | op::LOG0
+            ..=op::LOG4
+                | op::CREATE
+                | op::CALL
+                | op::CALLCODE
+                | op::DELEGATECALL
+                | op::CREATE2
+                | op::STATICCALL
+    )
+}
+
+/// Calls the function identified by `selector` with boundary values of `arguments`
+///
+/// Largest values of the inferred types fail range checks if a type was inferred wider
+/// than it is, and calldata size and offset checks fail if the layout is wrong. Execution
+/// is followed until the function body starts reading state or the call ends.
+pub(crate) fn validate_arguments(
+    code: &[u8],
+    selector: Selector,
+    arguments: &[DynSolType],
+    gas_limit: u32,
+) -> (ArgumentsValidation, Vec<Diagnostic>) {
+    let real_gas_limit = if gas_limit == 0 {
+        1e5 as u32
+    } else {
+        gas_limit
+    };
+    let mut diagnostics = Vec::new();
+
+    let calldata = ConcreteCallData {
+        data: sample_calldata(selector, arguments, SampleKind::Boundary),
+    };
+    let mut vm = Vm::<(), _>::new(code, &calldata);
+    let mut gas_used = match execute_until_function_start(&mut vm, real_gas_limit) {
+        Ok(g) => g,
+        Err(e) => {
+            e.record(&mut diagnostics, AnalysisPhase::ArgumentsValidation);
+            return (ArgumentsValidation::Inconclusive, diagnostics);
+        }
+    };
+
+    while !vm.stopped {
+        let pc = vm.pc;
+        let ret = match vm.step() {
+            Ok(v) => v,
+            Err(e) => {
+                let kind = DiagnosticKind::from_error(e.as_ref());
+                record(
+                    &mut diagnostics,
+                    AnalysisPhase::ArgumentsValidation,
+                    pc,
+                    kind,
+                );
+                break;
+            }
+        };
+        gas_used += ret.gas_used;
+        if gas_used > real_gas_limit {
+            record(
+                &mut diagnostics,
+                AnalysisPhase::ArgumentsValidation,
+                pc,
+                DiagnosticKind::GasLimit,
+            );
+            break;
+        }
+
+        match ret.op {
+            op::REVERT if ret.args[1].data == VAL_0_B => {
+                return (ArgumentsValidation::Contradicted, diagnostics);
+            }
+            op::STOP | op::RETURN | op::REVERT | op::SELFDESTRUCT => {
+                return (ArgumentsValidation::Validated, diagnostics);
+            }
+            opcode if is_body(opcode) => {
+                return (ArgumentsValidation::Validated, diagnostics);
+            }
+            _ => {}
+        }
+    }
+    (ArgumentsValidation::Inconclusive, diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::hex;
+
+    #[test]
+    fn test_range_and_size_checks() {
+        // 0x11223344(uint8): reverts if calldatasize < 36 or the argument exceeds 0xff,
+        // then stores it
+        let code = hex::decode(
+            "60003560e01c63112233441460105700\
+             5b602436106023576004358060ff1015602757\
+             5b5f5ffd5b5f5500",
+        )
+        .unwrap();
+        let selector = [0x11, 0x22, 0x33, 0x44];
+
+        let (v, diagnostics) = validate_arguments(&code, selector, &[DynSolType::Uint(8)], 0);
+        assert_eq!(v, ArgumentsValidation::Validated);
+        assert!(diagnostics.is_empty());
+
+        let (v, _) = validate_arguments(&code, selector, &[DynSolType::Uint(256)], 0);
+        assert_eq!(v, ArgumentsValidation::Contradicted);
+
+        let (v, _) = validate_arguments(&code, selector, &[], 0);
+        assert_eq!(v, ArgumentsValidation::Contradicted);
+    }
+}